@@ -0,0 +1,349 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    Codes, CodesBuilder, FileEncoder, FrequencyMap, HuffmanArchiver, ShannonFanoEncoder, Utf8Archiver,
+    utf8::{ESCAPE_BASE, to_symbols},
+};
+
+/// Энтропия файла разных порядков в битах на символ.
+#[derive(Debug, Clone, Serialize)]
+pub struct EntropyReport {
+    /// Безусловная энтропия `H(X)`.
+    pub order0: f64,
+    /// Условная энтропия при известном предыдущем байте `H(Xn | Xn-1)`.
+    pub order1: f64,
+    /// Условная энтропия при известных двух предыдущих байтах `H(Xn | Xn-2 Xn-1)`.
+    pub order2: f64,
+}
+
+/// Показатели одного способа кодирования на конкретном файле.
+#[derive(Debug, Clone, Serialize)]
+pub struct CoderReport {
+    pub name: String,
+    pub mean_code_length: f64,
+    pub relative_efficiency_ratio: f64,
+    pub statistical_compression_ratio: f64,
+    /// Размер закодированных данных без таблицы кодов, предсказанный по длинам кодов, в байтах.
+    pub predicted_size: usize,
+    /// Действительный размер архива вместе с таблицей кодов и заголовком, в байтах.
+    pub archive_size: usize,
+}
+
+/// Показатели кодирования Хаффмана по кодовым точкам UTF-8 (см. [`Utf8Archiver`]).
+#[derive(Debug, Clone, Serialize)]
+pub struct Utf8Report {
+    /// Количество символов: кодовых точек и escape-символов.
+    pub symbols: usize,
+    /// Количество различных символов.
+    pub unique_symbols: usize,
+    /// Количество байтов недопустимых последовательностей UTF-8, закодированных escape-символами.
+    pub escaped_bytes: usize,
+    /// Энтропия в битах на символ.
+    pub entropy: f64,
+    pub mean_code_length: f64,
+    /// Размер закодированных данных без таблицы кодов, предсказанный по длинам кодов, в байтах.
+    pub predicted_size: usize,
+    /// Действительный размер архива вместе с таблицей кодов и заголовком, в байтах.
+    pub archive_size: usize,
+    /// Выигрыш относительно побайтового архива Хаффмана `1 - utf8_archive / byte_archive`.
+    /// Отрицательное значение означает, что побайтовое кодирование выгоднее.
+    pub gain: f64,
+}
+
+/// Результат анализа файла: энтропия и сравнение кодировщиков с теоретической границей.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileAnalysis {
+    pub path: String,
+    pub size: usize,
+    pub unique_bytes: usize,
+    pub entropy: EntropyReport,
+    /// Теоретическая граница размера для посимвольного кодирования `H0 * size / 8`, в байтах.
+    pub entropy_bound: f64,
+    pub coders: Vec<CoderReport>,
+    /// Кодирование по кодовым точкам UTF-8. Отсутствует для пустого файла.
+    pub utf8: Option<Utf8Report>,
+}
+
+impl FileAnalysis {
+    /// Заголовок CSV, соответствующий строкам [`FileAnalysis::to_csv_rows`].
+    pub const CSV_HEADER: &str = "path,size,unique_bytes,entropy_order0,entropy_order1,entropy_order2,\
+        entropy_bound,coder,mean_code_length,relative_efficiency_ratio,statistical_compression_ratio,\
+        predicted_size,archive_size,utf8_archive_size,utf8_gain";
+
+    pub fn analyze_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+        Self::analyze(path.display().to_string(), &bytes)
+    }
+
+    pub fn analyze(path: String, bytes: &[u8]) -> Result<Self> {
+        let mut freq_map = FrequencyMap::new();
+        freq_map.consume(bytes);
+
+        let entropy = EntropyReport {
+            order0: freq_map.entropy(),
+            order1: conditional_entropy(bytes, 1),
+            order2: conditional_entropy(bytes, 2),
+        };
+
+        // Для пустого файла коды не строятся
+        let coders = if bytes.is_empty() {
+            Vec::new()
+        } else {
+            let probabilities = freq_map.build();
+            vec![
+                Self::analyze_coder(
+                    "Shannon-Fano",
+                    ShannonFanoEncoder::build_optimal_codes_from_hashmap(probabilities.clone()),
+                    &freq_map,
+                    bytes,
+                )?,
+                Self::analyze_coder(
+                    "Huffman",
+                    HuffmanArchiver::build_optimal_codes_from_hashmap(probabilities),
+                    &freq_map,
+                    bytes,
+                )?,
+            ]
+        };
+
+        let utf8 = match coders.iter().find(|coder| coder.name == "Huffman") {
+            Some(huffman) => Some(Self::analyze_utf8(bytes, huffman.archive_size)?),
+            None => None,
+        };
+
+        Ok(Self {
+            path,
+            size: bytes.len(),
+            unique_bytes: freq_map.counts().len(),
+            entropy_bound: entropy.order0 * bytes.len() as f64 / 8.0,
+            entropy,
+            coders,
+            utf8,
+        })
+    }
+
+    fn analyze_utf8(bytes: &[u8], byte_archive_size: usize) -> Result<Utf8Report> {
+        let symbols = to_symbols(bytes);
+        let mut counts = HashMap::new();
+        for &symbol in &symbols {
+            *counts.entry(symbol).or_insert(0) += 1;
+        }
+
+        let archiver = Utf8Archiver::from_counts(&counts);
+        let total = symbols.len() as f64;
+        let predicted_bits: usize = counts
+            .iter()
+            .map(|(symbol, count)| count * archiver.word_code()[symbol].len())
+            .sum();
+        let entropy = counts
+            .values()
+            .map(|&count| {
+                let p = count as f64 / total;
+                -p * p.log2()
+            })
+            .sum::<f64>()
+            .max(0.0);
+
+        let mut archive = Vec::new();
+        archiver
+            .encode_to_writer(bytes, &mut archive)
+            .context("Failed to archive with UTF-8 codes")?;
+
+        Ok(Utf8Report {
+            symbols: symbols.len(),
+            unique_symbols: counts.len(),
+            escaped_bytes: symbols.iter().filter(|&&symbol| symbol >= ESCAPE_BASE).count(),
+            entropy,
+            mean_code_length: predicted_bits as f64 / total,
+            predicted_size: predicted_bits.div_ceil(8),
+            archive_size: archive.len(),
+            gain: 1.0 - archive.len() as f64 / byte_archive_size as f64,
+        })
+    }
+
+    fn analyze_coder(name: &str, codes: Codes, freq_map: &FrequencyMap, bytes: &[u8]) -> Result<CoderReport> {
+        let word_code: HashMap<u8, String> = (&codes).into();
+        let predicted_bits: usize = freq_map
+            .counts()
+            .iter()
+            .map(|(word, count)| count * word_code[word].len())
+            .sum();
+
+        let mean_code_length = codes.mean_code_length();
+        let relative_efficiency_ratio = codes.relative_efficiency_ratio();
+        let statistical_compression_ratio = codes.statistical_compression_ratio();
+
+        // Архивируем в память тем же форматом, что и `HuffmanArchiver::archive`
+        let mut archive = Vec::new();
+        HuffmanArchiver::from_codes(codes)
+            .encode_to_writer(bytes, &mut archive)
+            .with_context(|| format!("Failed to archive with {} codes", name))?;
+
+        Ok(CoderReport {
+            name: name.to_string(),
+            mean_code_length,
+            relative_efficiency_ratio,
+            statistical_compression_ratio,
+            predicted_size: predicted_bits.div_ceil(8),
+            archive_size: archive.len(),
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize analysis to JSON")
+    }
+
+    /// Строки CSV без заголовка, по одной на каждый кодировщик.
+    pub fn to_csv_rows(&self) -> Vec<String> {
+        let (utf8_archive_size, utf8_gain) = match &self.utf8 {
+            Some(utf8) => (utf8.archive_size.to_string(), utf8.gain.to_string()),
+            None => (String::new(), String::new()),
+        };
+
+        self.coders
+            .iter()
+            .map(|coder| {
+                format!(
+                    "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                    csv_field(&self.path),
+                    self.size,
+                    self.unique_bytes,
+                    self.entropy.order0,
+                    self.entropy.order1,
+                    self.entropy.order2,
+                    self.entropy_bound,
+                    csv_field(&coder.name),
+                    coder.mean_code_length,
+                    coder.relative_efficiency_ratio,
+                    coder.statistical_compression_ratio,
+                    coder.predicted_size,
+                    coder.archive_size,
+                    utf8_archive_size,
+                    utf8_gain,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Вычисляет условную энтропию порядка `order` (не больше 2) в битах на символ:
+///
+/// `H(X | C) = -Σ p(c, x) * log2(p(c, x) / p(c))`
+///
+/// где `C` - контекст из `order` предыдущих байтов. Первые `order` байтов файла без полного
+/// контекста не учитываются.
+pub fn conditional_entropy(bytes: &[u8], order: usize) -> f64 {
+    assert!(order <= 2, "Context order must not exceed 2, actual: {}", order);
+
+    if order == 0 {
+        let mut freq_map = FrequencyMap::new();
+        freq_map.consume(bytes);
+        return freq_map.entropy();
+    }
+
+    if bytes.len() <= order {
+        return 0.0;
+    }
+
+    let mut joint: HashMap<(u16, u8), usize> = HashMap::new();
+    let mut contexts: HashMap<u16, usize> = HashMap::new();
+
+    for window in bytes.windows(order + 1) {
+        let (context, symbol) = window.split_at(order);
+        let context = context.iter().fold(0u16, |acc, &b| (acc << 8) | b as u16);
+
+        *joint.entry((context, symbol[0])).or_insert(0) += 1;
+        *contexts.entry(context).or_insert(0) += 1;
+    }
+
+    let total = (bytes.len() - order) as f64;
+    joint
+        .iter()
+        .map(|((context, _), &count)| {
+            let p_joint = count as f64 / total;
+            let p_conditional = count as f64 / contexts[context] as f64;
+            -p_joint * p_conditional.log2()
+        })
+        .sum::<f64>()
+        .max(0.0)
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditional_entropy() {
+        // Равновероятные символы без зависимости от контекста
+        assert_eq!(conditional_entropy(&[0, 1, 2, 3], 0), 2.0);
+
+        // Чередование полностью определяется предыдущим символом
+        let alternating = [1u8, 2].repeat(100);
+        assert_eq!(conditional_entropy(&alternating, 0), 1.0);
+        assert_eq!(conditional_entropy(&alternating, 1), 0.0);
+        assert_eq!(conditional_entropy(&alternating, 2), 0.0);
+
+        // Последовательность с периодом 3 требует контекст из двух байтов: после 1 идет 1 или 2
+        let period = [1u8, 1, 2].repeat(100);
+        assert!(conditional_entropy(&period, 1) > 0.5);
+        assert_eq!(conditional_entropy(&period, 2), 0.0);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let bytes = b"abracadabra".repeat(10);
+        let analysis = FileAnalysis::analyze("abracadabra".into(), &bytes).unwrap();
+
+        assert_eq!(analysis.size, 110);
+        assert_eq!(analysis.unique_bytes, 5);
+        assert_eq!(analysis.coders.len(), 2);
+
+        for coder in &analysis.coders {
+            // Длина кода не может быть меньше энтропии
+            assert!(coder.mean_code_length >= analysis.entropy.order0);
+            assert!(coder.predicted_size as f64 >= analysis.entropy_bound.floor());
+
+            // Архив содержит таблицу кодов, заголовок и сами данные
+            assert!(coder.archive_size > coder.predicted_size);
+        }
+
+        assert_eq!(analysis.to_csv_rows().len(), 2);
+        assert_eq!(analysis.utf8.as_ref().unwrap().symbols, 110);
+    }
+
+    #[test]
+    fn test_analyze_utf8_gain() {
+        let bytes = "Кодовые точки вместо байтов. ".repeat(40).into_bytes();
+        let analysis = FileAnalysis::analyze("cyrillic".into(), &bytes).unwrap();
+        let utf8 = analysis.utf8.unwrap();
+
+        assert_eq!(utf8.escaped_bytes, 0);
+        assert!(utf8.mean_code_length >= utf8.entropy);
+        assert!(utf8.gain > 0.0, "gain: {}", utf8.gain);
+
+        let mut invalid = bytes.clone();
+        invalid.push(0xFF);
+        let analysis = FileAnalysis::analyze("invalid".into(), &invalid).unwrap();
+        assert_eq!(analysis.utf8.unwrap().escaped_bytes, 1);
+    }
+
+    #[test]
+    fn test_analyze_empty() {
+        let analysis = FileAnalysis::analyze("empty".into(), &[]).unwrap();
+
+        assert_eq!(analysis.entropy.order0, 0.0);
+        assert!(analysis.coders.is_empty());
+        assert!(analysis.utf8.is_none());
+    }
+}
@@ -0,0 +1,399 @@
+//! Сравнение кодеков на воспроизводимом наборе файлов: степень сжатия, скорость и пиковая память.
+
+use anyhow::{Context, Result};
+use pseudorandom::{PRNG, XorShift32, extensions::F64Ext};
+use serde::Serialize;
+use std::{
+    io::Cursor,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    FileDecoder, FileEncoder, FilteredArchiver, FrequencyMap, HuffmanArchiver, RleArchiver, RleHuffmanArchiver,
+    SeekableArchiver, SeekableReader, Utf8Archiver, huffman::MAX_CODE_LENGTH, report::markdown_table,
+};
+
+/// Файл тестового набора.
+#[derive(Debug, Clone)]
+pub struct CorpusFile {
+    pub name: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+/// Генерирует набор файлов по `size` байтов: случайные байты, байты с геометрическим распределением,
+/// повторяющийся текст, исходный код и двоичную таблицу. Одинаковые `seed` дают одинаковые файлы.
+pub fn generate_corpus(seed: u32, size: usize) -> Result<Vec<CorpusFile>> {
+    let mut rng = XorShift32::build(seed)?;
+
+    Ok(vec![
+        CorpusFile {
+            name: "random",
+            bytes: (0..size).map(|_| rng.next() as u8).collect(),
+        },
+        CorpusFile {
+            name: "skewed",
+            bytes: skewed_bytes(&mut rng, size),
+        },
+        CorpusFile {
+            name: "text",
+            bytes: text(&mut rng, size),
+        },
+        CorpusFile {
+            name: "source",
+            bytes: source_code(&mut rng, size),
+        },
+        CorpusFile {
+            name: "table",
+            bytes: binary_table(&mut rng, size),
+        },
+    ])
+}
+
+/// Байты с геометрическим распределением: байт `k` встречается с вероятностью `p * (1 - p)^k`.
+fn skewed_bytes(rng: &mut XorShift32, size: usize) -> Vec<u8> {
+    const P: f64 = 0.2;
+    (0..size)
+        .map(|_| {
+            let u = 1.0 - rng.next_f64();
+            (u.ln() / (1.0 - P).ln()).floor().min(255.0) as u8
+        })
+        .collect()
+}
+
+const WORDS: &[&str] = &[
+    "the",
+    "of",
+    "and",
+    "code",
+    "tree",
+    "symbol",
+    "archive",
+    "length",
+    "probability",
+    "entropy",
+    "bit",
+    "byte",
+    "file",
+    "huffman",
+    "encoder",
+    "decoder",
+    "table",
+    "stream",
+    "block",
+    "frequency",
+];
+
+/// Текст из слов небольшого словаря. Слова в начале словаря встречаются чаще.
+fn text(rng: &mut XorShift32, size: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(size + 16);
+
+    while bytes.len() < size {
+        // Квадрат равномерной величины смещает выбор к началу словаря
+        let index = (rng.next_f64().powi(2) * WORDS.len() as f64) as usize;
+        bytes.extend_from_slice(WORDS[index].as_bytes());
+        bytes.push(if rng.next().is_multiple_of(12) { b'\n' } else { b' ' });
+    }
+
+    bytes.truncate(size);
+    bytes
+}
+
+const SOURCE_LINES: &[&str] = &[
+    "fn {name}(bytes: &[u8]) -> Result<Vec<u8>> {\n",
+    "    let mut {name} = Vec::with_capacity(bytes.len());\n",
+    "    for (i, byte) in bytes.iter().enumerate() {\n",
+    "        {name}.push(byte.wrapping_add(i as u8));\n",
+    "    }\n",
+    "    // Возвращаем {name}\n",
+    "    Ok({name})\n",
+    "}\n\n",
+];
+
+const IDENTIFIERS: &[&str] = &[
+    "encoded", "decoded", "counts", "codes", "buffer", "state", "filtered", "index",
+];
+
+/// Исходный код из шаблонов строк со случайными идентификаторами.
+fn source_code(rng: &mut XorShift32, size: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(size + 64);
+
+    while bytes.len() < size {
+        let name = IDENTIFIERS[rng.next() as usize % IDENTIFIERS.len()];
+        for line in SOURCE_LINES {
+            bytes.extend_from_slice(line.replace("{name}", name).as_bytes());
+        }
+    }
+
+    bytes.truncate(size);
+    bytes
+}
+
+/// Таблица записей: возрастающий идентификатор (u32), медленно меняющееся измерение (f32)
+/// и категория из небольшого набора (u16).
+fn binary_table(rng: &mut XorShift32, size: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(size + 10);
+    let mut id = 0u32;
+    let mut value = 20.0f32;
+
+    while bytes.len() < size {
+        id += 1 + rng.next() % 3;
+        value += (rng.next_f64() as f32 - 0.5) * 0.1;
+        let category = (rng.next() % 5) as u16;
+
+        bytes.extend_from_slice(&id.to_le_bytes());
+        bytes.extend_from_slice(&value.to_le_bytes());
+        bytes.extend_from_slice(&category.to_le_bytes());
+    }
+
+    bytes.truncate(size);
+    bytes
+}
+
+/// Кодек, участвующий в сравнении.
+#[derive(Debug, Clone, Copy)]
+pub struct Codec {
+    pub name: &'static str,
+    encode: fn(&[u8]) -> Result<Vec<u8>>,
+    decode: fn(&[u8]) -> Result<Vec<u8>>,
+}
+
+impl Codec {
+    pub fn encode(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        (self.encode)(bytes)
+    }
+
+    pub fn decode(&self, archive: &[u8]) -> Result<Vec<u8>> {
+        (self.decode)(archive)
+    }
+
+    /// Сжимает и распаковывает файл, проверяя, что данные восстановлены без изменений.
+    pub fn benchmark(&self, file: &CorpusFile, probe: &impl MemoryProbe) -> Result<BenchResult> {
+        probe.reset_peak();
+        let start = Instant::now();
+        let archive = self.encode(&file.bytes)?;
+        let encode_time = start.elapsed();
+        let encode_peak_memory = probe.peak();
+
+        probe.reset_peak();
+        let start = Instant::now();
+        let decoded = self.decode(&archive)?;
+        let decode_time = start.elapsed();
+        let decode_peak_memory = probe.peak();
+
+        anyhow::ensure!(
+            decoded == file.bytes,
+            "Codec {} failed to restore file {}",
+            self.name,
+            file.name
+        );
+
+        Ok(BenchResult {
+            codec: self.name,
+            file: file.name,
+            original_size: file.bytes.len(),
+            archive_size: archive.len(),
+            encode_time,
+            decode_time,
+            peak_memory: encode_peak_memory.zip(decode_peak_memory).map(|(e, d)| e.max(d)),
+        })
+    }
+}
+
+/// Все доступные кодеки.
+pub fn codecs() -> Vec<Codec> {
+    vec![
+        Codec {
+            name: "huffman",
+            encode: |bytes| {
+                // Для сильно неравномерных данных оптимальные коды длиннее, чем можно сохранить в таблице
+                let mut freq_map = FrequencyMap::new();
+                freq_map.consume(bytes);
+                let codes = HuffmanArchiver::build_length_limited_codes(freq_map.counts(), MAX_CODE_LENGTH);
+                archive(HuffmanArchiver::from_codes(codes), bytes)
+            },
+            decode: |archive| HuffmanArchiver::decode_from_reader(&mut Cursor::new(archive)),
+        },
+        Codec {
+            name: "utf8",
+            encode: |bytes| archive(Utf8Archiver::new(bytes), bytes),
+            decode: |archive| Utf8Archiver::decode_from_reader(&mut Cursor::new(archive)),
+        },
+        Codec {
+            name: "filtered",
+            encode: |bytes| archive(FilteredArchiver::auto(bytes), bytes),
+            decode: |archive| FilteredArchiver::decode_from_reader(&mut Cursor::new(archive)),
+        },
+        Codec {
+            name: "rle",
+            encode: |bytes| archive(RleArchiver::new(), bytes),
+            decode: |archive| RleArchiver::decode_from_reader(&mut Cursor::new(archive)),
+        },
+        Codec {
+            name: "rle-huffman",
+            encode: |bytes| archive(RleHuffmanArchiver::new(bytes), bytes),
+            decode: |archive| RleHuffmanArchiver::decode_from_reader(&mut Cursor::new(archive)),
+        },
+        Codec {
+            name: "seekable",
+            encode: |bytes| {
+                let mut archive = Vec::new();
+                SeekableArchiver::new().encode_to_writer(bytes, &mut archive)?;
+                Ok(archive)
+            },
+            decode: |archive| SeekableReader::new(Cursor::new(archive))?.read_all(),
+        },
+    ]
+}
+
+fn archive<E: FileEncoder>(encoder: E, bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut archive = Vec::new();
+    encoder.encode_to_writer(bytes, &mut archive)?;
+    Ok(archive)
+}
+
+/// Источник сведений о выделенной памяти, например глобальный аллокатор со счетчиками.
+pub trait MemoryProbe {
+    /// Начинает новое измерение пиковой памяти.
+    fn reset_peak(&self);
+
+    /// Максимальный объем памяти в байтах, выделенный сверх занятого при вызове
+    /// [`MemoryProbe::reset_peak`], или `None`, если память не измеряется.
+    fn peak(&self) -> Option<usize>;
+}
+
+/// Память не измеряется.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoMemoryProbe;
+
+impl MemoryProbe for NoMemoryProbe {
+    fn reset_peak(&self) {}
+
+    fn peak(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Результат одного кодека на одном файле.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchResult {
+    pub codec: &'static str,
+    pub file: &'static str,
+    pub original_size: usize,
+    pub archive_size: usize,
+    pub encode_time: Duration,
+    pub decode_time: Duration,
+    /// Пиковая память при сжатии или распаковке в байтах.
+    pub peak_memory: Option<usize>,
+}
+
+impl BenchResult {
+    /// Отношение размера архива к размеру исходного файла.
+    pub fn compression_ratio(&self) -> f64 {
+        self.archive_size as f64 / self.original_size as f64
+    }
+
+    /// Скорость сжатия в МБ/с.
+    pub fn encode_throughput(&self) -> f64 {
+        throughput(self.original_size, self.encode_time)
+    }
+
+    /// Скорость распаковки в МБ/с.
+    pub fn decode_throughput(&self) -> f64 {
+        throughput(self.original_size, self.decode_time)
+    }
+}
+
+fn throughput(size: usize, time: Duration) -> f64 {
+    size as f64 / 1e6 / time.as_secs_f64().max(f64::EPSILON)
+}
+
+/// Таблица Markdown с результатами.
+pub fn to_table(results: &[BenchResult]) -> String {
+    let rows = results
+        .iter()
+        .map(|result| {
+            vec![
+                result.codec.to_string(),
+                result.file.to_string(),
+                result.original_size.to_string(),
+                result.archive_size.to_string(),
+                format!("{:.2}%", result.compression_ratio() * 100.0),
+                format!("{:.2}", result.encode_throughput()),
+                format!("{:.2}", result.decode_throughput()),
+                result
+                    .peak_memory
+                    .map_or_else(|| "-".to_string(), |peak| format!("{:.2}", peak as f64 / 1e6)),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    markdown_table(
+        &[
+            "Codec",
+            "File",
+            "Size",
+            "Archive",
+            "Ratio",
+            "Encode MB/s",
+            "Decode MB/s",
+            "Peak MB",
+        ],
+        &rows,
+    )
+}
+
+/// Запускает выбранные кодеки на всех файлах набора.
+pub fn run(corpus: &[CorpusFile], codecs: &[Codec], probe: &impl MemoryProbe) -> Result<Vec<BenchResult>> {
+    let mut results = Vec::with_capacity(corpus.len() * codecs.len());
+    for codec in codecs {
+        for file in corpus {
+            let result = codec
+                .benchmark(file, probe)
+                .with_context(|| format!("Benchmark of {} on {} failed", codec.name, file.name))?;
+            results.push(result);
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_is_reproducible() {
+        let first = generate_corpus(42, 1000).unwrap();
+        let second = generate_corpus(42, 1000).unwrap();
+        let other = generate_corpus(7, 1000).unwrap();
+
+        assert_eq!(first.len(), 5);
+        for ((a, b), c) in first.iter().zip(&second).zip(&other) {
+            assert_eq!(a.bytes.len(), 1000);
+            assert_eq!(a.bytes, b.bytes, "{}", a.name);
+            assert_ne!(a.bytes, c.bytes, "{}", a.name);
+        }
+
+        assert!(generate_corpus(0, 1000).is_err());
+    }
+
+    #[test]
+    fn test_run_all_codecs() {
+        let corpus = generate_corpus(42, 2000).unwrap();
+        let results = run(&corpus, &codecs(), &NoMemoryProbe).unwrap();
+        assert_eq!(results.len(), corpus.len() * codecs().len());
+
+        let ratio = |codec: &str, file: &str| {
+            results
+                .iter()
+                .find(|result| result.codec == codec && result.file == file)
+                .unwrap()
+                .compression_ratio()
+        };
+        // Случайные данные не сжимаются, а текст из небольшого словаря сжимается
+        assert!(ratio("huffman", "random") > 1.0);
+        assert!(ratio("huffman", "text") < 0.8);
+
+        let table = to_table(&results);
+        assert_eq!(table.lines().count(), 2 + results.len());
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use anyhow::Result;
+use clap::Parser;
+
+use archiver::bench::{self, MemoryProbe};
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let corpus = bench::generate_corpus(cli.seed, cli.size)?;
+    let codecs = bench::codecs()
+        .into_iter()
+        .filter(|codec| cli.codecs.is_empty() || cli.codecs.iter().any(|name| name == codec.name))
+        .collect::<Vec<_>>();
+    anyhow::ensure!(!codecs.is_empty(), "No codecs match {:?}", cli.codecs);
+
+    let results = bench::run(&corpus, &codecs, &ALLOCATOR)?;
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        print!("{}", bench::to_table(&results));
+    }
+    Ok(())
+}
+
+/// Сравнивает кодеки на наборе сгенерированных файлов: степень сжатия, скорость и пиковая память
+#[derive(Parser)]
+#[command(version, author = "laroxyss")]
+struct Cli {
+    /// Размер каждого файла набора в байтах
+    #[arg(short, long, default_value_t = 1 << 20)]
+    size: usize,
+
+    /// Семя генератора XorShift32, не равное нулю
+    #[arg(long, default_value_t = 42)]
+    seed: u32,
+
+    /// Запускаемые кодеки: huffman, utf8, filtered, rle, rle-huffman, seekable. По умолчанию все
+    #[arg(short, long = "codec")]
+    codecs: Vec<String>,
+
+    /// Вывести результаты в JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Аллокатор, считающий занятую память и ее максимум.
+struct CountingAllocator {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+    baseline: AtomicUsize,
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+    baseline: AtomicUsize::new(0),
+};
+
+impl CountingAllocator {
+    fn add(&self, size: usize) {
+        let current = self.current.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn sub(&self, size: usize) {
+        self.current.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            self.add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        self.sub(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            self.sub(layout.size());
+            self.add(new_size);
+        }
+        new_ptr
+    }
+}
+
+impl MemoryProbe for CountingAllocator {
+    fn reset_peak(&self) {
+        let current = self.current.load(Ordering::Relaxed);
+        self.baseline.store(current, Ordering::Relaxed);
+        self.peak.store(current, Ordering::Relaxed);
+    }
+
+    fn peak(&self) -> Option<usize> {
+        let peak = self.peak.load(Ordering::Relaxed);
+        Some(peak.saturating_sub(self.baseline.load(Ordering::Relaxed)))
+    }
+}
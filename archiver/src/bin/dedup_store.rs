@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use humansize::{DECIMAL, format_size};
+
+use archiver::dedup::{ChunkStore, Chunker};
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let chunker = Chunker::new().try_with_sizes(cli.min_size, cli.avg_size, cli.max_size)?;
+    let store = ChunkStore::open(&cli.store)?.with_chunker(chunker);
+
+    match cli.command {
+        Command::Add { files } => {
+            for file in files {
+                let manifest = store.add_file(&file)?;
+                println!(
+                    "{}: {}, {} chunks",
+                    manifest.name,
+                    format_size(manifest.size, DECIMAL),
+                    manifest.chunks.len()
+                );
+            }
+        }
+        Command::Restore { name, destination } => store.restore_file(&name, &destination)?,
+        Command::List => {
+            for name in store.names()? {
+                println!("{}", name);
+            }
+        }
+        Command::Stats => {
+            let stats = store.stats()?;
+            println!("Files: {}", stats.files);
+            println!("Logical size: {}", format_size(stats.logical_size, DECIMAL));
+            println!("Chunks: {} ({} unique)", stats.chunk_refs, stats.unique_chunks);
+            println!("Unique size: {}", format_size(stats.unique_size, DECIMAL));
+            println!("Stored size: {}", format_size(stats.stored_size, DECIMAL));
+            println!("Deduplication ratio: {:.3}", stats.dedup_ratio());
+            println!("Total compression ratio: {:.3}", stats.compression_ratio());
+        }
+        Command::Gc => println!("Removed {} chunks", store.collect_garbage()?),
+    }
+    Ok(())
+}
+
+/// Хранилище с дедупликацией: файлы делятся на фрагменты по содержимому, уникальные фрагменты сжимаются один раз
+#[derive(Parser)]
+#[command(version, author = "laroxyss")]
+struct Cli {
+    /// Каталог хранилища
+    #[arg(short, long)]
+    store: PathBuf,
+
+    /// Минимальный размер фрагмента в байтах
+    #[arg(long, default_value_t = 2 * 1024)]
+    min_size: usize,
+
+    /// Средний размер фрагмента в байтах, степень двойки
+    #[arg(long, default_value_t = 8 * 1024)]
+    avg_size: usize,
+
+    /// Максимальный размер фрагмента в байтах
+    #[arg(long, default_value_t = 64 * 1024)]
+    max_size: usize,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Добавляет файлы под их именами
+    Add {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Восстанавливает файл из хранилища
+    Restore { name: String, destination: PathBuf },
+
+    /// Выводит имена файлов в хранилище
+    List,
+
+    /// Выводит статистику дедупликации
+    Stats,
+
+    /// Удаляет фрагменты, на которые не ссылается ни один файл
+    Gc,
+}
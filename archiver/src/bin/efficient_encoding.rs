@@ -1,18 +1,122 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+
 use archiver::io::{print_codes, read_vec_numbers};
-use archiver::{CodesBuilder, HuffmanArchiver, ShannonFanoEncoder};
+use archiver::report::{self, CodesReport};
+use archiver::{CodeTree, CodesBuilder, HuTuckerEncoder, HuffmanArchiver, ShannonFanoEncoder};
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let probabilities: Vec<f64> = match cli.probabilities {
+        Some(probabilities) => probabilities,
+        None => {
+            let probabilities = read_vec_numbers(
+                "Enter a vector of probabilities for characters appearing in a sequence separated by whitespace:",
+            );
+            print!("\n");
+            probabilities
+        }
+    };
+
+    // Символы нумеруются в порядке ввода, чтобы их можно было различить на деревьях
+    let words = (0..probabilities.len() as u64).collect::<Vec<_>>();
+
+    let shannon_fano_codes = ShannonFanoEncoder::build_optimal_codes(words.clone(), probabilities.clone());
+    let huffman_codes = HuffmanArchiver::build_optimal_codes(words.clone(), probabilities.clone());
+    let hu_tucker_codes = HuTuckerEncoder::build_optimal_codes(words.clone(), probabilities.clone());
+
+    let reports = [
+        CodesReport::new("Shannon-Fano", &shannon_fano_codes),
+        CodesReport::new("Huffman", &huffman_codes),
+        CodesReport::new("Hu-Tucker", &hu_tucker_codes),
+    ];
+    match cli.format {
+        OutputFormat::Text => {
+            print_codes("Shannon-Fano codes", &shannon_fano_codes);
+            print_codes("Huffman codes", &huffman_codes);
+            print_codes("Hu-Tucker (alphabetic) codes", &hu_tucker_codes);
+        }
+        OutputFormat::Markdown => print!("{}", report::to_markdown(&reports)),
+        OutputFormat::Csv => print!("{}", report::to_csv(&reports)),
+        OutputFormat::Json => println!("{}", report::to_json(&reports)?),
+    }
+
+    let trees = [
+        (
+            "shannon_fano",
+            ShannonFanoEncoder::build_code_tree(words.clone(), probabilities.clone()),
+        ),
+        (
+            "huffman",
+            HuffmanArchiver::build_code_tree(words.clone(), probabilities.clone()),
+        ),
+        ("hu_tucker", HuTuckerEncoder::build_code_tree(words, probabilities)),
+    ];
+
+    for (name, tree) in trees {
+        let Some(tree) = tree else { continue };
+
+        if cli.tree {
+            println!("{} tree:\n{}", name, tree.to_unicode());
+        }
+        if let Some(dir) = &cli.tree_dir {
+            write_tree(dir, name, &tree)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Сравнение способов кодирования Шеннона-Фано, Хаффмана и алфавитного кода Ху-Таккера
+#[derive(Parser)]
+#[command(version, author = "laroxyss")]
+struct Cli {
+    /// Вероятности символов. Если не указаны, запрашиваются интерактивно
+    #[arg(short, long, num_args = 1.., value_delimiter = ',')]
+    probabilities: Option<Vec<f64>>,
+
+    /// Формат вывода
+    #[arg(short, long, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// Печатает деревья кодов псевдографикой
+    #[arg(long)]
+    tree: bool,
+
+    /// Каталог, в который записываются деревья кодов в форматах Graphviz (.dot) и текстовом (.txt)
+    #[arg(long)]
+    tree_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// Текст для чтения человеком
+    Text,
+
+    /// Таблица Markdown, как в README
+    Markdown,
+
+    /// CSV, одна строка на пару (кодировщик, символ)
+    Csv,
+
+    /// JSON массив с результатами по каждому кодировщику
+    Json,
+}
 
-fn main() {
-    let probabilities = read_vec_numbers(
-        "Enter a vector of probabilities for characters appearing in a sequence separated by whitespace:",
-    );
-    print!("\n");
+fn write_tree(dir: &PathBuf, name: &str, tree: &CodeTree<u64>) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir.display()))?;
 
-    let words = vec![0u8; probabilities.len()];
+    let dot_path = dir.join(format!("{}.dot", name));
+    std::fs::write(&dot_path, tree.to_dot(name))
+        .with_context(|| format!("Failed to write file: {}", dot_path.display()))?;
 
-    let shannon_fano_codes =
-        ShannonFanoEncoder::build_optimal_codes(words.iter().copied().collect(), probabilities.clone());
-    print_codes("Shannon-Fano codes", &shannon_fano_codes);
+    let txt_path = dir.join(format!("{}.txt", name));
+    std::fs::write(&txt_path, tree.to_unicode())
+        .with_context(|| format!("Failed to write file: {}", txt_path.display()))?;
 
-    let huffman_codes = HuffmanArchiver::build_optimal_codes(words, probabilities);
-    print_codes("Huffman codes", &huffman_codes);
+    println!("Trees saved: {}, {}", dot_path.display(), txt_path.display());
+    Ok(())
 }
@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+
+use archiver::analyzer::FileAnalysis;
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let analyses = cli
+        .paths
+        .iter()
+        .map(FileAnalysis::analyze_file)
+        .collect::<Result<Vec<_>>>()?;
+
+    match cli.format {
+        OutputFormat::Text => analyses.iter().for_each(print_analysis),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&analyses)?),
+        OutputFormat::Csv => {
+            println!("{}", FileAnalysis::CSV_HEADER);
+            for row in analyses.iter().flat_map(FileAnalysis::to_csv_rows) {
+                println!("{}", row);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Анализатор энтропии файлов: сравнивает коды Шеннона-Фано и Хаффмана с теоретической границей
+#[derive(Parser)]
+#[command(version, author = "laroxyss")]
+struct Cli {
+    /// Анализируемые файлы
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Формат вывода
+    #[arg(short, long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// Текст для чтения человеком
+    Text,
+
+    /// JSON массив с результатами по каждому файлу
+    Json,
+
+    /// CSV, одна строка на пару (файл, кодировщик)
+    Csv,
+}
+
+fn print_analysis(analysis: &FileAnalysis) {
+    println!("{}:", analysis.path);
+    println!("Size: {} bytes, unique bytes: {}", analysis.size, analysis.unique_bytes);
+    println!(
+        "Entropy: H0 = {:.4}, H1 = {:.4}, H2 = {:.4} bits/symbol",
+        analysis.entropy.order0, analysis.entropy.order1, analysis.entropy.order2
+    );
+    println!("Entropy bound: {:.1} bytes", analysis.entropy_bound);
+
+    for coder in &analysis.coders {
+        println!("{}:", coder.name);
+        println!("  Mean length: {}", coder.mean_code_length);
+        println!("  Relative efficiency ratio: {}", coder.relative_efficiency_ratio);
+        println!(
+            "  Statistical compression ratio: {}",
+            coder.statistical_compression_ratio
+        );
+        println!(
+            "  Predicted size: {} bytes, archive size: {} bytes",
+            coder.predicted_size, coder.archive_size
+        );
+    }
+
+    if let Some(utf8) = &analysis.utf8 {
+        println!("Huffman (UTF-8 code points):");
+        println!(
+            "  Symbols: {}, unique: {}, escaped bytes: {}",
+            utf8.symbols, utf8.unique_symbols, utf8.escaped_bytes
+        );
+        println!(
+            "  Entropy: {:.4} bits/symbol, mean length: {}",
+            utf8.entropy, utf8.mean_code_length
+        );
+        println!(
+            "  Predicted size: {} bytes, archive size: {} bytes",
+            utf8.predicted_size, utf8.archive_size
+        );
+        println!("  Gain over byte-level Huffman: {:.2}%", utf8.gain * 100.0);
+    }
+    println!();
+}
@@ -1,18 +1,132 @@
-use archiver::{FileDecoder, HuffmanArchiver, io::read_filepath};
+use std::{fs::File, ops::Range, path::PathBuf};
 
-use anyhow::Result;
+use archiver::{
+    Dictionary, FileDecoder, FilteredArchiver, HuffmanArchiver, RleArchiver, RleHuffmanArchiver, SeekableReader,
+    Utf8Archiver,
+    io::{ProgressBar, path_to_absolute, read_filepath},
+    progress::CancellationToken,
+};
+
+use anyhow::{Context, Result};
+use clap::Parser;
 
 fn main() -> Result<()> {
-    let target = read_filepath(&"Please provide the location of the file you wish to extract:")?;
-    let destination =
-        read_filepath(&"Please specify the location where you want to save the outcome of the operation:")?;
+    let cli = Cli::parse();
+
+    let target = match cli.target {
+        Some(path) => path_to_absolute(path)?,
+        None => read_filepath(&"Please provide the location of the file you wish to extract:")?,
+    };
+    let destination = match cli.destination {
+        Some(path) => path_to_absolute(path)?,
+        None => read_filepath(&"Please specify the location where you want to save the outcome of the operation:")?,
+    };
+
+    if let Some(dictionary) = cli.dictionary {
+        return Dictionary::load(dictionary)?.extract(&target, &destination);
+    }
+    // Отфильтрованные архивы определяются по сигнатуре в состоянии, флаг `--filtered` необязателен
+    if cli.filtered
+        || FilteredArchiver::is_filtered_archive(&mut File::open(&target).context("Failed to open archive")?)?
+    {
+        return FilteredArchiver::decode_file(&target, &destination);
+    }
+    if cli.utf8 {
+        return Utf8Archiver::decode_file(&target, &destination);
+    }
+    if cli.rle {
+        return RleArchiver::decode_file(&target, &destination);
+    }
+    if cli.rle_huffman {
+        return RleHuffmanArchiver::decode_file(&target, &destination);
+    }
+
+    // Архивы с произвольным доступом определяются по сигнатуре
+    let mut file = File::open(&target).context("Failed to open archive")?;
+    if !SeekableReader::is_seekable_archive(&mut file)? {
+        anyhow::ensure!(cli.range.is_none(), "Byte range requires a seekable archive");
+
+        if !cli.progress {
+            return HuffmanArchiver::decode_file(&target, &destination);
+        }
+
+        // Ctrl+C прерывает распаковку и удаляет недописанный файл
+        let token = CancellationToken::new();
+        let handler_token = token.clone();
+        ctrlc::set_handler(move || handler_token.cancel()).context("Failed to set Ctrl+C handler")?;
+
+        let mut progress_bar = ProgressBar::new();
+        let result = HuffmanArchiver::decode_file_with_progress(&target, &destination, &mut progress_bar, &token);
+        progress_bar.finish();
+        return result;
+    }
+
+    let mut reader = SeekableReader::open(&target)?;
+    let range = cli.range.unwrap_or(0..reader.len());
+    std::fs::write(destination, reader.read_range(range)?).context("Failed to write to file")
+}
+
+/// Разбирает диапазон байтов вида `START..END`.
+fn parse_range(value: &str) -> Result<Range<u64>, String> {
+    let (start, end) = value
+        .split_once("..")
+        .ok_or_else(|| format!("Expected range START..END, got '{}'", value))?;
+
+    let start = start.parse::<u64>().map_err(|err| format!("Invalid start: {}", err))?;
+    let end = end.parse::<u64>().map_err(|err| format!("Invalid end: {}", err))?;
+
+    if start > end {
+        return Err(format!("Range start {} is greater than end {}", start, end));
+    }
+    Ok(start..end)
+}
+
+/// Распаковывает архив, созданный `huffman_encoder`. Пути, не указанные в аргументах, запрашиваются
+/// интерактивно
+#[derive(Parser)]
+#[command(version, author = "laroxyss")]
+struct Cli {
+    /// Распаковываемый архив
+    target: Option<PathBuf>,
+
+    /// Путь к распакованному файлу
+    destination: Option<PathBuf>,
+
+    /// Общий словарь, которым был сжат архив
+    #[arg(short, long)]
+    dictionary: Option<PathBuf>,
+
+    /// Распаковать только диапазон байтов `START..END` архива с произвольным доступом
+    #[arg(short, long, conflicts_with = "dictionary", value_parser = parse_range)]
+    range: Option<Range<u64>>,
+
+    /// Архив создан с фильтром (`huffman_encoder --filter`). Такие архивы распознаются и без флага
+    #[arg(short, long, conflicts_with_all = ["dictionary", "range"])]
+    filtered: bool,
+
+    /// Архив создан по кодовым точкам UTF-8 (`huffman_encoder --utf8`)
+    #[arg(short, long, conflicts_with_all = ["dictionary", "range", "filtered"])]
+    utf8: bool,
+
+    /// Архив создан схемой PackBits (`huffman_encoder --rle`)
+    #[arg(long, conflicts_with_all = ["dictionary", "range", "filtered", "utf8"])]
+    rle: bool,
+
+    /// Архив создан по парам (байт, длина серии) (`huffman_encoder --rle-huffman`)
+    #[arg(long, conflicts_with_all = ["dictionary", "range", "filtered", "utf8", "rle"])]
+    rle_huffman: bool,
 
-    HuffmanArchiver::decode_file(&target, &destination)
+    /// Показывать ход распаковки. Ctrl+C отменяет операцию
+    #[arg(short, long, conflicts_with_all = ["dictionary", "filtered", "utf8", "rle", "rle_huffman"])]
+    progress: bool,
 }
 
 #[cfg(test)]
 mod tests {
-    use archiver::{FileDecoder, HuffmanArchiver, io::path_to_absolute, utils::cmp_files};
+    use super::parse_range;
+    use archiver::{
+        FileDecoder, HuffmanArchiver, SeekableArchiver, SeekableReader, io::path_to_absolute, utils::cmp_files,
+    };
     use std::{fs, path::PathBuf};
 
     #[test]
@@ -42,4 +156,28 @@ mod tests {
         fs::remove_file(&archived).expect("Failed to remove archived file");
         fs::remove_file(&extracted).expect("Failed to remove extracted file");
     }
+
+    #[test]
+    fn test_seekable_range() {
+        let original = path_to_absolute(PathBuf::from("./src/lib.rs")).unwrap();
+        let archived = path_to_absolute(PathBuf::from("decoder_test_seekable.huff")).unwrap();
+        fs::remove_file(&archived).ok();
+
+        SeekableArchiver::new()
+            .with_block_size(256)
+            .archive(&original, &archived)
+            .expect("Failed to archive file");
+
+        let range = parse_range("300..700").unwrap();
+        let mut reader = SeekableReader::open(&archived).unwrap();
+        assert_eq!(
+            reader.read_range(range).unwrap(),
+            fs::read(&original).unwrap()[300..700]
+        );
+
+        assert!(parse_range("700..300").is_err());
+        assert!(parse_range("300").is_err());
+
+        fs::remove_file(&archived).expect("Failed to remove archived file");
+    }
 }
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+
+use archiver::Dictionary;
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let dictionary = Dictionary::train(&cli.samples)?;
+    dictionary.save(&cli.output)?;
+
+    println!(
+        "Dictionary {:016x} trained on {} files saved to {}",
+        dictionary.id(),
+        cli.samples.len(),
+        cli.output.display()
+    );
+    Ok(())
+}
+
+/// Обучает общий словарь кодов Хаффмана на файлах-образцах для сжатия множества маленьких файлов
+#[derive(Parser)]
+#[command(version, author = "laroxyss")]
+struct Cli {
+    /// Файлы-образцы с типичным содержимым
+    #[arg(required = true)]
+    samples: Vec<PathBuf>,
+
+    /// Путь к создаваемому файлу словаря
+    #[arg(short, long)]
+    output: PathBuf,
+}
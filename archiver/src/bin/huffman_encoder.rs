@@ -1,26 +1,131 @@
-use anyhow::Result;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
 
 use archiver::{
-    HuffmanArchiver,
-    io::{print_sizes, read_filepath},
+    Dictionary, Filter, FilteredArchiver, HuffmanArchiver, RleArchiver, RleHuffmanArchiver, SeekableArchiver,
+    Utf8Archiver,
+    io::{ProgressBar, path_to_absolute, print_sizes, read_filepath},
+    progress::CancellationToken,
 };
 
 fn main() -> Result<()> {
-    let target = read_filepath(&"Please enter the path to the file you want to archive:")?;
+    let cli = Cli::parse();
+
+    let target = match cli.target {
+        Some(path) => path_to_absolute(path)?,
+        None => read_filepath(&"Please enter the path to the file you want to archive:")?,
+    };
 
     // Проверяем существование файла
     if !target.exists() {
         anyhow::bail!("File does not exist: {}", target.display());
     }
 
-    let destination =
-        read_filepath(&"Please enter the path where you would like the result of the operation to be saved:")?;
+    let destination = match cli.destination {
+        Some(path) => path_to_absolute(path)?,
+        None => read_filepath(&"Please enter the path where you would like the result of the operation to be saved:")?,
+    };
+
+    if let Some(dictionary) = cli.dictionary {
+        Dictionary::load(dictionary)?.archive(&target, &destination)?;
+    } else if let Some(block_size) = cli.block_size {
+        SeekableArchiver::new()
+            .with_block_size(block_size as usize)
+            .archive(&target, &destination)?;
+    } else if let Some(filter) = cli.filter {
+        let filter = FilteredArchiver::archive(filter.into(), &target, &destination)?;
+        println!("Filter: {}", filter);
+    } else if cli.utf8 {
+        Utf8Archiver::archive(&target, &destination)?;
+    } else if cli.rle {
+        RleArchiver::archive(&target, &destination)?;
+    } else if cli.rle_huffman {
+        RleHuffmanArchiver::archive(&target, &destination)?;
+    } else if cli.progress {
+        // Ctrl+C прерывает архивацию и удаляет недописанный архив
+        let token = CancellationToken::new();
+        let handler_token = token.clone();
+        ctrlc::set_handler(move || handler_token.cancel()).context("Failed to set Ctrl+C handler")?;
 
-    HuffmanArchiver::archive(&target, &destination).unwrap();
+        let mut progress_bar = ProgressBar::new();
+        let result = HuffmanArchiver::archive_with_progress(&target, &destination, &mut progress_bar, &token);
+        progress_bar.finish();
+        result?;
+    } else {
+        HuffmanArchiver::archive(&target, &destination)?;
+    }
 
     print_sizes(target, destination)
 }
 
+/// Архивирует файл кодом Хаффмана. Пути, не указанные в аргументах, запрашиваются интерактивно
+#[derive(Parser)]
+#[command(version, author = "laroxyss")]
+struct Cli {
+    /// Архивируемый файл
+    target: Option<PathBuf>,
+
+    /// Путь к создаваемому архиву
+    destination: Option<PathBuf>,
+
+    /// Общий словарь, созданный `huffman_dictionary`. Таблица кодов в архив не записывается
+    #[arg(short, long)]
+    dictionary: Option<PathBuf>,
+
+    /// Создать архив с произвольным доступом из независимых блоков указанного размера в байтах
+    #[arg(short, long, conflicts_with = "dictionary", value_parser = clap::value_parser!(u64).range(1..))]
+    block_size: Option<u64>,
+
+    /// Фильтр перед кодированием: `auto`, `none`, `delta:STRIDE`, `sub:ROW_LEN:BPP`, `up:ROW_LEN`,
+    /// `paeth:ROW_LEN:BPP` или `xor-float:WIDTH`. Распаковщик определяет такие архивы по сигнатуре
+    #[arg(short, long, conflicts_with_all = ["dictionary", "block_size"], value_parser = parse_filter)]
+    filter: Option<FilterChoice>,
+
+    /// Кодировать кодовые точки UTF-8 вместо байтов. Распаковка с флагом `--utf8`
+    #[arg(short, long, conflicts_with_all = ["dictionary", "block_size", "filter"])]
+    utf8: bool,
+
+    /// Кодировать серии одинаковых байтов схемой PackBits без кода Хаффмана. Распаковка с флагом `--rle`
+    #[arg(long, conflicts_with_all = ["dictionary", "block_size", "filter", "utf8"])]
+    rle: bool,
+
+    /// Кодировать кодом Хаффмана пары (байт, длина серии). Распаковка с флагом `--rle-huffman`
+    #[arg(long, conflicts_with_all = ["dictionary", "block_size", "filter", "utf8", "rle"])]
+    rle_huffman: bool,
+
+    /// Показывать ход архивации. Ctrl+C отменяет операцию
+    #[arg(short, long, conflicts_with_all = ["dictionary", "block_size", "filter", "utf8", "rle", "rle_huffman"])]
+    progress: bool,
+}
+
+#[derive(Clone, Copy)]
+enum FilterChoice {
+    /// Фильтр с минимальной энтропией отфильтрованных данных
+    Auto,
+    Fixed(Filter),
+}
+
+impl From<FilterChoice> for Option<Filter> {
+    fn from(choice: FilterChoice) -> Self {
+        match choice {
+            FilterChoice::Auto => None,
+            FilterChoice::Fixed(filter) => Some(filter),
+        }
+    }
+}
+
+fn parse_filter(value: &str) -> Result<FilterChoice, String> {
+    match value {
+        "auto" => Ok(FilterChoice::Auto),
+        _ => value
+            .parse()
+            .map(FilterChoice::Fixed)
+            .map_err(|err: anyhow::Error| err.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,182 @@
+use anyhow::Result;
+
+/// Побитовая запись в буфер, старший бит байта записывается первым.
+///
+/// В отличие от битовых строк [`crate::Encoder`], не тратит байт на каждый бит.
+#[derive(Debug, Clone, Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Количество записанных битов, не считая уже забранных [`BitWriter::take_full_bytes`].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 0x80 >> (self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    /// Записывает `count` младших битов `value`, начиная со старшего из них.
+    ///
+    /// # Panics
+    /// Паникует, если `count > 64`.
+    pub fn write_bits(&mut self, value: u64, count: u32) {
+        assert!(count <= 64, "Cannot write more than 64 bits at once, actual: {}", count);
+
+        for i in (0..count).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    /// Записывает `count` одинаковых битов.
+    pub fn write_repeated(&mut self, bit: bool, count: u64) {
+        for _ in 0..count {
+            self.write_bit(bit);
+        }
+    }
+
+    /// Записанные биты в виде строки из `0` и `1`.
+    pub fn to_bit_string(&self) -> String {
+        (0..self.len)
+            .map(|i| {
+                if self.bytes[i / 8] & (0x80 >> (i % 8)) != 0 {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect()
+    }
+
+    /// Забирает полностью записанные байты, в буфере остается только неполный последний байт.
+    /// Позволяет записывать результат по частям.
+    pub fn take_full_bytes(&mut self) -> Vec<u8> {
+        let full = self.len / 8;
+        let rest = self.bytes.split_off(full);
+        self.len -= full * 8;
+        std::mem::replace(&mut self.bytes, rest)
+    }
+
+    /// Возвращает байты, последний байт дополнен нулями.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Побитовое чтение из среза байтов, записанных [`BitWriter`].
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Номер следующего читаемого бита.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Количество непрочитанных битов, включая биты дополнения последнего байта.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() * 8 - self.position
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool> {
+        anyhow::ensure!(
+            self.remaining() > 0,
+            "Unexpected end of bit stream at bit {}",
+            self.position
+        );
+
+        let bit = self.bytes[self.position / 8] & (0x80 >> (self.position % 8)) != 0;
+        self.position += 1;
+        Ok(bit)
+    }
+
+    /// Читает `count` битов как число, первый прочитанный бит - старший.
+    ///
+    /// # Panics
+    /// Паникует, если `count > 64`.
+    pub fn read_bits(&mut self, count: u32) -> Result<u64> {
+        assert!(count <= 64, "Cannot read more than 64 bits at once, actual: {}", count);
+        anyhow::ensure!(
+            self.remaining() >= count as usize,
+            "Unexpected end of bit stream: {} bits requested, {} left",
+            count,
+            self.remaining()
+        );
+
+        let mut value = 0u64;
+        for _ in 0..count {
+            value = (value << 1) | self.read_bit()? as u64;
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_and_read() {
+        let mut writer = BitWriter::new();
+        writer.write_bit(true);
+        writer.write_bits(0b0110, 4);
+        writer.write_repeated(true, 5);
+        writer.write_bits(u64::MAX, 64);
+
+        assert_eq!(writer.len(), 74);
+        assert!(writer.to_bit_string().starts_with("1011011111"));
+
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes.len(), 10);
+        assert_eq!(bytes[0], 0b1011_0111);
+
+        let mut reader = BitReader::new(&bytes);
+        assert!(reader.read_bit().unwrap());
+        assert_eq!(reader.read_bits(4).unwrap(), 0b0110);
+        assert_eq!(reader.read_bits(5).unwrap(), 0b11111);
+        assert_eq!(reader.read_bits(64).unwrap(), u64::MAX);
+        assert_eq!(reader.position(), 74);
+
+        // Биты дополнения нулевые, дальше конец потока
+        assert_eq!(reader.read_bits(6).unwrap(), 0);
+        assert!(reader.read_bit().is_err());
+    }
+
+    #[test]
+    fn test_take_full_bytes() {
+        let mut writer = BitWriter::new();
+        writer.write_bits(0b101_0101_0111, 11);
+
+        assert_eq!(writer.take_full_bytes(), [0b1010_1010]);
+        assert_eq!(writer.len(), 3);
+        assert!(writer.take_full_bytes().is_empty());
+
+        writer.write_bits(0b00001, 5);
+        assert_eq!(writer.take_full_bytes(), [0b1110_0001]);
+        assert!(writer.is_empty());
+    }
+}
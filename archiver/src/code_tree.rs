@@ -0,0 +1,201 @@
+use std::fmt::Write;
+
+use crate::Word;
+
+/// Дерево префиксного кода для наглядного отображения: переход влево - `0`, вправо - `1`.
+///
+/// Узлы подписываются суммарной вероятностью поддерева, листья - символом, вероятностью и кодом.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CodeTree<W = u8> {
+    Leaf {
+        probability: f64,
+        word: W,
+        code: String,
+    },
+    Node {
+        probability: f64,
+        left: Box<CodeTree<W>>,
+        right: Box<CodeTree<W>>,
+    },
+}
+
+impl<W: Word> CodeTree<W> {
+    pub fn probability(&self) -> f64 {
+        match self {
+            CodeTree::Leaf { probability, .. } => *probability,
+            CodeTree::Node { probability, .. } => *probability,
+        }
+    }
+
+    /// Коды листьев слева направо.
+    pub fn codes(&self) -> Vec<String> {
+        match self {
+            CodeTree::Leaf { code, .. } => vec![code.clone()],
+            CodeTree::Node { left, right, .. } => {
+                let mut codes = left.codes();
+                codes.extend(right.codes());
+                codes
+            }
+        }
+    }
+
+    /// Экспортирует дерево в формат Graphviz DOT.
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut dot = String::new();
+        let mut next_id = 0;
+
+        writeln!(dot, "digraph \"{}\" {{", escape_dot(name)).unwrap();
+        writeln!(dot, "    node [shape=circle, fontname=\"monospace\"];").unwrap();
+        self.write_dot(&mut dot, &mut next_id);
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    /// Записывает вершину с потомками и возвращает ее идентификатор.
+    fn write_dot(&self, dot: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+
+        match self {
+            CodeTree::Leaf {
+                probability,
+                word,
+                code,
+            } => {
+                writeln!(
+                    dot,
+                    "    n{} [shape=box, label=\"{}\\n{:.4}\\n{}\"];",
+                    id,
+                    escape_dot(&word_label(*word)),
+                    probability,
+                    display_code(code)
+                )
+                .unwrap();
+            }
+            CodeTree::Node {
+                probability,
+                left,
+                right,
+            } => {
+                writeln!(dot, "    n{} [label=\"{:.4}\"];", id, probability).unwrap();
+
+                let left_id = left.write_dot(dot, next_id);
+                writeln!(dot, "    n{} -> n{} [label=\"0\"];", id, left_id).unwrap();
+
+                let right_id = right.write_dot(dot, next_id);
+                writeln!(dot, "    n{} -> n{} [label=\"1\"];", id, right_id).unwrap();
+            }
+        }
+
+        id
+    }
+
+    /// Рисует дерево с отступами псевдографикой Unicode.
+    pub fn to_unicode(&self) -> String {
+        let mut result = String::new();
+        writeln!(result, "{}", self.unicode_label()).unwrap();
+        self.write_unicode_children(&mut result, "");
+        result
+    }
+
+    fn write_unicode_children(&self, result: &mut String, prefix: &str) {
+        if let CodeTree::Node { left, right, .. } = self {
+            for (bit, child, is_last) in [('0', left, false), ('1', right, true)] {
+                let (branch, indent) = if is_last {
+                    ("└─", "   ")
+                } else {
+                    ("├─", "│  ")
+                };
+
+                writeln!(result, "{}{}{}─ {}", prefix, branch, bit, child.unicode_label()).unwrap();
+                child.write_unicode_children(result, &format!("{}{}", prefix, indent));
+            }
+        }
+    }
+
+    fn unicode_label(&self) -> String {
+        match self {
+            CodeTree::Leaf {
+                probability,
+                word,
+                code,
+            } => format!("{} p={:.4} code={}", word_label(*word), probability, display_code(code)),
+            CodeTree::Node { probability, .. } => format!("p={:.4}", probability),
+        }
+    }
+}
+
+/// Печатные ASCII символы показываются вместе с кодом символа.
+fn word_label<W: Word>(word: W) -> String {
+    let value: u64 = word.into();
+    match u8::try_from(value) {
+        Ok(byte) if byte.is_ascii_graphic() => format!("{} '{}'", word, byte as char),
+        _ => format!("{}", word),
+    }
+}
+
+/// Пустой код единственного символа показывается явно.
+fn display_code(code: &str) -> &str {
+    if code.is_empty() { "ε" } else { code }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_test_tree() -> CodeTree {
+        CodeTree::Node {
+            probability: 1.0,
+            left: CodeTree::Leaf {
+                probability: 0.5,
+                word: b'a',
+                code: "0".into(),
+            }
+            .into(),
+            right: CodeTree::Node {
+                probability: 0.5,
+                left: CodeTree::Leaf {
+                    probability: 0.25,
+                    word: b'"',
+                    code: "10".into(),
+                }
+                .into(),
+                right: CodeTree::Leaf {
+                    probability: 0.25,
+                    word: 0,
+                    code: "11".into(),
+                }
+                .into(),
+            }
+            .into(),
+        }
+    }
+
+    #[test]
+    fn test_to_unicode() {
+        let expected = "\
+p=1.0000
+├─0─ 97 'a' p=0.5000 code=0
+└─1─ p=0.5000
+   ├─0─ 34 '\"' p=0.2500 code=10
+   └─1─ 0 p=0.2500 code=11
+";
+        assert_eq!(new_test_tree().to_unicode(), expected);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = new_test_tree().to_dot("test");
+
+        assert!(dot.starts_with("digraph \"test\" {"));
+        assert!(dot.contains("n0 -> n1 [label=\"0\"];"));
+        assert!(dot.contains("n0 -> n2 [label=\"1\"];"));
+        assert!(dot.contains("n3 [shape=box, label=\"34 '\\\"'\\n0.2500\\n10\"];"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+}
@@ -1,13 +1,16 @@
+use anyhow::Result;
 use std::collections::HashMap;
 
+use crate::Word;
+
 #[derive(Debug, Default)]
-pub struct Codes {
+pub struct Codes<W = u8> {
     probabilities: Vec<f64>,
     codes: Vec<String>,
-    words: Vec<u8>,
+    words: Vec<W>,
 }
 
-impl Codes {
+impl<W: Word> Codes<W> {
     pub fn probabilities(&self) -> &[f64] {
         &self.probabilities
     }
@@ -16,11 +19,32 @@ impl Codes {
         &self.codes
     }
 
-    pub fn words(&self) -> &[u8] {
+    pub fn words(&self) -> &[W] {
         &self.words
     }
 
-    pub fn new(words: Vec<u8>, probabilities: Vec<f64>, codes: Vec<String>) -> Self {
+    /// Создает таблицу кодов.
+    ///
+    /// # Panics
+    /// Паникует, если длины векторов различаются или коды не образуют префиксный код,
+    /// см. [`Codes::try_new`].
+    pub fn new(words: Vec<W>, probabilities: Vec<f64>, codes: Vec<String>) -> Self {
+        Self::try_new(words, probabilities, codes).unwrap_or_else(|err| panic!("Invalid code table: {:#}", err))
+    }
+
+    /// Создает таблицу кодов, предварительно проверив, что коды состоят из `0` и `1`,
+    /// удовлетворяют неравенству Крафта и ни один код не является началом другого.
+    pub fn try_new(words: Vec<W>, probabilities: Vec<f64>, codes: Vec<String>) -> Result<Self> {
+        validate_prefix_code(codes.iter().map(String::as_str))?;
+        Ok(Self::new_unchecked(words, probabilities, codes))
+    }
+
+    /// Создает таблицу кодов без проверки префиксности. Полезно для вычисления характеристик
+    /// произвольных (в том числе неоднозначно декодируемых) кодов.
+    ///
+    /// # Panics
+    /// Паникует, если длины векторов различаются.
+    pub fn new_unchecked(words: Vec<W>, probabilities: Vec<f64>, codes: Vec<String>) -> Self {
         assert_eq!(words.len(), probabilities.len());
         assert_eq!(words.len(), codes.len());
 
@@ -74,10 +98,83 @@ impl Codes {
     pub fn entropy(&self) -> f64 {
         -(self.probabilities.iter().map(|&p| p * p.log2()).sum::<f64>())
     }
+
+    /// Вычисляет сумму из неравенства Крафта-Макмиллана: `Σ 2^(-ki)`.
+    ///
+    /// Для любого однозначно декодируемого кода сумма не превышает `1`.
+    pub fn kraft_sum(&self) -> f64 {
+        kraft_sum(self.codes.iter().map(String::as_str))
+    }
+
+    /// Проверяет, что ни один код не является началом другого.
+    pub fn is_prefix_free(&self) -> bool {
+        find_prefix_conflict(self.codes.iter().map(String::as_str)).is_none()
+    }
+
+    /// Префиксный код полный, если к нему нельзя добавить ни одного кода без нарушения префиксности,
+    /// то есть сумма Крафта равна `1`: каждый узел дерева кода имеет двух потомков.
+    pub fn is_complete(&self) -> bool {
+        self.is_prefix_free() && (self.kraft_sum() - 1.0).abs() < KRAFT_EPSILON
+    }
+
+    /// Вычисляет абсолютную избыточность кода в битах на символ: `Σ(pi * ki) - H`.
+    pub fn absolute_redundancy(&self) -> f64 {
+        self.mean_code_length() - self.entropy()
+    }
+
+    /// Вычисляет относительную избыточность кода: `(Σ(pi * ki) - H) / Σ(pi * ki)`.
+    pub fn relative_redundancy(&self) -> f64 {
+        self.absolute_redundancy() / self.mean_code_length()
+    }
+}
+
+/// Погрешность сравнения суммы Крафта с единицей.
+const KRAFT_EPSILON: f64 = 1e-12;
+
+/// Вычисляет сумму Крафта `Σ 2^(-ki)` для набора кодов.
+pub(crate) fn kraft_sum<'a>(codes: impl IntoIterator<Item = &'a str>) -> f64 {
+    codes.into_iter().map(|code| 0.5f64.powi(code.len() as i32)).sum()
+}
+
+/// Ищет пару кодов, в которой первый является началом второго.
+///
+/// После лексикографической сортировки код и начинающиеся с него коды идут подряд,
+/// поэтому достаточно сравнить соседей.
+fn find_prefix_conflict<'a>(codes: impl IntoIterator<Item = &'a str>) -> Option<(&'a str, &'a str)> {
+    let mut codes = codes.into_iter().collect::<Vec<_>>();
+    codes.sort_unstable();
+
+    codes
+        .windows(2)
+        .find(|pair| pair[1].starts_with(pair[0]))
+        .map(|pair| (pair[0], pair[1]))
+}
+
+/// Проверяет таблицу кодов: коды состоят только из `0` и `1`, удовлетворяют неравенству Крафта
+/// и образуют префиксный код. Пустой код допустим только для единственного символа.
+pub(crate) fn validate_prefix_code<'a>(codes: impl IntoIterator<Item = &'a str> + Clone) -> Result<()> {
+    if let Some(code) = codes
+        .clone()
+        .into_iter()
+        .find(|code| code.chars().any(|c| c != '0' && c != '1'))
+    {
+        anyhow::bail!("Code '{}' contains characters other than '0' and '1'", code);
+    }
+
+    let sum = kraft_sum(codes.clone());
+    if sum > 1.0 + KRAFT_EPSILON {
+        anyhow::bail!("Codes violate Kraft inequality: Σ 2^(-ki) = {} > 1", sum);
+    }
+
+    if let Some((prefix, code)) = find_prefix_conflict(codes) {
+        anyhow::bail!("Code '{}' is a prefix of code '{}'", prefix, code);
+    }
+
+    Ok(())
 }
 
-impl Into<HashMap<u8, String>> for &Codes {
-    fn into(self) -> HashMap<u8, String> {
+impl<W: Word> Into<HashMap<W, String>> for &Codes<W> {
+    fn into(self) -> HashMap<W, String> {
         let mut word_code = HashMap::new();
         for (&word, code) in self.words.iter().zip(self.codes.iter()) {
             if word_code.insert(word, code.clone()).is_some() {
@@ -88,8 +185,8 @@ impl Into<HashMap<u8, String>> for &Codes {
     }
 }
 
-impl Into<HashMap<u8, String>> for Codes {
-    fn into(self) -> HashMap<u8, String> {
+impl<W: Word> Into<HashMap<W, String>> for Codes<W> {
+    fn into(self) -> HashMap<W, String> {
         let mut word_code = HashMap::new();
         for (word, code) in self.words.into_iter().zip(self.codes.into_iter()) {
             if word_code.insert(word, code).is_some() {
@@ -110,7 +207,7 @@ mod tests {
     }
 
     fn codes_without_words(probabilities: Vec<f64>, codes: &[&str]) -> Codes {
-        Codes::new(vec![0; probabilities.len()], probabilities, str_vec(codes))
+        Codes::new_unchecked(vec![0; probabilities.len()], probabilities, str_vec(codes))
     }
 
     #[test]
@@ -172,4 +269,69 @@ mod tests {
         let codes = codes_without_words(vec![0.8, 0.1, 0.1], &["0", "10", "11"]);
         assert!((codes.statistical_compression_ratio() - 1.66666).abs() < 0.00001);
     }
+
+    #[test]
+    fn test_kraft_sum() {
+        let codes = codes_without_words(vec![0.25; 4], &["00", "01", "10", "11"]);
+        assert_eq!(1.0, codes.kraft_sum());
+
+        let codes = codes_without_words(vec![0.5, 0.25, 0.25], &["0", "10", "110"]);
+        assert_eq!(0.875, codes.kraft_sum());
+
+        let codes = codes_without_words(vec![0.5, 0.25, 0.25], &["0", "1", "10"]);
+        assert_eq!(1.25, codes.kraft_sum());
+    }
+
+    #[test]
+    fn test_prefix_free_and_complete() {
+        let codes = codes_without_words(vec![0.5, 0.25, 0.25], &["0", "10", "11"]);
+        assert!(codes.is_prefix_free());
+        assert!(codes.is_complete());
+
+        let codes = codes_without_words(vec![0.5, 0.25, 0.25], &["0", "10", "110"]);
+        assert!(codes.is_prefix_free());
+        assert!(!codes.is_complete());
+
+        let codes = codes_without_words(vec![0.5, 0.4, 0.1], &["000", "1110000", "0001111"]);
+        assert!(!codes.is_prefix_free());
+        assert!(!codes.is_complete());
+
+        let codes = codes_without_words(vec![0.5, 0.5], &["01", "01"]);
+        assert!(!codes.is_prefix_free());
+
+        let codes = codes_without_words(vec![1.0], &[""]);
+        assert!(codes.is_prefix_free());
+        assert!(codes.is_complete());
+    }
+
+    #[test]
+    fn test_redundancy() {
+        let codes = codes_without_words(vec![0.5, 0.25, 0.25], &["0", "10", "11"]);
+        assert_eq!(0.0, codes.absolute_redundancy());
+        assert_eq!(0.0, codes.relative_redundancy());
+
+        let codes = codes_without_words(vec![0.25; 4], &["0", "10", "110", "111"]);
+        assert_eq!(0.25, codes.absolute_redundancy());
+        assert_eq!(0.25 / 2.25, codes.relative_redundancy());
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(Codes::try_new(vec![1u8, 2, 3], vec![0.5, 0.25, 0.25], str_vec(&["0", "10", "11"])).is_ok());
+
+        let err = Codes::try_new(vec![1u8, 2], vec![0.5, 0.5], str_vec(&["0", "01"])).unwrap_err();
+        assert!(err.to_string().contains("prefix"), "{}", err);
+
+        let err = Codes::try_new(vec![1u8, 2, 3], vec![0.4, 0.3, 0.3], str_vec(&["0", "1", "00"])).unwrap_err();
+        assert!(err.to_string().contains("Kraft"), "{}", err);
+
+        let err = Codes::try_new(vec![1u8, 2], vec![0.5, 0.5], str_vec(&["0", "12"])).unwrap_err();
+        assert!(err.to_string().contains("characters"), "{}", err);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid code table")]
+    fn test_new_rejects_invalid_codes() {
+        Codes::new(vec![1u8, 2], vec![0.5, 0.5], str_vec(&["1", "1"]));
+    }
 }
@@ -1,11 +1,12 @@
 use anyhow::{Context, Result};
 use std::{
     fs::File,
-    io::{Read, Write},
+    io::{Read, Seek, Write},
     path::Path,
 };
 
 use super::{StateSaver, utils::convert_to_string};
+use crate::progress::{CHUNK_SIZE, CancellationToken, NoProgress, Phase, Progress, ProgressObserver, remove_on_error};
 
 pub trait Decoder {
     /// Декодирует строку битов.
@@ -15,6 +16,15 @@ pub trait Decoder {
         let bit_string = convert_to_string(bytes);
         self.decode_string(&bit_string)
     }
+
+    /// Декодирует `original_size` байтов из `bytes` и передает результат в `f` частями. По умолчанию
+    /// все данные декодируются сразу и передаются одной частью; кодеки, умеющие декодировать
+    /// по частям, переопределяют метод, чтобы распаковка чаще сообщала прогресс и проверяла отмену.
+    fn decode_chunks(&self, bytes: &[u8], original_size: usize, f: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()> {
+        let mut decoded = self.decode_bytes(bytes).context("Failed to decode")?;
+        decoded.truncate(original_size);
+        f(&decoded)
+    }
 }
 
 impl<T> FileDecoder for T where T: Decoder + StateSaver {}
@@ -24,24 +34,100 @@ where
     Self: Decoder + StateSaver + Sized,
 {
     fn decode_file<P: AsRef<Path>>(target: P, destination: P) -> Result<()> {
-        let mut file = File::open(target).context("Failed to create file")?;
+        Self::decode_file_with_progress(target, destination, &mut NoProgress, &CancellationToken::new())
+    }
+
+    /// Распаковывает архив так же, как [`FileDecoder::decode_file`], сообщая о ходе работы `observer`.
+    ///
+    /// При отмене через `token` возвращает ошибку [`crate::progress::Cancelled`] и удаляет частично
+    /// записанный файл.
+    fn decode_file_with_progress<P: AsRef<Path>>(
+        target: P,
+        destination: P,
+        observer: &mut impl ProgressObserver,
+        token: &CancellationToken,
+    ) -> Result<()> {
+        let mut file = File::open(target).context("Failed to open archive")?;
+        let archive_size = file.metadata().context("Failed to read file metadata")?.len();
+        let (decoder, original_size) = Self::read_header(&mut file)?;
+        let header_size = file.stream_position().context("Failed to read archive header")?;
+
+        // Чтение сжатых данных
+        let payload_size = archive_size.saturating_sub(header_size);
+        let mut payload = Vec::with_capacity(payload_size as usize);
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        loop {
+            let n = file.read(&mut buf).context("Failed to read encoded part")?;
+            if n == 0 {
+                break;
+            }
+            payload.extend_from_slice(&buf[..n]);
+
+            observer.on_progress(&Progress {
+                phase: Phase::Reading,
+                processed: payload.len() as u64,
+                total: payload_size,
+                compression_ratio: None,
+            });
+            token.check()?;
+        }
+
+        let compression_ratio = Some(archive_size as f64 / original_size.max(1) as f64);
 
+        remove_on_error(destination.as_ref(), |file| {
+            let mut decoded = 0;
+            decoder.decode_chunks(&payload, original_size, &mut |chunk| {
+                file.write_all(chunk).context("Failed to write to file")?;
+                decoded += chunk.len();
+
+                observer.on_progress(&Progress {
+                    phase: Phase::Decoding,
+                    processed: decoded as u64,
+                    total: original_size as u64,
+                    compression_ratio,
+                });
+                Ok(token.check()?)
+            })?;
+
+            file.flush().context("Failed to write to file")?;
+            observer.on_progress(&Progress {
+                phase: Phase::Writing,
+                processed: decoded as u64,
+                total: original_size as u64,
+                compression_ratio,
+            });
+            Ok(())
+        })
+    }
+
+    /// Читает заголовок архива: состояние кодека и исходный размер данных.
+    fn read_header<R: Read>(reader: &mut R) -> Result<(Self, usize)> {
         // Восстанавливаем состояние кодека
-        let state = Self::read_state(&mut file)?;
+        let state = Self::read_state(reader)?;
 
         // Читаем оригинальный размер файла (в байтах), записанный при кодировании
         let mut original_size_buf = [0u8; std::mem::size_of::<usize>()];
-        file.read_exact(&mut original_size_buf)
+        reader
+            .read_exact(&mut original_size_buf)
             .context("Failed to read original size")?;
         let original_size = usize::from_le_bytes(original_size_buf);
 
+        Ok((Self::load_state(state)?, original_size))
+    }
+
+    /// Читает архив, записанный [`FileEncoder::encode_to_writer`](crate::FileEncoder::encode_to_writer),
+    /// и возвращает исходные данные.
+    fn decode_from_reader<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+        let (decoder, original_size) = Self::read_header(reader)?;
+
         // Читаем оставшуюся закодированную часть до конца файла
         let mut bytes = Vec::new();
-        file.read_to_end(&mut bytes).context("Failed to read encoded part")?;
+        reader.read_to_end(&mut bytes).context("Failed to read encoded part")?;
 
-        // Декодируем файл
-        let decoder = Self::load_state(state)?;
-        decoder.decode_and_write(&bytes, destination, original_size)
+        // Декодируем и удаляем возможные лишние байты, появившиеся из-за паддинга при кодировании
+        let mut decoded = decoder.decode_bytes(&bytes).context("Failed to decode")?;
+        decoded.truncate(original_size);
+        Ok(decoded)
     }
 
     fn decode_and_write<P: AsRef<Path>>(&self, bytes: &[u8], destination: P, original_size: usize) -> Result<()> {
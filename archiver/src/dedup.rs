@@ -0,0 +1,635 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::Cursor,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{FileDecoder, FileEncoder, FrequencyMap, HuffmanArchiver, RleArchiver, huffman::MAX_CODE_LENGTH};
+
+/// Таблица случайных 64-битных чисел для rolling hash Gear, по одному на каждый байт.
+/// Заполняется генератором SplitMix64, чтобы границы фрагментов не зависели от версии программы.
+const GEAR: [u64; 256] = {
+    let mut table = [0u64; 256];
+    let mut state = 0x9E3779B97F4A7C15u64;
+    let mut i = 0;
+    while i < table.len() {
+        state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+};
+
+/// Разбиение данных на фрагменты переменного размера, определяемые содержимым (content-defined chunking).
+///
+/// Для каждого байта обновляется хеш Gear `hash = (hash << 1) + GEAR[byte]`, который зависит только
+/// от последних 64 байтов. Граница ставится, когда старшие `log2(avg_size)` битов хеша равны нулю.
+/// Поэтому вставка в начало файла сдвигает только ближайшие границы, а остальные фрагменты совпадают
+/// с фрагментами исходного файла.
+#[derive(Debug, Clone)]
+pub struct Chunker {
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+}
+
+impl Default for Chunker {
+    fn default() -> Self {
+        Self {
+            min_size: 2 * 1024,
+            avg_size: 8 * 1024,
+            max_size: 64 * 1024,
+        }
+    }
+}
+
+impl Chunker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Задает минимальный, средний и максимальный размер фрагмента.
+    ///
+    /// # Panics
+    /// Паникует, если `avg_size` не степень двойки или не выполнено `0 < min_size <= avg_size <= max_size`.
+    pub fn with_sizes(self, min_size: usize, avg_size: usize, max_size: usize) -> Self {
+        self.try_with_sizes(min_size, avg_size, max_size)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Как [`Chunker::with_sizes`], но для недопустимых размеров возвращает ошибку, например
+    /// для размеров из аргументов командной строки.
+    pub fn try_with_sizes(mut self, min_size: usize, avg_size: usize, max_size: usize) -> Result<Self> {
+        anyhow::ensure!(
+            avg_size.is_power_of_two(),
+            "Average chunk size must be a power of two, got {}",
+            avg_size
+        );
+        anyhow::ensure!(
+            0 < min_size && min_size <= avg_size && avg_size <= max_size,
+            "Chunk sizes must satisfy 0 < min <= avg <= max, got {} <= {} <= {}",
+            min_size,
+            avg_size,
+            max_size
+        );
+        self.min_size = min_size;
+        self.avg_size = avg_size;
+        self.max_size = max_size;
+        Ok(self)
+    }
+
+    pub fn min_size(&self) -> usize {
+        self.min_size
+    }
+
+    pub fn avg_size(&self) -> usize {
+        self.avg_size
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Разбивает `bytes` на фрагменты. Пустые данные дают пустой список.
+    pub fn split<'a>(&self, bytes: &'a [u8]) -> Vec<&'a [u8]> {
+        let mut chunks = Vec::new();
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            let (chunk, tail) = rest.split_at(self.boundary(rest));
+            chunks.push(chunk);
+            rest = tail;
+        }
+        chunks
+    }
+
+    /// Длина первого фрагмента `bytes`.
+    fn boundary(&self, bytes: &[u8]) -> usize {
+        if bytes.len() <= self.min_size {
+            return bytes.len();
+        }
+
+        let mask = !(u64::MAX >> self.avg_size.trailing_zeros());
+        let end = bytes.len().min(self.max_size);
+        let mut hash = 0u64;
+        for (i, &byte) in bytes.iter().enumerate().take(end).skip(self.min_size) {
+            hash = (hash << 1).wrapping_add(GEAR[byte as usize]);
+            if hash & mask == 0 {
+                return i + 1;
+            }
+        }
+        end
+    }
+}
+
+/// Идентификатор фрагмента - хеш SHA-256 его содержимого.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChunkId([u8; 32]);
+
+impl ChunkId {
+    pub fn of(bytes: &[u8]) -> Self {
+        Self(Sha256::digest(bytes).into())
+    }
+}
+
+impl fmt::Display for ChunkId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ChunkId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        anyhow::ensure!(
+            s.len() == 64 && s.is_ascii(),
+            "Invalid chunk id: expected 64 hex digits, got {:?}",
+            s
+        );
+
+        let mut id = [0u8; 32];
+        for (i, byte) in id.iter_mut().enumerate() {
+            *byte =
+                u8::from_str_radix(&s[2 * i..2 * i + 2], 16).with_context(|| format!("Invalid chunk id: {:?}", s))?;
+        }
+        Ok(Self(id))
+    }
+}
+
+impl Serialize for ChunkId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChunkId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Ссылка на фрагмент в манифесте файла.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkRef {
+    pub id: ChunkId,
+    pub size: u64,
+}
+
+/// Манифест файла в хранилище: размер и список фрагментов по порядку.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    pub size: u64,
+    pub chunks: Vec<ChunkRef>,
+}
+
+/// Статистика хранилища.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DedupStats {
+    /// Количество файлов (манифестов).
+    pub files: usize,
+    /// Суммарный размер всех файлов.
+    pub logical_size: u64,
+    /// Количество ссылок на фрагменты во всех манифестах.
+    pub chunk_refs: usize,
+    /// Количество уникальных фрагментов.
+    pub unique_chunks: usize,
+    /// Суммарный размер уникальных фрагментов до сжатия.
+    pub unique_size: u64,
+    /// Размер сжатых фрагментов на диске.
+    pub stored_size: u64,
+}
+
+impl DedupStats {
+    /// Коэффициент дедупликации: во сколько раз данные файлов больше уникальных фрагментов.
+    pub fn dedup_ratio(&self) -> f64 {
+        ratio(self.logical_size, self.unique_size)
+    }
+
+    /// Итоговый коэффициент с учетом сжатия фрагментов.
+    pub fn compression_ratio(&self) -> f64 {
+        ratio(self.logical_size, self.stored_size)
+    }
+}
+
+fn ratio(original: u64, stored: u64) -> f64 {
+    if stored == 0 {
+        return 1.0;
+    }
+    original as f64 / stored as f64
+}
+
+/// Кодек, которым сжат фрагмент. Записывается первым байтом файла фрагмента.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkCodec {
+    Stored = 0,
+    Huffman = 1,
+    Rle = 2,
+}
+
+impl ChunkCodec {
+    fn from_tag(tag: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(Self::Stored),
+            1 => Ok(Self::Huffman),
+            2 => Ok(Self::Rle),
+            _ => anyhow::bail!("Unknown chunk codec: {}", tag),
+        }
+    }
+
+    fn encode(self, bytes: &[u8]) -> Result<Vec<u8>> {
+        let mut encoded = vec![self as u8];
+        match self {
+            Self::Stored => encoded.extend_from_slice(bytes),
+            Self::Huffman => {
+                // Коды ограничены по длине, чтобы таблицу всегда можно было сохранить
+                let mut freq_map = FrequencyMap::new();
+                freq_map.consume(bytes);
+                let codes = HuffmanArchiver::build_length_limited_codes(freq_map.counts(), MAX_CODE_LENGTH);
+                HuffmanArchiver::from_codes(codes).encode_to_writer(bytes, &mut encoded)?;
+            }
+            Self::Rle => RleArchiver::new().encode_to_writer(bytes, &mut encoded)?,
+        }
+        Ok(encoded)
+    }
+
+    fn decode(encoded: &[u8]) -> Result<Vec<u8>> {
+        let (&tag, payload) = encoded.split_first().context("Empty chunk file")?;
+        match Self::from_tag(tag)? {
+            Self::Stored => Ok(payload.to_vec()),
+            Self::Huffman => HuffmanArchiver::decode_from_reader(&mut Cursor::new(payload)),
+            Self::Rle => RleArchiver::decode_from_reader(&mut Cursor::new(payload)),
+        }
+    }
+}
+
+/// Хранилище с дедупликацией.
+///
+/// Файлы разбиваются [`Chunker`] на фрагменты, каждый уникальный фрагмент сжимается один раз
+/// наименьшим из кодеков (без сжатия, Хаффман, PackBits) и сохраняется под своим [`ChunkId`].
+/// Для файла записывается манифест со списком идентификаторов фрагментов.
+///
+/// Структура каталога:
+///
+/// `chunks/<sha256>` - первый байт кодек, далее сжатый фрагмент;
+/// `manifests/<name>.json` - манифест файла.
+#[derive(Debug, Clone)]
+pub struct ChunkStore {
+    root: PathBuf,
+    chunker: Chunker,
+}
+
+impl ChunkStore {
+    /// Открывает хранилище в каталоге `root`, создавая его при необходимости.
+    pub fn open<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        for dir in [root.join("chunks"), root.join("manifests")] {
+            std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        Ok(Self {
+            root,
+            chunker: Chunker::default(),
+        })
+    }
+
+    /// Разбиение, которым будут разбиваться новые файлы. Уже сохраненные файлы от него не зависят.
+    pub fn with_chunker(mut self, chunker: Chunker) -> Self {
+        self.chunker = chunker;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Добавляет файл под его именем.
+    pub fn add_file<P: AsRef<Path>>(&self, path: P) -> Result<Manifest> {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .with_context(|| format!("Invalid file name: {}", path.display()))?;
+        let bytes = std::fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+        self.add(name, &bytes)
+    }
+
+    /// Добавляет данные под именем `name`, заменяя манифест с тем же именем.
+    /// Сохраняются только фрагменты, которых еще нет в хранилище.
+    pub fn add(&self, name: &str, bytes: &[u8]) -> Result<Manifest> {
+        let manifest_path = self.manifest_path(name)?;
+
+        let mut chunks = Vec::new();
+        for chunk in self.chunker.split(bytes) {
+            let id = ChunkId::of(chunk);
+            let chunk_path = self.chunk_path(&id);
+            if !chunk_path.exists() {
+                let encoded = compress_chunk(chunk)?;
+                write_atomically(&chunk_path, &encoded)?;
+            }
+            chunks.push(ChunkRef {
+                id,
+                size: chunk.len() as u64,
+            });
+        }
+
+        let manifest = Manifest {
+            name: name.into(),
+            size: bytes.len() as u64,
+            chunks,
+        };
+        let json = serde_json::to_vec_pretty(&manifest).context("Failed to serialize manifest")?;
+        write_atomically(&manifest_path, &json)?;
+        Ok(manifest)
+    }
+
+    pub fn manifest(&self, name: &str) -> Result<Manifest> {
+        let path = self.manifest_path(name)?;
+        let json = std::fs::read(&path).with_context(|| format!("File not found in store: {}", name))?;
+        serde_json::from_slice(&json).with_context(|| format!("Failed to parse manifest: {}", path.display()))
+    }
+
+    /// Имена всех файлов в хранилище по алфавиту.
+    pub fn names(&self) -> Result<Vec<String>> {
+        let dir = self.root.join("manifests");
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(&dir).with_context(|| format!("Failed to read directory: {}", dir.display()))? {
+            let path = entry.context("Failed to read directory entry")?.path();
+            if path.extension().is_some_and(|extension| extension == "json")
+                && let Some(name) = path.file_stem().and_then(|name| name.to_str())
+            {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Восстанавливает файл, проверяя хеш и размер каждого фрагмента.
+    pub fn restore(&self, name: &str) -> Result<Vec<u8>> {
+        let manifest = self.manifest(name)?;
+
+        let mut bytes = Vec::with_capacity(manifest.size as usize);
+        for chunk in &manifest.chunks {
+            let path = self.chunk_path(&chunk.id);
+            let encoded = std::fs::read(&path).with_context(|| format!("Missing chunk {}", chunk.id))?;
+            let decoded =
+                ChunkCodec::decode(&encoded).with_context(|| format!("Failed to decode chunk {}", chunk.id))?;
+
+            anyhow::ensure!(
+                decoded.len() as u64 == chunk.size && ChunkId::of(&decoded) == chunk.id,
+                "Chunk {} is corrupted",
+                chunk.id
+            );
+            bytes.extend_from_slice(&decoded);
+        }
+
+        anyhow::ensure!(
+            bytes.len() as u64 == manifest.size,
+            "Restored size {} does not match manifest size {}",
+            bytes.len(),
+            manifest.size
+        );
+        Ok(bytes)
+    }
+
+    /// Восстанавливает файл `name` в `destination`.
+    pub fn restore_file<P: AsRef<Path>>(&self, name: &str, destination: P) -> Result<()> {
+        let bytes = self.restore(name)?;
+        std::fs::write(destination, bytes).context("Failed to write to file")
+    }
+
+    /// Подсчитывает статистику по всем манифестам хранилища.
+    pub fn stats(&self) -> Result<DedupStats> {
+        let mut stats = DedupStats::default();
+        let mut unique = HashMap::new();
+
+        for name in self.names()? {
+            let manifest = self.manifest(&name)?;
+            stats.files += 1;
+            stats.logical_size += manifest.size;
+            stats.chunk_refs += manifest.chunks.len();
+            for chunk in manifest.chunks {
+                unique.insert(chunk.id, chunk.size);
+            }
+        }
+
+        stats.unique_chunks = unique.len();
+        stats.unique_size = unique.values().sum();
+        for id in unique.keys() {
+            let path = self.chunk_path(id);
+            let metadata = std::fs::metadata(&path).with_context(|| format!("Missing chunk {}", id))?;
+            stats.stored_size += metadata.len();
+        }
+        Ok(stats)
+    }
+
+    /// Удаляет фрагменты, на которые не ссылается ни один манифест. Возвращает количество удаленных фрагментов.
+    pub fn collect_garbage(&self) -> Result<usize> {
+        let mut referenced = HashSet::new();
+        for name in self.names()? {
+            referenced.extend(self.manifest(&name)?.chunks.into_iter().map(|chunk| chunk.id));
+        }
+
+        let dir = self.root.join("chunks");
+        let mut removed = 0;
+        for entry in std::fs::read_dir(&dir).with_context(|| format!("Failed to read directory: {}", dir.display()))? {
+            let path = entry.context("Failed to read directory entry")?.path();
+            let Some(id) = path.file_name().and_then(|name| name.to_str()?.parse::<ChunkId>().ok()) else {
+                continue;
+            };
+            if !referenced.contains(&id) {
+                std::fs::remove_file(&path).with_context(|| format!("Failed to remove chunk {}", id))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn chunk_path(&self, id: &ChunkId) -> PathBuf {
+        self.root.join("chunks").join(id.to_string())
+    }
+
+    fn manifest_path(&self, name: &str) -> Result<PathBuf> {
+        anyhow::ensure!(
+            !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\']),
+            "Invalid file name in store: {:?}",
+            name
+        );
+        Ok(self.root.join("manifests").join(format!("{}.json", name)))
+    }
+}
+
+/// Сжимает фрагмент всеми кодеками и выбирает наименьший результат.
+fn compress_chunk(chunk: &[u8]) -> Result<Vec<u8>> {
+    let mut best = ChunkCodec::Stored.encode(chunk)?;
+    for codec in [ChunkCodec::Huffman, ChunkCodec::Rle] {
+        let encoded = codec.encode(chunk)?;
+        if encoded.len() < best.len() {
+            best = encoded;
+        }
+    }
+    Ok(best)
+}
+
+/// Записывает файл через временный файл, чтобы прерванная запись не оставила поврежденный фрагмент.
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes).with_context(|| format!("Failed to write file: {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write file: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use pseudorandom::{PRNG, XorShift32};
+
+    use super::*;
+
+    fn random_bytes(seed: u32, len: usize) -> Vec<u8> {
+        let mut rng = XorShift32::build(seed).unwrap();
+        (0..len).map(|_| rng.next() as u8).collect()
+    }
+
+    fn temp_store(name: &str) -> ChunkStore {
+        let root = std::env::temp_dir().join(format!("dedup_{}_test", name));
+        let _ = std::fs::remove_dir_all(&root);
+        ChunkStore::open(root).unwrap()
+    }
+
+    #[test]
+    fn test_chunk_sizes() {
+        let chunker = Chunker::new().with_sizes(256, 1024, 4096);
+        let bytes = random_bytes(1, 200_000);
+
+        let chunks = chunker.split(&bytes);
+        assert_eq!(chunks.concat(), bytes);
+        assert!(
+            chunks[..chunks.len() - 1]
+                .iter()
+                .all(|chunk| (256..=4096).contains(&chunk.len()))
+        );
+
+        // Средний размер близок к заданному (граница ищется после минимального размера)
+        let mean = bytes.len() / chunks.len();
+        assert!((1024..=2048).contains(&mean), "mean chunk size {}", mean);
+
+        assert!(chunker.split(&[]).is_empty());
+        assert_eq!(chunker.split(&[7; 100]), vec![&[7; 100][..]]);
+    }
+
+    #[test]
+    fn test_invalid_sizes() {
+        assert!(Chunker::new().try_with_sizes(256, 1000, 4096).is_err());
+        assert!(Chunker::new().try_with_sizes(0, 1024, 4096).is_err());
+        assert!(Chunker::new().try_with_sizes(2048, 1024, 4096).is_err());
+        assert!(Chunker::new().try_with_sizes(256, 1024, 512).is_err());
+        assert_eq!(
+            Chunker::new().try_with_sizes(1024, 1024, 1024).unwrap().avg_size(),
+            1024
+        );
+    }
+
+    #[test]
+    fn test_boundaries_survive_insertion() {
+        let chunker = Chunker::new().with_sizes(256, 1024, 4096);
+        let bytes = random_bytes(2, 100_000);
+        let mut shifted = b"inserted prefix".to_vec();
+        shifted.extend_from_slice(&bytes);
+
+        let original = chunker
+            .split(&bytes)
+            .into_iter()
+            .map(ChunkId::of)
+            .collect::<HashSet<_>>();
+        let shifted = chunker.split(&shifted).into_iter().map(ChunkId::of).collect::<Vec<_>>();
+
+        let shared = shifted.iter().filter(|id| original.contains(id)).count();
+        assert!(
+            shared + 2 >= shifted.len(),
+            "{} of {} chunks shared",
+            shared,
+            shifted.len()
+        );
+    }
+
+    #[test]
+    fn test_chunk_id() {
+        let id = ChunkId::of(b"abc");
+        assert_eq!(
+            id.to_string(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(id.to_string().parse::<ChunkId>().unwrap(), id);
+        assert!("xyz".parse::<ChunkId>().is_err());
+    }
+
+    #[test]
+    fn test_add_restore_and_stats() {
+        let store = temp_store("round_trip").with_chunker(Chunker::new().with_sizes(256, 1024, 4096));
+
+        let base = random_bytes(3, 50_000);
+        let mut edited = base.clone();
+        edited[25_000..25_010].copy_from_slice(b"0123456789");
+        let text = "Lorem ipsum dolor sit amet. ".repeat(500).into_bytes();
+
+        store.add("base.bin", &base).unwrap();
+        store.add("edited.bin", &edited).unwrap();
+        store.add("copy.bin", &base).unwrap();
+        store.add("text.txt", &text).unwrap();
+        store.add("empty", &[]).unwrap();
+
+        assert_eq!(
+            store.names().unwrap(),
+            ["base.bin", "copy.bin", "edited.bin", "empty", "text.txt"]
+        );
+        assert_eq!(store.restore("base.bin").unwrap(), base);
+        assert_eq!(store.restore("edited.bin").unwrap(), edited);
+        assert_eq!(store.restore("text.txt").unwrap(), text);
+        assert!(store.restore("empty").unwrap().is_empty());
+        assert!(store.restore("missing").is_err());
+        assert!(store.add("../escape", &base).is_err());
+
+        let stats = store.stats().unwrap();
+        assert_eq!(stats.files, 5);
+        assert_eq!(stats.logical_size, (3 * base.len() + text.len()) as u64);
+        // Копия не занимает места, а правка добавляет лишь несколько фрагментов
+        assert!(stats.unique_size < (base.len() + base.len() / 4 + text.len()) as u64);
+        assert!(stats.dedup_ratio() > 2.0, "{:?}", stats);
+        // Повторяющийся текст сжимается кодеком
+        assert!(stats.compression_ratio() > stats.dedup_ratio(), "{:?}", stats);
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_chunk_and_garbage_collection() {
+        let store = temp_store("corrupted");
+        let bytes = random_bytes(4, 30_000);
+
+        let manifest = store.add("file", &bytes).unwrap();
+        let chunk_path = store.chunk_path(&manifest.chunks[0].id);
+        let mut encoded = std::fs::read(&chunk_path).unwrap();
+        *encoded.last_mut().unwrap() ^= 1;
+        std::fs::write(&chunk_path, encoded).unwrap();
+        assert!(store.restore("file").is_err());
+
+        // После замены файла старые фрагменты больше не нужны
+        store.add("file", b"new content").unwrap();
+        assert_eq!(store.collect_garbage().unwrap(), manifest.chunks.len());
+        assert_eq!(store.restore("file").unwrap(), b"new content");
+        assert_eq!(store.stats().unwrap().unique_chunks, 1);
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+}
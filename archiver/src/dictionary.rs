@@ -0,0 +1,270 @@
+use anyhow::{Context, Result};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{Decoder, Encoder, HuffmanArchiver, StateSaver, huffman::MAX_CODE_LENGTH};
+
+/// Общий словарь кодов Хаффмана, обученный на наборе типичных файлов.
+///
+/// Для маленьких файлов таблица кодов занимает больше места, чем сами данные. Словарь хранится
+/// отдельно, а архив содержит только его идентификатор - хеш таблицы кодов.
+///
+/// Формат архива совпадает с форматом [`crate::FileEncoder`], но вместо таблицы кодов в качестве
+/// состояния записывается идентификатор словаря (8 байт).
+#[derive(Debug, Clone)]
+pub struct Dictionary {
+    archiver: HuffmanArchiver,
+    id: u64,
+}
+
+impl Dictionary {
+    /// Сигнатура файла словаря.
+    const MAGIC: &[u8; 4] = b"HDIC";
+
+    /// Обучает словарь на файлах-образцах.
+    pub fn train<P: AsRef<Path>>(samples: &[P]) -> Result<Self> {
+        let samples = samples
+            .iter()
+            .map(|path| {
+                let path = path.as_ref();
+                std::fs::read(path).with_context(|| format!("Failed to read sample: {}", path.display()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::train_from_bytes(samples.iter().map(Vec::as_slice)))
+    }
+
+    /// Обучает словарь на образцах данных.
+    ///
+    /// К количеству вхождений каждого из 256 байтов прибавляется единица, поэтому словарем можно
+    /// сжать любые данные, даже содержащие байты, не встретившиеся в образцах.
+    pub fn train_from_bytes<'a>(samples: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let mut counts: HashMap<u8, usize> = (0..=255).map(|word| (word, 1)).collect();
+
+        for sample in samples {
+            for byte in sample {
+                *counts.get_mut(byte).unwrap() += 1;
+            }
+        }
+
+        let codes = HuffmanArchiver::build_length_limited_codes(&counts, MAX_CODE_LENGTH);
+        Self::from_archiver(HuffmanArchiver::from_codes(codes))
+    }
+
+    fn from_archiver(archiver: HuffmanArchiver) -> Self {
+        let id = table_hash(archiver.word_code());
+        Self { archiver, id }
+    }
+
+    /// Идентификатор словаря - хеш FNV-1a таблицы кодов, не зависящий от порядка записей.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut file = BufWriter::new(File::create(path).context("Failed to create dictionary file")?);
+
+        file.write_all(Self::MAGIC)
+            .context("Failed to write dictionary signature")?;
+        let state = self.archiver.clone().save_state()?;
+        HuffmanArchiver::write_state(&state, &mut file)?;
+
+        file.flush().context("Failed to write dictionary")
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut file = BufReader::new(File::open(path).context("Failed to open dictionary file")?);
+
+        let mut magic = [0u8; 4];
+        file.read_exact(&mut magic)
+            .context("Failed to read dictionary signature")?;
+        anyhow::ensure!(&magic == Self::MAGIC, "Not a dictionary file: invalid signature");
+
+        let state = HuffmanArchiver::read_state(&mut file)?;
+        let archiver = HuffmanArchiver::load_state(state).context("Failed to load dictionary")?;
+        Ok(Self::from_archiver(archiver))
+    }
+
+    /// Кодирует `bytes` словарем и записывает архив в `writer`.
+    pub fn encode_to_writer<W: Write>(&self, bytes: &[u8], writer: &mut W) -> Result<()> {
+        let word_code = self.archiver.word_code();
+        if let Some(byte) = bytes.iter().find(|byte| !word_code.contains_key(byte)) {
+            anyhow::bail!("Dictionary {:016x} has no code for byte {}", self.id, byte);
+        }
+
+        let encoded = self.archiver.encode_bytes(bytes)?;
+
+        HuffmanArchiver::write_state(&self.id.to_le_bytes(), writer)?;
+        writer
+            .write_all(&bytes.len().to_le_bytes())
+            .context("Failed to write original size")?;
+        writer.write_all(&encoded).context("Failed to write encoded data")?;
+        Ok(())
+    }
+
+    /// Читает архив, сжатый этим словарем, из `reader` и возвращает исходные данные.
+    pub fn decode_from_reader<R: Read>(&self, reader: &mut R) -> Result<Vec<u8>> {
+        let state = HuffmanArchiver::read_state(reader)?;
+        let id = <[u8; 8]>::try_from(state.as_slice())
+            .map(u64::from_le_bytes)
+            .map_err(|_| anyhow::anyhow!("Archive was not compressed with a dictionary"))?;
+
+        anyhow::ensure!(
+            id == self.id,
+            "Archive was compressed with dictionary {:016x}, but dictionary {:016x} is provided",
+            id,
+            self.id
+        );
+
+        let mut original_size = [0u8; std::mem::size_of::<usize>()];
+        reader
+            .read_exact(&mut original_size)
+            .context("Failed to read original size")?;
+        let original_size = usize::from_le_bytes(original_size);
+
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).context("Failed to read encoded part")?;
+
+        let mut decoded = self.archiver.decode_bytes(&bytes).context("Failed to decode")?;
+        decoded.truncate(original_size);
+        Ok(decoded)
+    }
+
+    /// Архивирует файл словарем.
+    pub fn archive<P: AsRef<Path>>(&self, target: P, destination: P) -> Result<()> {
+        let bytes = std::fs::read(target).context("Failed to read file")?;
+        let mut file = BufWriter::new(File::create(destination).context("Failed to create file")?);
+        self.encode_to_writer(&bytes, &mut file)?;
+        file.flush().context("Failed to write archive")
+    }
+
+    /// Распаковывает архив, сжатый словарем.
+    pub fn extract<P: AsRef<Path>>(&self, target: P, destination: P) -> Result<()> {
+        let mut file = BufReader::new(File::open(target).context("Failed to open archive")?);
+        let decoded = self.decode_from_reader(&mut file)?;
+        std::fs::write(destination, decoded).context("Failed to write to file")
+    }
+}
+
+/// Хеш FNV-1a таблицы кодов, записи которой упорядочены по символу.
+fn table_hash(word_code: &HashMap<u8, String>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut items = word_code.iter().collect::<Vec<_>>();
+    items.sort_by_key(|(word, _)| **word);
+
+    let mut hash = OFFSET_BASIS;
+    for (&word, code) in items {
+        for byte in [word, code.len() as u8].into_iter().chain(code.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CodesBuilder, FileEncoder};
+
+    fn json_samples() -> Vec<Vec<u8>> {
+        (0..100)
+            .map(|i| format!(r#"{{"id":{},"name":"user{}","active":{}}}"#, i, i * 7, i % 2 == 0).into_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_train_and_round_trip() {
+        let dictionary = Dictionary::train_from_bytes(json_samples().iter().map(Vec::as_slice));
+
+        let message = br#"{"id":1000,"name":"user7000","active":true}"#;
+        let mut archive = Vec::new();
+        dictionary.encode_to_writer(message, &mut archive).unwrap();
+
+        // Архив со словарем меньше обычного, так как таблица кодов в нем не хранится
+        let mut own_table_archive = Vec::new();
+        let mut freq_map = crate::FrequencyMap::new();
+        freq_map.consume(message);
+        HuffmanArchiver::new(freq_map.build())
+            .encode_to_writer(message, &mut own_table_archive)
+            .unwrap();
+        assert!(
+            archive.len() < own_table_archive.len() / 2,
+            "{} >= {}",
+            archive.len(),
+            own_table_archive.len() / 2
+        );
+        assert_eq!(dictionary.decode_from_reader(&mut archive.as_slice()).unwrap(), message);
+
+        // Байты, не встречавшиеся в образцах, тоже кодируются
+        let unseen = (0..=255).collect::<Vec<u8>>();
+        let mut archive = Vec::new();
+        dictionary.encode_to_writer(&unseen, &mut archive).unwrap();
+        assert_eq!(dictionary.decode_from_reader(&mut archive.as_slice()).unwrap(), unseen);
+    }
+
+    #[test]
+    fn test_dictionary_mismatch() {
+        let dictionary = Dictionary::train_from_bytes(json_samples().iter().map(Vec::as_slice));
+        let other = Dictionary::train_from_bytes([b"aaaaaaaaaaaaaaaaaaaab".as_slice()]);
+        assert_ne!(dictionary.id(), other.id());
+
+        let mut archive = Vec::new();
+        dictionary.encode_to_writer(b"{}", &mut archive).unwrap();
+
+        let err = other.decode_from_reader(&mut archive.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("dictionary"), "{}", err);
+    }
+
+    #[test]
+    fn test_codes_are_length_limited() {
+        // Количества байтов - числа Фибоначчи: без ограничения самый редкий байт получает код длиннее 16 бит
+        let mut fibonacci = vec![1, 1];
+        while fibonacci.len() < 26 {
+            fibonacci.push(fibonacci[fibonacci.len() - 1] + fibonacci[fibonacci.len() - 2]);
+        }
+        let sample = fibonacci
+            .iter()
+            .enumerate()
+            .flat_map(|(byte, &count)| std::iter::repeat_n(byte as u8, count))
+            .collect::<Vec<_>>();
+
+        let counts = (0..256).map(|byte| fibonacci.get(byte).unwrap_or(&0) + 1);
+        let unlimited =
+            HuffmanArchiver::build_optimal_codes((0..=255u8).collect(), counts.map(|count| count as f64).collect());
+        assert!(unlimited.codes().iter().any(|code| code.len() > MAX_CODE_LENGTH));
+
+        let dictionary = Dictionary::train_from_bytes([sample.as_slice()]);
+
+        assert!(
+            dictionary
+                .archiver
+                .word_code()
+                .values()
+                .all(|code| code.len() <= MAX_CODE_LENGTH)
+        );
+        assert_eq!(dictionary.archiver.word_code().len(), 256);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dictionary = Dictionary::train_from_bytes(json_samples().iter().map(Vec::as_slice));
+
+        let filename = "test_dictionary_save_and_load.hdic";
+        std::fs::remove_file(filename).ok();
+
+        dictionary.save(filename).unwrap();
+        let loaded = Dictionary::load(filename).unwrap();
+
+        assert_eq!(loaded.id(), dictionary.id());
+        assert_eq!(loaded.archiver.word_code(), dictionary.archiver.word_code());
+
+        std::fs::remove_file(filename).unwrap();
+    }
+}
@@ -10,13 +10,14 @@ use crate::StateSaver;
 
 /// Интерфейс для кодирования последовательности байтов в строку. Не потоковый!
 pub trait Encoder {
-    /// Преобразует целевую последовательность байтов в закодированную строку.
-    fn convert_to_string(&self, bytes: &[u8]) -> String;
+    /// Преобразует целевую последовательность байтов в закодированную строку. Возвращает ошибку,
+    /// если для слова нет кода.
+    fn convert_to_string(&self, bytes: &[u8]) -> Result<String>;
 
     /// Кодирует последовательность байтов.
-    fn encode_bytes(&self, bytes: &[u8]) -> Vec<u8> {
-        let bit_string = self.convert_to_string(bytes);
-        Self::convert_to_bytes(bit_string)
+    fn encode_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        let bit_string = self.convert_to_string(bytes)?;
+        Ok(Self::convert_to_bytes(bit_string))
     }
 
     fn convert_to_bytes(mut bit_string: String) -> Vec<u8> {
@@ -48,7 +49,7 @@ pub trait Encoder {
         let mut buf = Vec::with_capacity(size as usize);
         file.read_to_end(&mut buf).expect("Failed to read file");
 
-        Ok(self.encode_bytes(&buf))
+        self.encode_bytes(&buf)
     }
 }
 
@@ -59,22 +60,24 @@ where
     Self: Encoder + StateSaver + Sized,
 {
     fn encode_file(self, target: &PathBuf, destination: &PathBuf) -> Result<()> {
-        // Размер исходного файла (в байтах) для корректного удаления паддинга при декодировании
-        let original_size = std::fs::File::open(target)
-            .and_then(|f| f.metadata())
-            .map(|m| m.len() as usize)
-            .context("Failed to extract file metadata")?;
+        let bytes = std::fs::read(target).context("Failed to read file")?;
+        let mut file = File::create(destination).context("Failed to create file")?;
+        self.encode_to_writer(&bytes, &mut file)
+    }
 
-        let encoded_file = Encoder::encode_file(&self, target);
+    /// Кодирует `bytes` и записывает архив в `writer`: размер состояния, состояние, размер исходных
+    /// данных и закодированные данные.
+    fn encode_to_writer<W: Write>(self, bytes: &[u8], writer: &mut W) -> Result<()> {
+        let encoded = self.encode_bytes(bytes)?;
         let state = self.save_state()?;
-        let mut file = File::create(destination).context("Failed to create file")?;
 
         // Записываем размер состояния, состояние и сжатый файл
-        Self::write_state(&state, &mut file)?;
-        // Записываем размер исходного файла (usize)
-        file.write_all(&original_size.to_le_bytes())
+        Self::write_state(&state, writer)?;
+        // Записываем размер исходного файла (usize) для корректного удаления паддинга при декодировании
+        writer
+            .write_all(&bytes.len().to_le_bytes())
             .context("Failed to write original size")?;
-        file.write_all(&encoded_file?).context("Failed to write encoded file")?;
+        writer.write_all(&encoded).context("Failed to write encoded file")?;
         Ok(())
     }
 }
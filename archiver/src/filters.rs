@@ -0,0 +1,565 @@
+use anyhow::{Context, Result};
+use std::{
+    fmt,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{Decoder, Encoder, FileEncoder, FrequencyMap, HuffmanArchiver, StateSaver, huffman::MAX_CODE_LENGTH};
+
+/// Обратимый фильтр, применяемый к данным перед кодированием.
+///
+/// Фильтр заменяет каждый байт разностью с предсказанием по уже обработанным байтам. Для плавно
+/// меняющихся сигналов, таблиц чисел и изображений разности близки к нулю, поэтому их энтропия
+/// нулевого порядка меньше энтропии исходных байтов.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    None,
+    /// Разность с байтом на `stride` позиций раньше, например `stride = 2` для 16-битных отсчетов.
+    Delta {
+        stride: usize,
+    },
+    /// Фильтр PNG Sub: разность с соответствующим байтом левого пикселя той же строки.
+    Sub {
+        row_len: usize,
+        bytes_per_pixel: usize,
+    },
+    /// Фильтр PNG Up: разность с байтом строки выше.
+    Up {
+        row_len: usize,
+    },
+    /// Фильтр PNG Paeth: разность с тем из левого, верхнего и левого верхнего байтов, который ближе
+    /// к линейному предсказанию `left + up - up_left`.
+    Paeth {
+        row_len: usize,
+        bytes_per_pixel: usize,
+    },
+    /// Исключающее ИЛИ с предыдущим числом с плавающей точкой размером `width` (4 или 8) байтов.
+    XorFloat {
+        width: usize,
+    },
+}
+
+impl Filter {
+    /// Фильтры, перебираемые в автоматическом режиме. Фильтры изображений требуют длину строки,
+    /// поэтому в список не входят.
+    pub const AUTO_CANDIDATES: [Filter; 7] = [
+        Filter::None,
+        Filter::Delta { stride: 1 },
+        Filter::Delta { stride: 2 },
+        Filter::Delta { stride: 4 },
+        Filter::Delta { stride: 8 },
+        Filter::XorFloat { width: 4 },
+        Filter::XorFloat { width: 8 },
+    ];
+
+    /// Максимальная длина строки изображения для фильтров Sub, Up и Paeth.
+    pub const MAX_ROW_LEN: usize = 1 << 30;
+
+    pub fn validate(&self) -> Result<()> {
+        if let Filter::Sub { row_len, .. } | Filter::Up { row_len } | Filter::Paeth { row_len, .. } = *self {
+            anyhow::ensure!(
+                row_len <= Self::MAX_ROW_LEN,
+                "Row length must not exceed {}, actual: {}",
+                Self::MAX_ROW_LEN,
+                row_len
+            );
+        }
+
+        match *self {
+            Filter::None => {}
+            Filter::Delta { stride } => anyhow::ensure!(stride > 0, "Delta stride must be positive"),
+            Filter::Sub {
+                row_len,
+                bytes_per_pixel,
+            }
+            | Filter::Paeth {
+                row_len,
+                bytes_per_pixel,
+            } => anyhow::ensure!(
+                bytes_per_pixel > 0 && row_len >= bytes_per_pixel,
+                "Invalid image layout: row length {}, bytes per pixel {}",
+                row_len,
+                bytes_per_pixel
+            ),
+            Filter::Up { row_len } => anyhow::ensure!(row_len > 0, "Row length must be positive"),
+            Filter::XorFloat { width } => {
+                anyhow::ensure!(
+                    width == 4 || width == 8,
+                    "Float width must be 4 or 8, actual: {}",
+                    width
+                )
+            }
+        }
+        Ok(())
+    }
+
+    /// Применяет фильтр.
+    pub fn apply(&self, bytes: &[u8]) -> Vec<u8> {
+        (0..bytes.len())
+            .map(|i| match self {
+                Filter::XorFloat { .. } => bytes[i] ^ self.predict(bytes, i),
+                _ => bytes[i].wrapping_sub(self.predict(bytes, i)),
+            })
+            .collect()
+    }
+
+    /// Восстанавливает исходные данные. Предсказание зависит только от предыдущих байтов, поэтому
+    /// они восстанавливаются по порядку.
+    pub fn reverse(&self, filtered: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(filtered.len());
+        for (i, &value) in filtered.iter().enumerate() {
+            let prediction = self.predict(&bytes, i);
+            bytes.push(match self {
+                Filter::XorFloat { .. } => value ^ prediction,
+                _ => value.wrapping_add(prediction),
+            });
+        }
+        bytes
+    }
+
+    /// Предсказание `i`-го байта по байтам `bytes[..i]`.
+    fn predict(&self, bytes: &[u8], i: usize) -> u8 {
+        let back = |distance: usize| if i >= distance { bytes[i - distance] } else { 0 };
+
+        match *self {
+            Filter::None => 0,
+            Filter::Delta { stride } => back(stride),
+            Filter::XorFloat { width } => back(width),
+            Filter::Up { row_len } => back(row_len),
+            Filter::Sub {
+                row_len,
+                bytes_per_pixel,
+            } => {
+                if i % row_len >= bytes_per_pixel {
+                    back(bytes_per_pixel)
+                } else {
+                    0
+                }
+            }
+            Filter::Paeth {
+                row_len,
+                bytes_per_pixel,
+            } => {
+                let has_left = i % row_len >= bytes_per_pixel;
+                let left = if has_left { back(bytes_per_pixel) } else { 0 };
+                let up = back(row_len);
+                let up_left = if has_left {
+                    back(row_len.saturating_add(bytes_per_pixel))
+                } else {
+                    0
+                };
+                paeth_predictor(left, up, up_left)
+            }
+        }
+    }
+
+    /// Выбирает из `candidates` фильтр, после которого энтропия нулевого порядка минимальна.
+    pub fn choose_best(bytes: &[u8], candidates: &[Filter]) -> Filter {
+        candidates
+            .iter()
+            .map(|filter| {
+                let mut freq_map = FrequencyMap::new();
+                freq_map.consume(&filter.apply(bytes));
+                (*filter, freq_map.entropy())
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(filter, _)| filter)
+            .unwrap_or(Filter::None)
+    }
+
+    /// Длина сохраненного фильтра: тег и два параметра по 8 байт.
+    const STATE_LEN: usize = 17;
+
+    fn to_bytes(self) -> [u8; Self::STATE_LEN] {
+        let (tag, first, second) = match self {
+            Filter::None => (0, 0, 0),
+            Filter::Delta { stride } => (1, stride, 0),
+            Filter::Sub {
+                row_len,
+                bytes_per_pixel,
+            } => (2, row_len, bytes_per_pixel),
+            Filter::Up { row_len } => (3, row_len, 0),
+            Filter::Paeth {
+                row_len,
+                bytes_per_pixel,
+            } => (4, row_len, bytes_per_pixel),
+            Filter::XorFloat { width } => (5, width, 0),
+        };
+
+        let mut bytes = [0u8; Self::STATE_LEN];
+        bytes[0] = tag;
+        bytes[1..9].copy_from_slice(&(first as u64).to_le_bytes());
+        bytes[9..17].copy_from_slice(&(second as u64).to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        anyhow::ensure!(
+            bytes.len() == Self::STATE_LEN,
+            "Filter state must be {} bytes long, actual: {}",
+            Self::STATE_LEN,
+            bytes.len()
+        );
+
+        let param = |range: std::ops::Range<usize>| {
+            let value = u64::from_le_bytes(bytes[range].try_into().unwrap());
+            usize::try_from(value).with_context(|| format!("Filter parameter {} does not fit into usize", value))
+        };
+        let first = param(1..9)?;
+        let second = param(9..17)?;
+
+        let filter = match bytes[0] {
+            0 => Filter::None,
+            1 => Filter::Delta { stride: first },
+            2 => Filter::Sub {
+                row_len: first,
+                bytes_per_pixel: second,
+            },
+            3 => Filter::Up { row_len: first },
+            4 => Filter::Paeth {
+                row_len: first,
+                bytes_per_pixel: second,
+            },
+            5 => Filter::XorFloat { width: first },
+            tag => anyhow::bail!("Unknown filter: {}", tag),
+        };
+
+        filter.validate()?;
+        Ok(filter)
+    }
+}
+
+/// Предсказатель Паэта из спецификации PNG.
+fn paeth_predictor(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance_left = (estimate - left as i16).abs();
+    let distance_up = (estimate - up as i16).abs();
+    let distance_up_left = (estimate - up_left as i16).abs();
+
+    if distance_left <= distance_up && distance_left <= distance_up_left {
+        left
+    } else if distance_up <= distance_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// Разбирает фильтр из строки: `none`, `delta:STRIDE`, `sub:ROW_LEN:BPP`, `up:ROW_LEN`,
+/// `paeth:ROW_LEN:BPP` или `xor-float:WIDTH`.
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split(':');
+        let name = parts.next().unwrap_or_default();
+        let params = parts
+            .map(|param| {
+                param
+                    .parse::<usize>()
+                    .with_context(|| format!("Invalid filter parameter '{}'", param))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let filter = match (name, params.as_slice()) {
+            ("none", []) => Filter::None,
+            ("delta", [stride]) => Filter::Delta { stride: *stride },
+            ("sub", [row_len, bytes_per_pixel]) => Filter::Sub {
+                row_len: *row_len,
+                bytes_per_pixel: *bytes_per_pixel,
+            },
+            ("up", [row_len]) => Filter::Up { row_len: *row_len },
+            ("paeth", [row_len, bytes_per_pixel]) => Filter::Paeth {
+                row_len: *row_len,
+                bytes_per_pixel: *bytes_per_pixel,
+            },
+            ("xor-float", [width]) => Filter::XorFloat { width: *width },
+            _ => anyhow::bail!(
+                "Unknown filter '{}', expected none, delta:STRIDE, sub:ROW_LEN:BPP, up:ROW_LEN, \
+                paeth:ROW_LEN:BPP or xor-float:WIDTH",
+                s
+            ),
+        };
+
+        filter.validate()?;
+        Ok(filter)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::None => write!(f, "none"),
+            Filter::Delta { stride } => write!(f, "delta:{}", stride),
+            Filter::Sub {
+                row_len,
+                bytes_per_pixel,
+            } => write!(f, "sub:{}:{}", row_len, bytes_per_pixel),
+            Filter::Up { row_len } => write!(f, "up:{}", row_len),
+            Filter::Paeth {
+                row_len,
+                bytes_per_pixel,
+            } => write!(f, "paeth:{}:{}", row_len, bytes_per_pixel),
+            Filter::XorFloat { width } => write!(f, "xor-float:{}", width),
+        }
+    }
+}
+
+/// Сигнатура в начале состояния [`FilteredArchiver`], по которой распаковщик узнает отфильтрованный архив.
+const MAGIC: &[u8; 4] = b"HFLT";
+
+/// Архиватор Хаффмана с предварительной фильтрацией данных.
+///
+/// Коды строятся по частотам отфильтрованных байтов. Состояние начинается с сигнатуры [`MAGIC`],
+/// за ней следуют фильтр и таблица кодов.
+#[derive(Debug, Clone)]
+pub struct FilteredArchiver {
+    filter: Filter,
+    archiver: HuffmanArchiver,
+}
+
+impl FilteredArchiver {
+    /// Строит коды по данным `bytes`, которые будут закодированы с фильтром `filter`.
+    /// Возвращает ошибку, если параметры фильтра некорректны, см. [`Filter::validate`].
+    pub fn new(filter: Filter, bytes: &[u8]) -> Result<Self> {
+        filter.validate()?;
+        Ok(Self::with_valid_filter(filter, bytes))
+    }
+
+    fn with_valid_filter(filter: Filter, bytes: &[u8]) -> Self {
+        let mut freq_map = FrequencyMap::new();
+        freq_map.consume(&filter.apply(bytes));
+
+        let codes = if freq_map.counts().is_empty() {
+            Default::default()
+        } else {
+            HuffmanArchiver::build_length_limited_codes(freq_map.counts(), MAX_CODE_LENGTH)
+        };
+
+        Self {
+            filter,
+            archiver: HuffmanArchiver::from_codes(codes),
+        }
+    }
+
+    /// Выбирает фильтр из [`Filter::AUTO_CANDIDATES`] с минимальной энтропией отфильтрованных данных.
+    pub fn auto(bytes: &[u8]) -> Self {
+        Self::with_valid_filter(Filter::choose_best(bytes, &Filter::AUTO_CANDIDATES), bytes)
+    }
+
+    /// Проверяет, что архив записан [`FilteredArchiver`], не изменяя позицию чтения.
+    pub fn is_filtered_archive<R: Read + Seek>(reader: &mut R) -> Result<bool> {
+        let position = reader.stream_position().context("Failed to get position")?;
+
+        // Состояние записывается после своего размера
+        let mut header = [0u8; std::mem::size_of::<usize>() + MAGIC.len()];
+        let is_filtered = reader.read_exact(&mut header).is_ok() && &header[std::mem::size_of::<usize>()..] == MAGIC;
+
+        reader
+            .seek(SeekFrom::Start(position))
+            .context("Failed to restore position")?;
+        Ok(is_filtered)
+    }
+
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// Архивирует файл с фильтром `filter`, а если он не указан - с автоматически выбранным.
+    pub fn archive<P: AsRef<Path>>(filter: Option<Filter>, target: P, destination: P) -> Result<Filter> {
+        let bytes = std::fs::read(target).context("Failed to read file")?;
+
+        let archiver = match filter {
+            Some(filter) => Self::new(filter, &bytes)?,
+            None => Self::auto(&bytes),
+        };
+        let filter = archiver.filter;
+
+        let mut file = File::create(destination).context("Failed to create file")?;
+        archiver.encode_to_writer(&bytes, &mut file)?;
+        Ok(filter)
+    }
+}
+
+impl Encoder for FilteredArchiver {
+    fn convert_to_string(&self, bytes: &[u8]) -> Result<String> {
+        self.archiver.convert_to_string(&self.filter.apply(bytes))
+    }
+}
+
+impl Decoder for FilteredArchiver {
+    fn decode_string(&self, bit_string: &str) -> Result<Vec<u8>> {
+        // Лишние байты из-за паддинга оказываются в конце и не влияют на восстановление предыдущих
+        let filtered = self.archiver.decode_string(bit_string)?;
+        Ok(self.filter.reverse(&filtered))
+    }
+}
+
+impl StateSaver for FilteredArchiver {
+    fn save_state(self) -> Result<Vec<u8>> {
+        let mut state = MAGIC.to_vec();
+        state.extend(self.filter.to_bytes());
+        state.extend(self.archiver.save_state()?);
+        Ok(state)
+    }
+
+    fn load_state(mut state: Vec<u8>) -> Result<Self> {
+        anyhow::ensure!(state.starts_with(MAGIC), "Not a filtered archive: invalid signature");
+        let header_len = MAGIC.len() + Filter::STATE_LEN;
+        anyhow::ensure!(state.len() >= header_len, "State is too short to contain a filter");
+
+        let archiver_state = state.split_off(header_len);
+        Ok(Self {
+            filter: Filter::from_bytes(&state[MAGIC.len()..])?,
+            archiver: HuffmanArchiver::load_state(archiver_state)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileDecoder;
+
+    fn all_filters() -> Vec<Filter> {
+        vec![
+            Filter::None,
+            Filter::Delta { stride: 1 },
+            Filter::Delta { stride: 3 },
+            Filter::Sub {
+                row_len: 12,
+                bytes_per_pixel: 3,
+            },
+            Filter::Up { row_len: 12 },
+            Filter::Paeth {
+                row_len: 12,
+                bytes_per_pixel: 3,
+            },
+            Filter::XorFloat { width: 4 },
+            Filter::XorFloat { width: 8 },
+        ]
+    }
+
+    #[test]
+    fn test_filters_are_reversible() {
+        // Последняя строка изображения неполная
+        let bytes = (0..100u32)
+            .map(|i| (i * 37 % 256) as u8 ^ (i / 7) as u8)
+            .collect::<Vec<_>>();
+
+        for filter in all_filters() {
+            let filtered = filter.apply(&bytes);
+            assert_eq!(filter.reverse(&filtered), bytes, "{}", filter);
+
+            // Сохранение и разбор фильтра
+            assert_eq!(Filter::from_bytes(&filter.to_bytes()).unwrap(), filter);
+            assert_eq!(filter.to_string().parse::<Filter>().unwrap(), filter);
+        }
+    }
+
+    #[test]
+    fn test_known_values() {
+        assert_eq!(Filter::Delta { stride: 1 }.apply(&[10, 12, 15, 11]), [10, 2, 3, 252]);
+        assert_eq!(Filter::Up { row_len: 2 }.apply(&[1, 2, 4, 6]), [1, 2, 3, 4]);
+        assert_eq!(
+            Filter::Sub {
+                row_len: 2,
+                bytes_per_pixel: 1
+            }
+            .apply(&[1, 2, 4, 6]),
+            [1, 1, 4, 2]
+        );
+
+        // left = 4, up = 2, up_left = 1: оценка 5 ближе всего к left
+        assert_eq!(paeth_predictor(4, 2, 1), 4);
+        assert_eq!(paeth_predictor(1, 2, 4), 1);
+        assert_eq!(paeth_predictor(3, 9, 1), 9);
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!("delta:2".parse::<Filter>().unwrap(), Filter::Delta { stride: 2 });
+        assert!("delta:0".parse::<Filter>().is_err());
+        assert!("xor-float:3".parse::<Filter>().is_err());
+        assert!("sub:2:3".parse::<Filter>().is_err());
+        assert!(format!("paeth:{}:1", usize::MAX).parse::<Filter>().is_err());
+        assert!("median".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn test_huge_row_len() {
+        // Предсказание не переполняется даже для фильтра, созданного без проверки
+        let filter = Filter::Paeth {
+            row_len: usize::MAX,
+            bytes_per_pixel: 1,
+        };
+        let bytes = [1, 2, 3, 4];
+        assert_eq!(filter.reverse(&filter.apply(&bytes)), bytes);
+
+        // Такой фильтр в состоянии архива отвергается при загрузке
+        let mut state = MAGIC.to_vec();
+        state.extend(filter.to_bytes());
+        state.extend(HuffmanArchiver::new(FrequencyMap::new().build()).save_state().unwrap());
+        let err = FilteredArchiver::load_state(state).unwrap_err();
+        assert!(err.to_string().contains("Row length"), "{}", err);
+    }
+
+    #[test]
+    fn test_auto_filter() {
+        // Линейно растущие 16-битные отсчеты: разность младших байтов через 2 байта постоянна
+        let signal = (0..2000u16).flat_map(|i| (i * 3).to_le_bytes()).collect::<Vec<_>>();
+        assert_eq!(FilteredArchiver::auto(&signal).filter(), Filter::Delta { stride: 2 });
+
+        // Медленно меняющиеся числа с плавающей точкой: старшие байты совпадают
+        let floats = (0..1000)
+            .flat_map(|i| (1000.0 + i as f64 * 1e-9).to_le_bytes())
+            .collect::<Vec<_>>();
+        let filter = Filter::choose_best(&floats, &Filter::AUTO_CANDIDATES);
+        assert_ne!(filter, Filter::None);
+    }
+
+    #[test]
+    fn test_encode_and_decode() {
+        let bytes = (0..3000u32).map(|i| (i / 3) as u8).collect::<Vec<_>>();
+
+        for filter in all_filters() {
+            let archiver = FilteredArchiver::new(filter, &bytes).unwrap();
+            let encoded = archiver.encode_bytes(&bytes).unwrap();
+
+            let restored = FilteredArchiver::load_state(archiver.save_state().unwrap()).unwrap();
+            let mut decoded = restored.decode_bytes(&encoded).unwrap();
+            decoded.truncate(bytes.len());
+
+            assert_eq!(decoded, bytes, "{}", filter);
+        }
+
+        assert!(FilteredArchiver::new(Filter::Delta { stride: 0 }, &bytes).is_err());
+    }
+
+    #[test]
+    fn test_archive_is_detected() {
+        let target = std::env::temp_dir().join("filtered_detect_test.raw");
+        let archived = target.with_extension("huff");
+        let plain = target.with_extension("plain");
+        let bytes = (0..2000u16).flat_map(|i| (i * 3).to_le_bytes()).collect::<Vec<_>>();
+        std::fs::write(&target, &bytes).unwrap();
+
+        FilteredArchiver::archive(None, &target, &archived).unwrap();
+        HuffmanArchiver::archive(&target, &plain).unwrap();
+
+        let mut file = std::fs::File::open(&archived).unwrap();
+        assert!(FilteredArchiver::is_filtered_archive(&mut file).unwrap());
+        assert_eq!(file.stream_position().unwrap(), 0);
+        assert!(!FilteredArchiver::is_filtered_archive(&mut std::fs::File::open(&plain).unwrap()).unwrap());
+
+        // Обычный архив не распаковывается как отфильтрованный и наоборот
+        assert!(FilteredArchiver::decode_from_reader(&mut std::fs::File::open(&plain).unwrap()).is_err());
+        assert!(HuffmanArchiver::decode_from_reader(&mut std::fs::File::open(&archived).unwrap()).is_err());
+        for path in [target, archived, plain] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
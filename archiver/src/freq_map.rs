@@ -31,4 +31,24 @@ impl FrequencyMap {
             }
         }
     }
+
+    /// Количество вхождений каждого байта.
+    pub fn counts(&self) -> &HashMap<u8, usize> {
+        &self.hashmap
+    }
+
+    /// Энтропия нулевого порядка в битах на символ: `-Σ(pi * log2(pi))`.
+    pub fn entropy(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        let total = self.total as f64;
+        -self
+            .hashmap
+            .values()
+            .map(|&count| count as f64 / total)
+            .map(|p| p * p.log2())
+            .sum::<f64>()
+    }
 }
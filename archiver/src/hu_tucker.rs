@@ -0,0 +1,247 @@
+use super::{CodeTree, Codes, CodesBuilder, Word};
+
+/// Оптимальный алфавитный код: коды символов идут в том же лексикографическом порядке, что и сами символы,
+/// поэтому закодированные ключи отсортированного словаря можно сравнивать без декодирования.
+///
+/// Длины кодов совпадают с алгоритмом Ху-Таккера, но вычисляются более простым алгоритмом Гарсиа-Уокса.
+/// Средняя длина такого кода не меньше, чем у кода Хаффмана, и превышает ее не более чем на 2 бита.
+#[derive(Debug, Default)]
+pub struct HuTuckerEncoder {}
+
+impl HuTuckerEncoder {
+    pub fn new() -> Self {
+        HuTuckerEncoder {}
+    }
+
+    /// Строит дерево алфавитного кода для визуализации: листья слева направо идут в порядке возрастания символов.
+    /// Для пустого набора вероятностей возвращает `None`.
+    pub fn build_code_tree<W: Word>(words: Vec<W>, probabilities: Vec<f64>) -> Option<CodeTree<W>> {
+        if probabilities.is_empty() {
+            return None;
+        }
+
+        let codes = Self::build_optimal_codes(words, probabilities);
+        Some(build_prefix_tree(
+            codes.words(),
+            codes.probabilities(),
+            codes.codes(),
+            0,
+        ))
+    }
+}
+
+impl CodesBuilder for HuTuckerEncoder {
+    fn build_optimal_codes<W: Word>(words: Vec<W>, probabilities: Vec<f64>) -> Codes<W> {
+        match probabilities.len() {
+            0 => return Codes::default(),
+            1 => return Codes::new(words, probabilities, vec!["0".into()]),
+            _ => {}
+        }
+
+        // Порядок символов, который должен сохранить код
+        let mut word_probability = words.into_iter().zip(probabilities).collect::<Vec<_>>();
+        word_probability.sort_by_key(|&(word, _)| word.into());
+        let (words, probabilities): (Vec<_>, Vec<_>) = word_probability.into_iter().unzip();
+
+        let lengths = garsia_wachs_lengths(&probabilities);
+        let codes = alphabetic_codes(&lengths);
+        Codes::new(words, probabilities, codes)
+    }
+}
+
+/// Вычисляет длины кодов оптимального алфавитного дерева алгоритмом Гарсиа-Уокса.
+///
+/// Сначала строится (не алфавитное) дерево: находится самая левая пара соседей `(i - 1, i)`,
+/// для которой `w[i - 1] <= w[i + 1]`, пара объединяется, а новый узел сдвигается влево за все меньшие веса.
+/// Глубины листьев этого дерева совпадают с глубинами в оптимальном алфавитном дереве.
+fn garsia_wachs_lengths(weights: &[f64]) -> Vec<usize> {
+    let mut lengths = vec![0; weights.len()];
+    // Вес узла и листья его поддерева
+    let mut nodes = weights
+        .iter()
+        .enumerate()
+        .map(|(i, &weight)| (weight, vec![i]))
+        .collect::<Vec<_>>();
+
+    while nodes.len() > 1 {
+        // За последним узлом стоит бесконечный вес, поэтому пара всегда найдется
+        let i = (1..nodes.len())
+            .find(|&i| nodes.get(i + 1).is_none_or(|(next, _)| nodes[i - 1].0 <= *next))
+            .unwrap();
+
+        let (right_weight, right) = nodes.remove(i);
+        let (left_weight, mut leaves) = nodes.remove(i - 1);
+        leaves.extend(right);
+        for &leaf in &leaves {
+            lengths[leaf] += 1;
+        }
+
+        let weight = left_weight + right_weight;
+        let position = nodes[..i - 1]
+            .iter()
+            .rposition(|(other, _)| *other >= weight)
+            .map_or(0, |j| j + 1);
+        nodes.insert(position, (weight, leaves));
+    }
+
+    lengths
+}
+
+/// Назначает коды по длинам слева направо: каждый следующий код - предыдущий, увеличенный на единицу
+/// и дополненный нулями (или укороченный) до нужной длины. Длины должны задавать полное алфавитное дерево.
+fn alphabetic_codes(lengths: &[usize]) -> Vec<String> {
+    let mut codes = Vec::with_capacity(lengths.len());
+    let mut code: Vec<u8> = Vec::new();
+
+    for (i, &length) in lengths.iter().enumerate() {
+        if i > 0 {
+            // Двоичное сложение с единицей
+            while code.last() == Some(&1) {
+                code.pop();
+            }
+            if let Some(last) = code.last_mut() {
+                *last = 1;
+            }
+        }
+        code.resize(length, 0);
+        codes.push(code.iter().map(|&bit| char::from(b'0' + bit)).collect());
+    }
+
+    codes
+}
+
+/// Восстанавливает дерево по кодам, упорядоченным лексикографически.
+fn build_prefix_tree<W: Word>(words: &[W], probabilities: &[f64], codes: &[String], depth: usize) -> CodeTree<W> {
+    if codes.len() == 1 {
+        return CodeTree::Leaf {
+            probability: probabilities[0],
+            word: words[0],
+            code: codes[0].clone(),
+        };
+    }
+
+    let mid = codes.partition_point(|code| code.as_bytes()[depth] == b'0');
+    CodeTree::Node {
+        probability: probabilities.iter().sum(),
+        left: Box::new(build_prefix_tree(
+            &words[..mid],
+            &probabilities[..mid],
+            &codes[..mid],
+            depth + 1,
+        )),
+        right: Box::new(build_prefix_tree(
+            &words[mid..],
+            &probabilities[mid..],
+            &codes[mid..],
+            depth + 1,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pseudorandom::{PRNG, XorShift32};
+
+    use super::*;
+    use crate::HuffmanArchiver;
+
+    fn build_optimal_codes_test_helper(expected: Vec<&str>, probabilities: Vec<f64>) {
+        let words = (0..probabilities.len() as u8).collect();
+        assert_eq!(
+            expected,
+            HuTuckerEncoder::build_optimal_codes(words, probabilities).codes()
+        );
+    }
+
+    /// Стоимость оптимального алфавитного дерева динамическим программированием за `O(n^3)`.
+    fn optimal_alphabetic_cost(weights: &[f64]) -> f64 {
+        let n = weights.len();
+        let mut cost = vec![vec![0.0; n]; n];
+        for width in 1..n {
+            for i in 0..n - width {
+                let j = i + width;
+                let sum: f64 = weights[i..=j].iter().sum();
+                cost[i][j] = (i..j)
+                    .map(|k| cost[i][k] + cost[k + 1][j])
+                    .fold(f64::INFINITY, f64::min)
+                    + sum;
+            }
+        }
+        cost[0][n - 1]
+    }
+
+    #[test]
+    fn test_build_optimal_codes() {
+        build_optimal_codes_test_helper(vec!["00", "01", "10", "11"], vec![0.25, 0.25, 0.25, 0.25]);
+        build_optimal_codes_test_helper(vec!["0", "10", "110", "111"], vec![0.5, 0.25, 0.125, 0.125]);
+        build_optimal_codes_test_helper(vec!["000", "001", "01", "1"], vec![0.125, 0.125, 0.25, 0.5]);
+        build_optimal_codes_test_helper(vec!["0"], vec![1.0]);
+    }
+
+    #[test]
+    fn test_order_costs_more_than_huffman() {
+        // Частые символы в середине алфавита нельзя поднять к корню, не нарушив порядок
+        let probabilities = vec![0.1, 0.4, 0.4, 0.1];
+        let words = vec![1u8, 2, 3, 4];
+
+        let alphabetic = HuTuckerEncoder::build_optimal_codes(words.clone(), probabilities.clone());
+        let huffman = HuffmanArchiver::build_optimal_codes(words, probabilities);
+
+        assert_eq!(alphabetic.codes(), ["00", "01", "10", "11"]);
+        assert!((alphabetic.mean_code_length() - 2.0).abs() < 1e-9);
+        assert!((huffman.mean_code_length() - 1.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_random_probabilities() {
+        let mut rng = XorShift32::build(0xA1FA).unwrap();
+
+        for n in 2..=16 {
+            for _ in 0..20 {
+                let weights = (0..n).map(|_| (rng.next() % 100 + 1) as f64).collect::<Vec<_>>();
+                let total: f64 = weights.iter().sum();
+                let probabilities = weights.iter().map(|w| w / total).collect::<Vec<_>>();
+                // Символы перемешаны, код должен упорядочить их сам
+                let words = (0..n as u8).rev().collect::<Vec<_>>();
+
+                let codes = HuTuckerEncoder::build_optimal_codes(words, probabilities.clone());
+                assert!(codes.words().is_sorted());
+                assert!(codes.codes().is_sorted());
+
+                let mut sorted_probabilities = probabilities.clone();
+                sorted_probabilities.reverse();
+                assert!((codes.mean_code_length() - optimal_alphabetic_cost(&sorted_probabilities)).abs() < 1e-9);
+
+                let huffman = HuffmanArchiver::build_optimal_codes(vec![0u8; n], probabilities);
+                assert!(codes.mean_code_length() + 1e-9 >= huffman.mean_code_length());
+                assert!(codes.mean_code_length() <= huffman.mean_code_length() + 2.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_code_tree() {
+        let tree = HuTuckerEncoder::build_code_tree(vec![3u8, 1, 2], vec![0.5, 0.25, 0.25]).unwrap();
+
+        let leaf = |probability, word, code: &str| {
+            Box::new(CodeTree::Leaf {
+                probability,
+                word,
+                code: code.into(),
+            })
+        };
+
+        assert_eq!(
+            tree,
+            CodeTree::Node {
+                probability: 1.0,
+                left: Box::new(CodeTree::Node {
+                    probability: 0.5,
+                    left: leaf(0.25, 1, "00"),
+                    right: leaf(0.25, 2, "01"),
+                }),
+                right: leaf(0.5, 3, "1"),
+            }
+        );
+    }
+}
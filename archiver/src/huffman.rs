@@ -2,14 +2,27 @@ use anyhow::{Context, Result};
 use std::{cell::RefCell, collections::HashMap, fmt, path::Path};
 
 use super::{
-    Codes, CodesBuilder, Encoder, StateSaver,
+    CodeTree, Codes, CodesBuilder, Encoder, StateSaver,
     utils::{convert_to_bytes, sort_words_and_probabilities},
 };
-use crate::{Decoder, FileEncoder, create_probabilities_map, huffman::huffman_tree::HuffmanTree};
-use decoder::HuffmanDecoder;
+use crate::{
+    Decoder,
+    bits::{BitReader, BitWriter},
+    codes::validate_prefix_code,
+    progress::{CHUNK_SIZE, CancellationToken, NoProgress},
+};
+pub(crate) use decoder::HuffmanDecoder;
+pub(crate) use huffman_tree::HuffmanTree;
+pub use huffman_tree::Word;
+pub(crate) use symbol_table::{MAX_SYMBOL_CODE_LENGTH, load_symbol_table, save_symbol_table};
 
 mod decoder;
 mod huffman_tree;
+mod progress;
+mod symbol_table;
+
+/// Максимальная длина кода, которую можно сохранить в состоянии архиватора (код хранится в `u16`).
+pub(crate) const MAX_CODE_LENGTH: usize = 16;
 
 #[derive(Debug)]
 pub struct HuffmanArchiver {
@@ -30,7 +43,11 @@ impl Clone for HuffmanArchiver {
 
 impl HuffmanArchiver {
     pub fn new(words_probabilities: HashMap<u8, f64>) -> Self {
-        let codes = Self::build_optimal_codes_from_hashmap(words_probabilities);
+        Self::from_codes(Self::build_optimal_codes_from_hashmap(words_probabilities))
+    }
+
+    /// Создает архиватор по готовой таблице кодов, например построенной другим `CodesBuilder`.
+    pub fn from_codes(codes: Codes) -> Self {
         Self::_new(codes.mean_code_length().ceil() as u16, codes.into())
     }
 
@@ -42,19 +59,79 @@ impl HuffmanArchiver {
         }
     }
 
+    /// Строит коды Хаффмана по количеству вхождений слов так, чтобы длина кода не превышала
+    /// `max_len`: пока самый длинный код слишком длинный, количества делятся пополам, не опускаясь ниже `1`.
+    /// Единственное слово получает код `0`.
+    ///
+    /// # Panics
+    /// Паникует, если слов больше, чем `2^max_len`.
+    pub(crate) fn build_length_limited_codes<W: Word>(counts: &HashMap<W, usize>, max_len: usize) -> Codes<W> {
+        assert!(
+            max_len >= usize::BITS as usize || counts.len() <= 1 << max_len,
+            "{} symbols cannot be coded with codes not longer than {}",
+            counts.len(),
+            max_len
+        );
+
+        // Сортировка делает коды независимыми от порядка обхода HashMap
+        let mut items = counts.iter().map(|(&word, &count)| (word, count)).collect::<Vec<_>>();
+        items.sort_unstable_by_key(|&(word, count)| (std::cmp::Reverse(count), word.into()));
+
+        loop {
+            let total = items.iter().map(|(_, count)| count).sum::<usize>() as f64;
+            let probabilities = items.iter().map(|&(_, count)| count as f64 / total).collect();
+            let words = items.iter().map(|&(word, _)| word).collect();
+
+            let codes = Self::build_optimal_codes(words, probabilities);
+            if codes.codes().iter().all(|code| code.len() <= max_len) {
+                return codes;
+            }
+
+            items.iter_mut().for_each(|(_, count)| *count = (*count / 2).max(1));
+        }
+    }
+
+    pub(crate) fn word_code(&self) -> &HashMap<u8, String> {
+        &self.word_code
+    }
+
+    /// Записывает коды `bytes` сразу в `writer`, без промежуточной строки [`Encoder::convert_to_string`].
+    /// Возвращает ошибку для байтов без кода.
+    pub(crate) fn write_bits(&self, bytes: &[u8], writer: &mut BitWriter) -> Result<()> {
+        for byte in bytes {
+            let code = self
+                .word_code
+                .get(byte)
+                .with_context(|| format!("Unknown byte: {}", byte))?;
+            code.chars().for_each(|bit| writer.write_bit(bit == '1'));
+        }
+        Ok(())
+    }
+
+    /// Декодирует ровно `count` байтов из `reader`.
+    pub(crate) fn read_words(&self, reader: &mut BitReader, count: usize) -> Result<Vec<u8>> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        self.decoder()?.decode_words(reader, count)
+    }
+
+    /// Строит дерево кодов Хаффмана для визуализации. Для пустого набора вероятностей возвращает `None`.
+    pub fn build_code_tree<W: Word>(words: Vec<W>, probabilities: Vec<f64>) -> Option<CodeTree<W>> {
+        if probabilities.is_empty() {
+            return None;
+        }
+
+        let (words, probabilities) = sort_words_and_probabilities(words, probabilities);
+        Some(HuffmanTree::build(&probabilities, &words).to_code_tree())
+    }
+
     /// Archives the file in the specified location.
     pub fn archive<P>(target: P, destination: P) -> Result<()>
     where
         P: AsRef<Path> + fmt::Debug,
     {
-        let target = &target.as_ref().to_path_buf();
-        let destination = &destination.as_ref().to_path_buf();
-
-        let probabilities = create_probabilities_map(target).context("Failed to create probabilities map")?;
-        let encoder = Self::new(probabilities);
-
-        <Self as FileEncoder>::encode_file(encoder, target, destination)?;
-        Ok(())
+        Self::archive_with_progress(target, destination, &mut NoProgress, &CancellationToken::new())
     }
 
     /// If decoder is not initialized, initialize it and return
@@ -69,29 +146,48 @@ impl HuffmanArchiver {
     }
 
     /// Build word_code from the remaining state bytes
-    fn build_word_code(state: &[u8]) -> HashMap<u8, String> {
+    fn build_word_code(state: &[u8]) -> Result<HashMap<u8, String>> {
+        anyhow::ensure!(
+            state.len().is_multiple_of(4),
+            "Corrupted code table: {} bytes is not a multiple of the record size 4",
+            state.len()
+        );
+
         let mut word_code = HashMap::new();
-        let mut i = 0;
 
-        while i < state.len() {
-            let word = state[i];
-            let code_len = state[i + 1] as usize;
-            i += 2;
+        for record in state.chunks_exact(4) {
+            let word = record[0];
+            let code_len = record[1] as usize;
 
             // Читаем значение кода
-            let code_value = u16::from_le_bytes([state[i], state[i + 1]]);
-            i += 2;
+            let code_value = u16::from_le_bytes([record[2], record[3]]);
+
+            anyhow::ensure!(
+                (1..=MAX_CODE_LENGTH).contains(&code_len)
+                    && (code_len == MAX_CODE_LENGTH || code_value >> code_len == 0),
+                "Corrupted code table: code value {} does not fit into length {} for word {}",
+                code_value,
+                code_len,
+                word
+            );
 
             // Преобразуем число обратно в строковый код
             let code = format!("{:0width$b}", code_value, width = code_len);
-            word_code.insert(word, code);
+
+            anyhow::ensure!(
+                word_code.insert(word, code).is_none(),
+                "Corrupted code table: duplicate word {}",
+                word
+            );
         }
-        word_code
+
+        validate_prefix_code(word_code.values().map(String::as_str)).context("Invalid code table")?;
+        Ok(word_code)
     }
 }
 
 impl Encoder for HuffmanArchiver {
-    fn convert_to_string(&self, bytes: &[u8]) -> String {
+    fn convert_to_string(&self, bytes: &[u8]) -> Result<String> {
         let capacity = bytes.len() * self.mean_code_length as usize;
         let mut bit_string = String::with_capacity(capacity);
 
@@ -99,15 +195,20 @@ impl Encoder for HuffmanArchiver {
             let code = self
                 .word_code
                 .get(byte)
-                .expect(format!("Unknown byte: {}", byte).as_str());
+                .with_context(|| format!("Unknown byte: {}", byte))?;
             bit_string.push_str(code);
         }
-        bit_string
+        Ok(bit_string)
     }
 }
 
 impl CodesBuilder for HuffmanArchiver {
-    fn build_optimal_codes(words: Vec<u8>, probabilities: Vec<f64>) -> Codes {
+    /// Для пустого набора вероятностей возвращает пустую таблицу, единственный символ получает код `0`.
+    fn build_optimal_codes<W: Word>(words: Vec<W>, probabilities: Vec<f64>) -> Codes<W> {
+        if probabilities.is_empty() {
+            return Codes::default();
+        }
+
         let (words, probabilities) = sort_words_and_probabilities(words, probabilities);
         let tree = HuffmanTree::build(&probabilities, &words);
         let codes = tree.build_codes();
@@ -128,6 +229,14 @@ impl StateSaver for HuffmanArchiver {
         items.sort_by_key(|(word, _)| *word);
 
         for (word, code) in items {
+            anyhow::ensure!(
+                code.len() <= MAX_CODE_LENGTH,
+                "Code of word {} is {} bits long, at most {} bits can be saved",
+                word,
+                code.len(),
+                MAX_CODE_LENGTH
+            );
+
             // Сохраняем слово (1 байт)
             result.push(word);
 
@@ -145,7 +254,7 @@ impl StateSaver for HuffmanArchiver {
 
     fn load_state(mut state: Vec<u8>) -> Result<Self> {
         // Ensure there are at least 2 bytes to extract the mean_code_length
-        assert!(
+        anyhow::ensure!(
             state.len() >= 2,
             "State must contain at least 2 bytes for mean_code_length"
         );
@@ -157,7 +266,7 @@ impl StateSaver for HuffmanArchiver {
         // Truncate the state to remove the mean_code_length bytes
         state.truncate(state.len() - 2);
 
-        Ok(Self::_new(mean_code_length, Self::build_word_code(&state)))
+        Ok(Self::_new(mean_code_length, Self::build_word_code(&state)?))
     }
 }
 
@@ -172,18 +281,36 @@ impl TryInto<HuffmanDecoder> for &HuffmanArchiver {
 
 impl Decoder for HuffmanArchiver {
     fn decode_string(&self, bit_string: &str) -> Result<Vec<u8>> {
+        // Архив пустого файла не содержит кодов, а закодированные данные пусты
+        if self.word_code.is_empty() {
+            return Ok(Vec::new());
+        }
         self.decoder()?.decode_string(bit_string)
     }
+
+    fn decode_chunks(&self, bytes: &[u8], original_size: usize, f: &mut dyn FnMut(&[u8]) -> Result<()>) -> Result<()> {
+        let mut reader = BitReader::new(bytes);
+        let mut decoded = 0;
+
+        while decoded < original_size {
+            let count = CHUNK_SIZE.min(original_size - decoded);
+            f(&self.read_words(&mut reader, count).context("Failed to decode")?)?;
+            decoded += count;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs::File;
 
+    use crate::{FileDecoder, FileEncoder};
+
     use super::*;
 
     fn new_simple_archiver() -> HuffmanArchiver {
-        let word_code = HashMap::from([(1, "1".into()), (2, "11".into()), (3, "1110".into())]);
+        let word_code = HashMap::from([(1, "0".into()), (2, "10".into()), (3, "1110".into())]);
         HuffmanArchiver {
             word_code: word_code,
             mean_code_length: 100,
@@ -195,22 +322,53 @@ mod tests {
     fn test_build_optimal_codes() {
         assert_eq!(
             vec!["0", "11", "10"],
-            HuffmanArchiver::build_optimal_codes(vec![1, 2, 3], vec![0.5, 0.25, 0.25]).codes()
+            HuffmanArchiver::build_optimal_codes(vec![1u8, 2, 3], vec![0.5, 0.25, 0.25]).codes()
         );
         assert_eq!(
             vec!["0", "10", "110", "111"],
-            HuffmanArchiver::build_optimal_codes(vec![1, 2, 3, 4], vec![0.5, 0.25, 0.125, 0.125]).codes()
+            HuffmanArchiver::build_optimal_codes(vec![1u8, 2, 3, 4], vec![0.5, 0.25, 0.125, 0.125]).codes()
         );
         assert_eq!(
             vec!["00", "111", "110", "101", "011", "010", "1001", "1000"],
             HuffmanArchiver::build_optimal_codes(
-                vec![1, 2, 3, 4, 5, 6, 7, 8],
+                vec![1u8, 2, 3, 4, 5, 6, 7, 8],
                 vec![0.170, 0.168, 0.166, 0.140, 0.118, 0.110, 0.083, 0.045]
             )
             .codes()
         );
     }
 
+    #[test]
+    fn test_build_code_tree() {
+        let words = vec![1u8, 2, 3, 4, 5, 6, 7, 8];
+        let probabilities = vec![0.170, 0.168, 0.166, 0.140, 0.118, 0.110, 0.083, 0.045];
+
+        let codes = HuffmanArchiver::build_optimal_codes(words.clone(), probabilities.clone());
+        let tree = HuffmanArchiver::build_code_tree(words, probabilities).unwrap();
+
+        assert!((tree.probability() - 1.0).abs() < 1e-9);
+
+        // Коды в листьях дерева совпадают с построенными кодами
+        let mut stack = vec![&tree];
+        let mut leaves = 0;
+        while let Some(node) = stack.pop() {
+            match node {
+                CodeTree::Leaf { word, code, .. } => {
+                    let index = codes.words().iter().position(|w| w == word).unwrap();
+                    assert_eq!(&codes.codes()[index], code);
+                    leaves += 1;
+                }
+                CodeTree::Node { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+        assert_eq!(leaves, 8);
+
+        assert!(HuffmanArchiver::build_code_tree::<u8>(vec![], vec![]).is_none());
+    }
+
     #[test]
     fn test_save_and_load_huffman_archiver() {
         let archiver = new_simple_archiver();
@@ -221,8 +379,8 @@ mod tests {
             state,
             vec![
                 // code, len, value
-                1, 1, 1, 0, // (1, "1")
-                2, 2, 3, 0, // (2, "11")
+                1, 1, 0, 0, // (1, "0")
+                2, 2, 2, 0, // (2, "10")
                 3, 4, 14, 0, // (3, "1110")
                 100, 0 // mean_code_length
             ]
@@ -234,6 +392,24 @@ mod tests {
         assert_eq!(archiver.word_code, word_code);
     }
 
+    #[test]
+    fn test_load_invalid_state() {
+        // "1" является началом "11"
+        let state = vec![1, 1, 1, 0, 2, 2, 3, 0, 0, 0];
+        let err = HuffmanArchiver::load_state(state).unwrap_err();
+        assert!(format!("{:#}", err).contains("prefix"), "{:#}", err);
+
+        // Значение 4 не помещается в код длины 2
+        let state = vec![1, 2, 4, 0, 0, 0];
+        assert!(HuffmanArchiver::load_state(state).is_err());
+
+        // Обрезанная запись
+        let state = vec![1, 1, 0, 0, 0];
+        assert!(HuffmanArchiver::load_state(state).is_err());
+
+        assert!(HuffmanArchiver::load_state(vec![0]).is_err());
+    }
+
     #[test]
     fn test_save_and_load_huffman_archiver_to_file() {
         let expected_archiver = new_simple_archiver();
@@ -262,4 +438,86 @@ mod tests {
 
         std::fs::remove_file(filename).ok();
     }
+
+    /// Случайные данные каждой длины от `0` до `max_len` с алфавитами из 1, 2, 3, 16 и 256 байтов.
+    fn random_inputs(max_len: usize) -> Vec<Vec<u8>> {
+        use pseudorandom::{PRNG, XorShift32};
+
+        let mut rng = XorShift32::build(0x5EED).unwrap();
+        let mut inputs = Vec::new();
+        for len in 0..=max_len {
+            for alphabet in [1, 2, 3, 16, 256] {
+                inputs.push((0..len).map(|_| (rng.next() % alphabet) as u8).collect());
+            }
+        }
+        inputs
+    }
+
+    fn round_trip(archiver: HuffmanArchiver, bytes: &[u8]) {
+        let mut archive = Vec::new();
+        archiver.encode_to_writer(bytes, &mut archive).unwrap();
+
+        let decoded = HuffmanArchiver::decode_from_reader(&mut std::io::Cursor::new(archive)).unwrap();
+        assert_eq!(decoded, bytes);
+    }
+
+    #[test]
+    fn test_round_trip_random_inputs() {
+        use crate::{FrequencyMap, ShannonFanoEncoder};
+
+        for bytes in random_inputs(256) {
+            let mut freq_map = FrequencyMap::new();
+            freq_map.consume(&bytes);
+
+            round_trip(HuffmanArchiver::new(freq_map.build()), &bytes);
+            round_trip(
+                HuffmanArchiver::from_codes(ShannonFanoEncoder::build_optimal_codes_from_hashmap(freq_map.build())),
+                &bytes,
+            );
+            round_trip(
+                HuffmanArchiver::from_codes(HuffmanArchiver::build_length_limited_codes(freq_map.counts(), 8)),
+                &bytes,
+            );
+        }
+    }
+
+    #[test]
+    fn test_empty_and_single_symbol() {
+        // Пустая таблица кодов: в состоянии только средняя длина кода
+        let empty = HuffmanArchiver::new(HashMap::new());
+        assert!(empty.encode_bytes(&[]).unwrap().is_empty());
+        let state = empty.save_state().unwrap();
+        assert_eq!(state.len(), 2);
+        assert!(
+            HuffmanArchiver::load_state(state)
+                .unwrap()
+                .decode_bytes(&[])
+                .unwrap()
+                .is_empty()
+        );
+
+        // Единственный символ получает код `0`, каждый байт занимает один бит
+        let single = HuffmanArchiver::new(HashMap::from([(b'a', 1.0)]));
+        assert_eq!(single.word_code[&b'a'], "0");
+        assert_eq!(single.encode_bytes(&[b'a'; 20]).unwrap(), [0, 0, 0]);
+        assert!(single.encode_bytes(b"ab").is_err());
+
+        // Пустой код не может быть сохранен
+        assert!(HuffmanArchiver::load_state(vec![b'a', 0, 0, 0, 1, 0]).is_err());
+
+        for (name, bytes) in [("empty", Vec::new()), ("single", vec![b'a'; 20])] {
+            let target = std::env::temp_dir().join(format!("huffman_{}_test.txt", name));
+            let archived = target.with_extension("huff");
+            let extracted = target.with_extension("extract");
+
+            std::fs::write(&target, &bytes).unwrap();
+            HuffmanArchiver::archive(&target, &archived).unwrap();
+            HuffmanArchiver::decode_file(&archived, &extracted).unwrap();
+            assert_eq!(std::fs::read(&extracted).unwrap(), bytes);
+
+            for path in [target, archived, extracted] {
+                std::fs::remove_file(path).unwrap();
+            }
+        }
+    }
 }
@@ -2,21 +2,59 @@ use std::collections::HashMap;
 
 use anyhow::{Context, Ok, Result};
 
-use crate::{Decoder, huffman::huffman_tree::HuffmanTree};
+use crate::{
+    Decoder,
+    bits::BitReader,
+    huffman::huffman_tree::{HuffmanTree, Word},
+};
 
 #[derive(Debug)]
-pub struct HuffmanDecoder {
-    tree: HuffmanTree,
+pub struct HuffmanDecoder<W: Word = u8> {
+    tree: HuffmanTree<W>,
 }
 
-impl HuffmanDecoder {
-    pub fn new(tree: HuffmanTree) -> Self {
+impl<W: Word> HuffmanDecoder<W> {
+    pub fn new(tree: HuffmanTree<W>) -> Self {
         Self { tree }
     }
-}
 
-impl Decoder for HuffmanDecoder {
-    fn decode_string(&self, bit_string: &str) -> Result<Vec<u8>> {
+    /// Декодирует ровно `count` слов, читая биты из `reader`. Оставшиеся биты не читаются.
+    pub fn decode_words(&self, reader: &mut BitReader, count: usize) -> Result<Vec<W>> {
+        let mut decoded = Vec::with_capacity(count);
+
+        while decoded.len() < count {
+            let mut node = &self.tree;
+
+            // Единственное слово кодируется одним битом `0`
+            if node.is_leaf() && reader.read_bit()? {
+                anyhow::bail!("Invalid bit sequence at bit {}", reader.position());
+            }
+            while let HuffmanTree::Node { left, right, .. } = node {
+                node = if reader.read_bit()? { right } else { left };
+            }
+
+            match node {
+                HuffmanTree::Leaf { word, index, .. } if *index != usize::MAX => decoded.push(*word),
+                _ => anyhow::bail!("Invalid bit sequence at bit {}", reader.position()),
+            }
+        }
+
+        Ok(decoded)
+    }
+
+    /// Декодирует строку битов. Незаконченный код в конце строки (биты дополнения) отбрасывается.
+    pub fn decode_symbols(&self, bit_string: &str) -> Result<Vec<W>> {
+        // Единственное слово кодируется одним битом `0`
+        if let HuffmanTree::Leaf { word, .. } = self.tree {
+            return bit_string
+                .bytes()
+                .map(|bit| match bit {
+                    b'0' => Ok(word),
+                    _ => anyhow::bail!("Invalid bit string"),
+                })
+                .collect();
+        }
+
         let mut decoded = Vec::new();
         let bit_string = bit_string.as_bytes();
 
@@ -26,10 +64,10 @@ impl Decoder for HuffmanDecoder {
         while i < bit_string.len() {
             match bit_string[i] {
                 b'0' => {
-                    node = node.left().ok_or(anyhow::anyhow!("Invalid bit string"))?;
+                    node = node.left().ok_or_else(|| anyhow::anyhow!("Invalid bit string"))?;
                 }
                 b'1' => {
-                    node = node.right().ok_or(anyhow::anyhow!("Invalid bit string"))?;
+                    node = node.right().ok_or_else(|| anyhow::anyhow!("Invalid bit string"))?;
                 }
                 _ => {
                     anyhow::bail!("Invalid bit string")
@@ -48,10 +86,16 @@ impl Decoder for HuffmanDecoder {
     }
 }
 
-impl TryFrom<&HashMap<u8, String>> for HuffmanDecoder {
+impl Decoder for HuffmanDecoder {
+    fn decode_string(&self, bit_string: &str) -> Result<Vec<u8>> {
+        self.decode_symbols(bit_string)
+    }
+}
+
+impl<W: Word> TryFrom<&HashMap<W, String>> for HuffmanDecoder<W> {
     type Error = anyhow::Error;
 
-    fn try_from(value: &HashMap<u8, String>) -> std::result::Result<Self, Self::Error> {
+    fn try_from(value: &HashMap<W, String>) -> std::result::Result<Self, Self::Error> {
         let tree = HuffmanTree::restore_from_word_code(value)
             .context("Failed to convert HuffmanArchiver into HuffmanDecoder")?;
         Ok(HuffmanDecoder::new(tree))
@@ -3,24 +3,32 @@ use std::{
     cmp::Ordering,
     collections::{BinaryHeap, HashMap, VecDeque},
     fmt,
+    hash::Hash,
 };
 
-pub enum HuffmanTree {
+use crate::CodeTree;
+
+/// Тип кодируемых слов: байты или, например, кодовые точки Unicode.
+pub trait Word: Copy + Eq + Hash + Default + fmt::Display + Into<u64> {}
+
+impl<T> Word for T where T: Copy + Eq + Hash + Default + fmt::Display + Into<u64> {}
+
+pub enum HuffmanTree<W = u8> {
     Leaf {
         probability: f64,
         index: usize,
-        word: u8,
+        word: W,
     },
     Node {
         probability: f64,
-        left: Box<HuffmanTree>,
-        right: Box<HuffmanTree>,
+        left: Box<HuffmanTree<W>>,
+        right: Box<HuffmanTree<W>>,
         count_codes: usize,
     },
 }
 
-impl HuffmanTree {
-    pub fn restore_from_word_code(codes: &HashMap<u8, String>) -> Result<Self> {
+impl<W: Word> HuffmanTree<W> {
+    pub fn restore_from_word_code(codes: &HashMap<W, String>) -> Result<Self> {
         if codes.is_empty() {
             anyhow::bail!("Cannot restore HuffmanTree: no codes provided");
         }
@@ -30,12 +38,12 @@ impl HuffmanTree {
             left: Box::new(Self::Leaf {
                 probability: 0.0,
                 index: usize::MAX,
-                word: 0,
+                word: W::default(),
             }),
             right: Box::new(HuffmanTree::Leaf {
                 probability: 0.0,
                 index: usize::MAX,
-                word: 0,
+                word: W::default(),
             }),
             count_codes: codes.len(),
         };
@@ -75,7 +83,7 @@ impl HuffmanTree {
             // Заменяем конечный узел на лист с данными
             *current = Self::Leaf {
                 probability: 0.0,
-                index: *word as usize,
+                index: (*word).into() as usize,
                 word: *word,
             };
         }
@@ -89,12 +97,12 @@ impl HuffmanTree {
             left: Box::new(Self::Leaf {
                 probability: 0.0,
                 index: usize::MAX,
-                word: 0,
+                word: W::default(),
             }),
             right: Box::new(Self::Leaf {
                 probability: 0.0,
                 index: usize::MAX,
-                word: 0,
+                word: W::default(),
             }),
             count_codes: 0,
         }
@@ -104,7 +112,12 @@ impl HuffmanTree {
         matches!(self, HuffmanTree::Leaf { index, .. } if *index == usize::MAX)
     }
 
-    pub fn build(probabilities: &[f64], words: &[u8]) -> HuffmanTree {
+    /// Строит дерево Хаффмана. Единственное слово становится корнем-листом с кодом `0`.
+    ///
+    /// # Panics
+    /// Паникует, если `probabilities` пуст: дерево без листьев не определено, пустой таблице кодов
+    /// соответствует пустой [`HashMap`].
+    pub fn build(probabilities: &[f64], words: &[W]) -> Self {
         match probabilities.len() {
             0 => panic!("No probabilities provided"),
             1 => return HuffmanTree::new_leaf(probabilities[0], 0, words[0]),
@@ -126,7 +139,7 @@ impl HuffmanTree {
         heap.pop().unwrap()
     }
 
-    pub fn new_leaf(probability: f64, index: usize, word: u8) -> Self {
+    pub fn new_leaf(probability: f64, index: usize, word: W) -> Self {
         HuffmanTree::Leaf {
             probability,
             index,
@@ -134,7 +147,7 @@ impl HuffmanTree {
         }
     }
 
-    pub fn unite(left: HuffmanTree, right: HuffmanTree) -> Self {
+    pub fn unite(left: Self, right: Self) -> Self {
         let probability = left.probability() + right.probability();
         let count_codes = left.count_codes() + right.count_codes();
 
@@ -185,11 +198,17 @@ impl HuffmanTree {
         }
     }
 
+    /// Код корня дерева. Дерево из единственного листа получает код `0`, иначе единственный символ
+    /// кодировался бы пустой строкой и не занимал бы ни одного бита.
+    fn root_code(&self) -> String {
+        if self.is_leaf() { "0".into() } else { String::new() }
+    }
+
     // Общий метод, который возвращает итератор пар (ключ, код)
-    fn build_code_pairs(&self) -> Vec<(usize, String, u8)> {
+    fn build_code_pairs(&self) -> Vec<(usize, String, W)> {
         let mut pairs = Vec::new();
         let mut queue = VecDeque::new();
-        queue.push_back((self, String::new()));
+        queue.push_back((self, self.root_code()));
 
         while let Some((node, code)) = queue.pop_front() {
             match node {
@@ -205,7 +224,7 @@ impl HuffmanTree {
         pairs
     }
 
-    pub fn build_word_code(&self) -> HashMap<u8, String> {
+    pub fn build_word_code(&self) -> HashMap<W, String> {
         self.build_code_pairs()
             .into_iter()
             .map(|(_, code, word)| (word, code))
@@ -225,7 +244,7 @@ impl HuffmanTree {
     // Альтернативная реализация build_codes с рекурсией
     pub fn build_codes_recursive(&self) -> Vec<String> {
         let mut codes = vec![String::new(); self.count_codes()];
-        self.build_codes_helper(String::new(), &mut codes);
+        self.build_codes_helper(self.root_code(), &mut codes);
         codes
     }
 
@@ -277,7 +296,34 @@ impl HuffmanTree {
     }
 }
 
-impl PartialEq for HuffmanTree {
+impl<W: Word> HuffmanTree<W> {
+    /// Преобразует дерево в [`CodeTree`] для визуализации.
+    pub fn to_code_tree(&self) -> CodeTree<W> {
+        self.to_code_tree_helper(self.root_code())
+    }
+
+    fn to_code_tree_helper(&self, code: String) -> CodeTree<W> {
+        match self {
+            HuffmanTree::Leaf { probability, word, .. } => CodeTree::Leaf {
+                probability: *probability,
+                word: *word,
+                code,
+            },
+            HuffmanTree::Node {
+                probability,
+                left,
+                right,
+                ..
+            } => CodeTree::Node {
+                probability: *probability,
+                left: Box::new(left.to_code_tree_helper(format!("{}0", code))),
+                right: Box::new(right.to_code_tree_helper(format!("{}1", code))),
+            },
+        }
+    }
+}
+
+impl<W: Word> PartialEq for HuffmanTree<W> {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (
@@ -316,21 +362,21 @@ impl PartialEq for HuffmanTree {
     }
 }
 
-impl Eq for HuffmanTree {}
+impl<W: Word> Eq for HuffmanTree<W> {}
 
-impl PartialOrd for HuffmanTree {
+impl<W: Word> PartialOrd for HuffmanTree<W> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         other.probability().partial_cmp(&self.probability())
     }
 }
 
-impl Ord for HuffmanTree {
+impl<W: Word> Ord for HuffmanTree<W> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.partial_cmp(other).unwrap_or(Ordering::Equal)
     }
 }
 
-impl fmt::Debug for HuffmanTree {
+impl<W: Word> fmt::Debug for HuffmanTree<W> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.fmt_with_indent(f, 0)
     }
@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use std::{io::Write, path::Path};
+
+use super::{HuffmanArchiver, MAX_CODE_LENGTH};
+use crate::{
+    FrequencyMap, StateSaver,
+    bits::BitWriter,
+    progress::{CancellationToken, Phase, Progress, ProgressObserver, for_each_chunk, remove_on_error},
+};
+
+impl HuffmanArchiver {
+    /// Архивирует файл так же, как [`HuffmanArchiver::archive`], сообщая о ходе работы `observer`.
+    /// Файл читается и кодируется по частям, а длина кодов ограничена, поэтому таблица сохраняется всегда.
+    ///
+    /// При отмене через `token` возвращает ошибку [`crate::progress::Cancelled`] и удаляет частично
+    /// записанный архив.
+    pub fn archive_with_progress<P: AsRef<Path>>(
+        target: P,
+        destination: P,
+        observer: &mut impl ProgressObserver,
+        token: &CancellationToken,
+    ) -> Result<()> {
+        let target = target.as_ref();
+        let total = std::fs::metadata(target).context("Failed to read file metadata")?.len();
+
+        // Первый проход: частоты
+        let mut freq_map = FrequencyMap::new();
+        for_each_chunk(target, |chunk, processed| {
+            freq_map.consume(chunk);
+            observer.on_progress(&Progress {
+                phase: Phase::CountingFrequencies,
+                processed,
+                total,
+                compression_ratio: None,
+            });
+            Ok(token.check()?)
+        })?;
+
+        let codes = if freq_map.counts().is_empty() {
+            Default::default()
+        } else {
+            Self::build_length_limited_codes(freq_map.counts(), MAX_CODE_LENGTH)
+        };
+        let archiver = Self::from_codes(codes);
+
+        // Второй проход: кодирование
+        remove_on_error(destination.as_ref(), |file| {
+            let state = archiver.clone().save_state()?;
+            Self::write_state(&state, file)?;
+            file.write_all(&(total as usize).to_le_bytes())
+                .context("Failed to write original size")?;
+
+            let header_size = (std::mem::size_of::<usize>() * 2 + state.len()) as u64;
+            let mut written = header_size;
+            let mut bits = BitWriter::new();
+
+            for_each_chunk(target, |chunk, processed| {
+                archiver.write_bits(chunk, &mut bits)?;
+
+                let bytes = bits.take_full_bytes();
+                file.write_all(&bytes).context("Failed to write encoded data")?;
+                written += bytes.len() as u64;
+
+                observer.on_progress(&Progress {
+                    phase: Phase::Encoding,
+                    processed,
+                    total,
+                    compression_ratio: Some(written as f64 / processed as f64),
+                });
+                Ok(token.check()?)
+            })?;
+
+            let rest = bits.into_bytes();
+            file.write_all(&rest).context("Failed to write encoded data")?;
+            file.flush().context("Failed to write archive")?;
+            written += rest.len() as u64;
+
+            observer.on_progress(&Progress {
+                phase: Phase::Writing,
+                processed: total,
+                total,
+                compression_ratio: Some(written as f64 / total.max(1) as f64),
+            });
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileDecoder, progress::Cancelled};
+
+    fn create_test_file(name: &str) -> (std::path::PathBuf, Vec<u8>) {
+        let bytes = (0..150_000u32).map(|i| (i % 251 % 13) as u8 + b'a').collect::<Vec<_>>();
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, &bytes).unwrap();
+        (path, bytes)
+    }
+
+    #[test]
+    fn test_archive_and_decode_with_progress() {
+        let (target, bytes) = create_test_file("progress_test.txt");
+        let archived = target.with_extension("huff");
+        let extracted = target.with_extension("extract");
+
+        let mut events = Vec::new();
+        let token = CancellationToken::new();
+        HuffmanArchiver::archive_with_progress(&target, &archived, &mut |p: &Progress| events.push(*p), &token)
+            .unwrap();
+
+        assert_eq!(events.first().unwrap().phase, Phase::CountingFrequencies);
+        assert_eq!(events.last().unwrap().phase, Phase::Writing);
+        assert!(
+            events
+                .iter()
+                .any(|p| p.phase == Phase::Encoding && p.processed < p.total)
+        );
+
+        let ratio = events.last().unwrap().compression_ratio.unwrap();
+        let archive_size = std::fs::metadata(&archived).unwrap().len();
+        assert_eq!(ratio, archive_size as f64 / bytes.len() as f64);
+
+        // Формат совпадает с обычным архивом
+        HuffmanArchiver::decode_file(&archived, &extracted).unwrap();
+        assert_eq!(std::fs::read(&extracted).unwrap(), bytes);
+
+        let mut phases = Vec::new();
+        HuffmanArchiver::decode_file_with_progress(
+            &archived,
+            &extracted,
+            &mut |p: &Progress| phases.push(p.phase),
+            &token,
+        )
+        .unwrap();
+        assert_eq!(std::fs::read(&extracted).unwrap(), bytes);
+        assert!(phases.contains(&Phase::Decoding));
+
+        for path in [target, archived, extracted] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_cancellation_removes_output() {
+        let (target, _) = create_test_file("progress_cancel_test.txt");
+        let archived = target.with_extension("huff");
+
+        // Отменяем после первой закодированной части
+        let token = CancellationToken::new();
+        let mut observer = {
+            let token = token.clone();
+            move |p: &Progress| {
+                if p.phase == Phase::Encoding {
+                    token.cancel();
+                }
+            }
+        };
+
+        let err = HuffmanArchiver::archive_with_progress(&target, &archived, &mut observer, &token).unwrap_err();
+        assert!(err.is::<Cancelled>(), "{:#}", err);
+        assert!(!archived.exists());
+
+        std::fs::remove_file(target).unwrap();
+    }
+}
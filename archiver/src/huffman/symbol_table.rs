@@ -0,0 +1,69 @@
+//! Таблица кодов для символов шире байта (кодовых точек, пар серий), хранимая в состоянии архива.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+
+use crate::codes::validate_prefix_code;
+
+/// Максимальная длина кода, которую можно сохранить в таблице (код хранится в `u32`).
+pub(crate) const MAX_SYMBOL_CODE_LENGTH: usize = 32;
+
+/// Размер записи таблицы кодов: символ (u32), длина кода (u8) и код (u32).
+const RECORD_SIZE: usize = 4 + 1 + 4;
+
+/// Записывает таблицу кодов, отсортированную по символам.
+pub(crate) fn save_symbol_table(word_code: HashMap<u32, String>) -> Vec<u8> {
+    let mut items = word_code.into_iter().collect::<Vec<_>>();
+    items.sort_unstable();
+
+    let mut state = Vec::with_capacity(items.len() * RECORD_SIZE);
+    for (symbol, code) in items {
+        let code_value = u32::from_str_radix(&code, 2).unwrap_or(0);
+
+        state.extend_from_slice(&symbol.to_le_bytes());
+        state.push(code.len() as u8);
+        state.extend_from_slice(&code_value.to_le_bytes());
+    }
+    state
+}
+
+/// Читает таблицу кодов, проверяя символы функцией `is_valid_symbol`, длины кодов и префиксность.
+pub(crate) fn load_symbol_table(state: &[u8], is_valid_symbol: impl Fn(u32) -> bool) -> Result<HashMap<u32, String>> {
+    anyhow::ensure!(
+        state.len().is_multiple_of(RECORD_SIZE),
+        "Corrupted code table: {} bytes is not a multiple of the record size {}",
+        state.len(),
+        RECORD_SIZE
+    );
+
+    let mut word_code = HashMap::new();
+    for record in state.chunks_exact(RECORD_SIZE) {
+        let symbol = u32::from_le_bytes(record[0..4].try_into().unwrap());
+        let code_len = record[4] as usize;
+        let code_value = u32::from_le_bytes(record[5..9].try_into().unwrap());
+
+        anyhow::ensure!(
+            is_valid_symbol(symbol),
+            "Corrupted code table: invalid symbol {:#x}",
+            symbol
+        );
+        anyhow::ensure!(
+            (1..=MAX_SYMBOL_CODE_LENGTH).contains(&code_len)
+                && (code_len == MAX_SYMBOL_CODE_LENGTH || code_value >> code_len == 0),
+            "Corrupted code table: code value {} does not fit into length {} for symbol {:#x}",
+            code_value,
+            code_len,
+            symbol
+        );
+
+        let code = format!("{:0width$b}", code_value, width = code_len);
+        anyhow::ensure!(
+            word_code.insert(symbol, code).is_none(),
+            "Corrupted code table: duplicate symbol {:#x}",
+            symbol
+        );
+    }
+
+    validate_prefix_code(word_code.values().map(String::as_str)).context("Invalid code table")?;
+    Ok(word_code)
+}
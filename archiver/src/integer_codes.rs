@@ -0,0 +1,573 @@
+//! Универсальные и параметрические коды натуральных чисел.
+//!
+//! В отличие от кодов Шеннона-Фано и Хаффмана, не требуют заранее известной статистики: код числа
+//! зависит только от самого числа (и параметра кода). Применяются, например, для кодирования длин серий.
+
+use anyhow::{Context, Result};
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    bits::{BitReader, BitWriter},
+    codes::kraft_sum,
+};
+
+/// Код целых чисел.
+///
+/// Коды Элиаса и Фибоначчи определены для чисел `n >= 1`, остальные - для `n >= 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UniversalCode {
+    /// `⌊log2 n⌋` нулей, затем `n` в двоичном виде.
+    EliasGamma,
+    /// Длина `n` в гамма-коде, затем `n` в двоичном виде без старшей единицы.
+    EliasDelta,
+    /// Рекурсивная запись длин, заканчивающаяся `0`.
+    EliasOmega,
+    /// Представление Цекендорфа от младшего числа Фибоначчи к старшему, затем `1`.
+    Fibonacci,
+    /// Экспоненциальный код Голомба порядка `k`: гамма-код числа `n + 2^k` без `k` ведущих нулей.
+    ExpGolomb(u32),
+    /// Код Голомба с параметром `m >= 1`: частное `n / m` в унарном коде, остаток в усеченном двоичном.
+    Golomb(u64),
+    /// Код Райса - код Голомба с параметром `m = 2^k`.
+    Rice(u32),
+}
+
+impl UniversalCode {
+    /// Наименьшее кодируемое число.
+    pub fn min_value(&self) -> u64 {
+        match self {
+            Self::EliasGamma | Self::EliasDelta | Self::EliasOmega | Self::Fibonacci => 1,
+            Self::ExpGolomb(_) | Self::Golomb(_) | Self::Rice(_) => 0,
+        }
+    }
+
+    fn validate(&self, n: u64) -> Result<()> {
+        match *self {
+            Self::Golomb(0) => anyhow::bail!("Golomb parameter must be positive"),
+            Self::ExpGolomb(k) | Self::Rice(k) if k > 63 => {
+                anyhow::bail!("{} parameter must not exceed 63, actual: {}", self, k)
+            }
+            _ => {}
+        }
+        anyhow::ensure!(
+            n >= self.min_value(),
+            "{} is defined for numbers >= {}, actual: {}",
+            self,
+            self.min_value(),
+            n
+        );
+        Ok(())
+    }
+
+    /// Записывает код числа `n`.
+    pub fn encode(&self, n: u64, writer: &mut BitWriter) -> Result<()> {
+        self.validate(n)?;
+
+        match *self {
+            Self::EliasGamma => write_gamma(n, writer),
+            Self::EliasDelta => {
+                let len = bit_length(n);
+                write_gamma(len as u64, writer);
+                writer.write_bits(n, len - 1);
+            }
+            Self::EliasOmega => {
+                // Группы записываются в обратном порядке: от самого числа к длине длины
+                let mut groups = Vec::new();
+                let mut n = n;
+                while n > 1 {
+                    groups.push(n);
+                    n = bit_length(n) as u64 - 1;
+                }
+                for &group in groups.iter().rev() {
+                    writer.write_bits(group, bit_length(group));
+                }
+                writer.write_bit(false);
+            }
+            Self::Fibonacci => {
+                let fibonacci = fibonacci_numbers();
+                let mut bits = vec![false; fibonacci.iter().take_while(|&&f| f <= n).count()];
+
+                let mut rest = n;
+                for i in (0..bits.len()).rev() {
+                    if fibonacci[i] <= rest {
+                        bits[i] = true;
+                        rest -= fibonacci[i];
+                    }
+                }
+
+                bits.into_iter().for_each(|bit| writer.write_bit(bit));
+                writer.write_bit(true);
+            }
+            Self::ExpGolomb(k) => {
+                let shifted = n
+                    .checked_add(1 << k)
+                    .context("Number is too large for Exp-Golomb code")?;
+                let len = bit_length(shifted);
+                writer.write_repeated(false, (len - 1 - k) as u64);
+                writer.write_bits(shifted, len);
+            }
+            Self::Golomb(m) => write_golomb(n, m, writer),
+            Self::Rice(k) => {
+                writer.write_repeated(true, n >> k);
+                writer.write_bit(false);
+                writer.write_bits(n, k);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Читает код одного числа.
+    pub fn decode(&self, reader: &mut BitReader) -> Result<u64> {
+        self.validate(self.min_value())?;
+
+        match *self {
+            Self::EliasGamma => read_gamma(reader),
+            Self::EliasDelta => {
+                let len = read_gamma(reader)?;
+                anyhow::ensure!(len <= 64, "Elias delta length {} exceeds 64 bits", len);
+                Ok((1 << (len - 1)) | reader.read_bits(len as u32 - 1)?)
+            }
+            Self::EliasOmega => {
+                let mut n = 1u64;
+                while reader.read_bit()? {
+                    anyhow::ensure!(n < 64, "Elias omega group of {} bits exceeds 64 bits", n + 1);
+                    n = (1 << n) | reader.read_bits(n as u32)?;
+                }
+                Ok(n)
+            }
+            Self::Fibonacci => {
+                let fibonacci = fibonacci_numbers();
+                let mut n = 0u64;
+                let mut previous = false;
+
+                for i in 0.. {
+                    let bit = reader.read_bit()?;
+                    if bit && previous {
+                        return Ok(n);
+                    }
+                    if bit {
+                        let f = fibonacci.get(i).context("Fibonacci code exceeds 64 bits")?;
+                        n = n.checked_add(*f).context("Fibonacci code exceeds 64 bits")?;
+                    }
+                    previous = bit;
+                }
+                unreachable!()
+            }
+            Self::ExpGolomb(k) => {
+                let zeros = read_zeros(reader)?;
+                let len = zeros + k + 1;
+                anyhow::ensure!(len <= 64, "Exp-Golomb code of {} bits exceeds 64 bits", len);
+
+                // Первая единица уже прочитана
+                let shifted = reader.read_bits(len - 1)? | (1 << (len - 1));
+                Ok(shifted - (1 << k))
+            }
+            Self::Golomb(m) => read_golomb(m, reader),
+            Self::Rice(k) => {
+                let quotient = read_ones(reader)?;
+                anyhow::ensure!(
+                    quotient.leading_zeros() >= k,
+                    "Rice quotient {} does not fit into 64 bits",
+                    quotient
+                );
+                Ok((quotient << k) | reader.read_bits(k)?)
+            }
+        }
+    }
+
+    /// Длина кода числа `n` в битах.
+    pub fn code_length(&self, n: u64) -> Result<usize> {
+        let mut writer = BitWriter::new();
+        self.encode(n, &mut writer)?;
+        Ok(writer.len())
+    }
+
+    /// Код числа `n` в виде строки из `0` и `1`.
+    pub fn code_string(&self, n: u64) -> Result<String> {
+        let mut writer = BitWriter::new();
+        self.encode(n, &mut writer)?;
+        Ok(writer.to_bit_string())
+    }
+
+    /// Кодирует последовательность чисел, последний байт дополняется нулями.
+    pub fn encode_all(&self, values: &[u64]) -> Result<Vec<u8>> {
+        let mut writer = BitWriter::new();
+        for &value in values {
+            self.encode(value, &mut writer)?;
+        }
+        Ok(writer.into_bytes())
+    }
+
+    /// Декодирует `count` чисел.
+    pub fn decode_all(&self, bytes: &[u8], count: usize) -> Result<Vec<u64>> {
+        let mut reader = BitReader::new(bytes);
+        (0..count)
+            .map(|i| {
+                self.decode(&mut reader)
+                    .with_context(|| format!("Failed to decode number {}", i))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for UniversalCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EliasGamma => write!(f, "Elias gamma"),
+            Self::EliasDelta => write!(f, "Elias delta"),
+            Self::EliasOmega => write!(f, "Elias omega"),
+            Self::Fibonacci => write!(f, "Fibonacci"),
+            Self::ExpGolomb(k) => write!(f, "Exp-Golomb(k={})", k),
+            Self::Golomb(m) => write!(f, "Golomb(m={})", m),
+            Self::Rice(k) => write!(f, "Rice(k={})", k),
+        }
+    }
+}
+
+/// Количество значащих битов числа `n > 0`.
+fn bit_length(n: u64) -> u32 {
+    64 - n.leading_zeros()
+}
+
+fn write_gamma(n: u64, writer: &mut BitWriter) {
+    let len = bit_length(n);
+    writer.write_repeated(false, (len - 1) as u64);
+    writer.write_bits(n, len);
+}
+
+fn read_gamma(reader: &mut BitReader) -> Result<u64> {
+    let zeros = read_zeros(reader)?;
+    anyhow::ensure!(zeros < 64, "Elias gamma code of {} bits exceeds 64 bits", zeros + 1);
+    Ok((1 << zeros) | reader.read_bits(zeros)?)
+}
+
+/// Читает нули до первой единицы включительно и возвращает их количество.
+fn read_zeros(reader: &mut BitReader) -> Result<u32> {
+    let mut zeros = 0;
+    while !reader.read_bit()? {
+        zeros += 1;
+    }
+    Ok(zeros)
+}
+
+/// Читает унарный код: единицы до первого нуля включительно.
+fn read_ones(reader: &mut BitReader) -> Result<u64> {
+    let mut ones = 0;
+    while reader.read_bit()? {
+        ones += 1;
+    }
+    Ok(ones)
+}
+
+/// Остаток записывается усеченным двоичным кодом: первые `2^b - m` остатков занимают `b - 1` бит,
+/// остальные - `b` бит, где `b = ⌈log2 m⌉`.
+fn write_golomb(n: u64, m: u64, writer: &mut BitWriter) {
+    writer.write_repeated(true, n / m);
+    writer.write_bit(false);
+
+    let remainder = n % m;
+    let b = ceil_log2(m);
+    let cutoff = (1u128 << b) - m as u128;
+
+    if (remainder as u128) < cutoff {
+        writer.write_bits(remainder, b - 1);
+    } else {
+        writer.write_bits((remainder as u128 + cutoff) as u64, b);
+    }
+}
+
+fn read_golomb(m: u64, reader: &mut BitReader) -> Result<u64> {
+    let quotient = read_ones(reader)?;
+
+    let b = ceil_log2(m);
+    let cutoff = (1u128 << b) - m as u128;
+
+    let remainder = if b == 0 {
+        0
+    } else {
+        let short = reader.read_bits(b - 1)? as u128;
+        if short < cutoff {
+            short
+        } else {
+            ((short << 1) | reader.read_bit()? as u128) - cutoff
+        }
+    };
+
+    quotient
+        .checked_mul(m)
+        .and_then(|n| n.checked_add(remainder as u64))
+        .context("Golomb code does not fit into 64 bits")
+}
+
+fn ceil_log2(m: u64) -> u32 {
+    if m <= 1 { 0 } else { bit_length(m - 1) }
+}
+
+/// Числа Фибоначчи `1, 2, 3, 5, 8, ...`, не превышающие `u64::MAX`.
+fn fibonacci_numbers() -> Vec<u64> {
+    let mut numbers = vec![1u64, 2];
+    while let Some(next) = numbers[numbers.len() - 2].checked_add(numbers[numbers.len() - 1]) {
+        numbers.push(next);
+    }
+    numbers
+}
+
+/// Оценивает параметр кода Голомба для чисел с геометрическим распределением по среднему:
+///
+/// `m = ⌈log(1 + p) / -log(p)⌉`, где `p = mean / (mean + 1)`.
+///
+/// Возвращает `1` для пустой последовательности.
+pub fn estimate_golomb_parameter(values: &[u64]) -> u64 {
+    if values.is_empty() {
+        return 1;
+    }
+
+    let mean = values.iter().map(|&v| v as f64).sum::<f64>() / values.len() as f64;
+    let p = mean / (mean + 1.0);
+    if p <= 0.0 {
+        return 1;
+    }
+
+    ((1.0 + p).ln() / -p.ln()).ceil().max(1.0) as u64
+}
+
+/// Подбирает параметр кода Райса, при котором последовательность кодируется наименьшим числом битов.
+pub fn estimate_rice_parameter(values: &[u64]) -> u32 {
+    (0..64)
+        .min_by_key(|&k| values.iter().map(|&v| (v >> k) as u128 + 1 + k as u128).sum::<u128>())
+        .unwrap()
+}
+
+/// Коды чисел с заданным распределением и их характеристики, аналогичные [`crate::Codes`].
+#[derive(Debug, Clone)]
+pub struct IntegerCodes {
+    code: UniversalCode,
+    values: Vec<u64>,
+    probabilities: Vec<f64>,
+    codes: Vec<String>,
+}
+
+impl IntegerCodes {
+    /// Строит коды чисел `values`, встречающихся с вероятностями `probabilities`.
+    pub fn new(code: UniversalCode, values: Vec<u64>, probabilities: Vec<f64>) -> Result<Self> {
+        anyhow::ensure!(
+            values.len() == probabilities.len(),
+            "Got {} values and {} probabilities",
+            values.len(),
+            probabilities.len()
+        );
+
+        let codes = values
+            .iter()
+            .map(|&value| code.code_string(value))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            code,
+            values,
+            probabilities,
+            codes,
+        })
+    }
+
+    /// Строит коды по эмпирическому распределению чисел последовательности.
+    pub fn from_samples(code: UniversalCode, samples: &[u64]) -> Result<Self> {
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+        for &sample in samples {
+            *counts.entry(sample).or_insert(0) += 1;
+        }
+
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        counts.sort_unstable();
+
+        let (values, probabilities) = counts
+            .into_iter()
+            .map(|(value, count)| (value, count as f64 / samples.len() as f64))
+            .unzip();
+        Self::new(code, values, probabilities)
+    }
+
+    pub fn code(&self) -> UniversalCode {
+        self.code
+    }
+
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    pub fn codes(&self) -> &[String] {
+        &self.codes
+    }
+
+    /// Средняя длина кода `Σ(pi * ki)`.
+    pub fn mean_code_length(&self) -> f64 {
+        self.codes
+            .iter()
+            .zip(&self.probabilities)
+            .map(|(code, p)| code.len() as f64 * p)
+            .sum()
+    }
+
+    /// Энтропия распределения `-Σ(pi * log2(pi))`.
+    pub fn entropy(&self) -> f64 {
+        -self
+            .probabilities
+            .iter()
+            .filter(|&&p| p > 0.0)
+            .map(|&p| p * p.log2())
+            .sum::<f64>()
+    }
+
+    /// Коэффициент относительной эффективности `H / Σ(pi * ki)`.
+    pub fn relative_efficiency_ratio(&self) -> f64 {
+        self.entropy() / self.mean_code_length()
+    }
+
+    /// Абсолютная избыточность `Σ(pi * ki) - H` в битах на число.
+    pub fn absolute_redundancy(&self) -> f64 {
+        self.mean_code_length() - self.entropy()
+    }
+
+    /// Сумма Крафта `Σ 2^(-ki)` по кодам распределения. Все коды модуля префиксные, поэтому
+    /// сумма не превышает `1`.
+    pub fn kraft_sum(&self) -> f64 {
+        kraft_sum(self.codes.iter().map(String::as_str))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_codes() -> Vec<UniversalCode> {
+        vec![
+            UniversalCode::EliasGamma,
+            UniversalCode::EliasDelta,
+            UniversalCode::EliasOmega,
+            UniversalCode::Fibonacci,
+            UniversalCode::ExpGolomb(0),
+            UniversalCode::ExpGolomb(3),
+            UniversalCode::Golomb(1),
+            UniversalCode::Golomb(3),
+            UniversalCode::Golomb(10),
+            UniversalCode::Rice(0),
+            UniversalCode::Rice(2),
+        ]
+    }
+
+    #[test]
+    fn test_known_codes() {
+        let cases = [
+            (UniversalCode::EliasGamma, 1, "1"),
+            (UniversalCode::EliasGamma, 2, "010"),
+            (UniversalCode::EliasGamma, 5, "00101"),
+            (UniversalCode::EliasDelta, 1, "1"),
+            (UniversalCode::EliasDelta, 2, "0100"),
+            (UniversalCode::EliasDelta, 10, "00100010"),
+            (UniversalCode::EliasOmega, 1, "0"),
+            (UniversalCode::EliasOmega, 2, "100"),
+            (UniversalCode::EliasOmega, 16, "10100100000"),
+            (UniversalCode::Fibonacci, 1, "11"),
+            (UniversalCode::Fibonacci, 2, "011"),
+            (UniversalCode::Fibonacci, 4, "1011"),
+            (UniversalCode::Fibonacci, 11, "001011"),
+            (UniversalCode::ExpGolomb(0), 0, "1"),
+            (UniversalCode::ExpGolomb(0), 3, "00100"),
+            (UniversalCode::ExpGolomb(1), 0, "10"),
+            (UniversalCode::ExpGolomb(1), 2, "0100"),
+            (UniversalCode::Golomb(3), 0, "00"),
+            (UniversalCode::Golomb(3), 1, "010"),
+            (UniversalCode::Golomb(3), 2, "011"),
+            (UniversalCode::Golomb(3), 3, "100"),
+            (UniversalCode::Rice(2), 5, "1001"),
+        ];
+
+        for (code, n, expected) in cases {
+            assert_eq!(code.code_string(n).unwrap(), expected, "{} of {}", code, n);
+            assert_eq!(code.code_length(n).unwrap(), expected.len());
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut values = (1..=1000).collect::<Vec<u64>>();
+        values.extend([1 << 20, 123_456_789, u32::MAX as u64]);
+
+        for code in all_codes() {
+            // Унарная часть кодов Голомба растет линейно, большие числа для них не проверяем
+            let values = match code {
+                UniversalCode::Golomb(_) | UniversalCode::Rice(_) => &values[..1000],
+                _ => &values[..],
+            };
+
+            let bytes = code.encode_all(values).unwrap();
+            assert_eq!(code.decode_all(&bytes, values.len()).unwrap(), values, "{}", code);
+        }
+
+        for code in [
+            UniversalCode::EliasGamma,
+            UniversalCode::EliasDelta,
+            UniversalCode::EliasOmega,
+            UniversalCode::Fibonacci,
+        ] {
+            let bytes = code.encode_all(&[u64::MAX, 1]).unwrap();
+            assert_eq!(code.decode_all(&bytes, 2).unwrap(), [u64::MAX, 1], "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut writer = BitWriter::new();
+        assert!(UniversalCode::EliasGamma.encode(0, &mut writer).is_err());
+        assert!(UniversalCode::Fibonacci.encode(0, &mut writer).is_err());
+        assert!(UniversalCode::Golomb(0).encode(5, &mut writer).is_err());
+        assert!(UniversalCode::ExpGolomb(0).encode(u64::MAX, &mut writer).is_err());
+        assert!(writer.is_empty());
+
+        // Обрезанный поток
+        assert!(UniversalCode::EliasGamma.decode_all(&[0b0000_0000], 1).is_err());
+    }
+
+    #[test]
+    fn test_estimate_parameters() {
+        assert_eq!(estimate_golomb_parameter(&[]), 1);
+        assert_eq!(estimate_golomb_parameter(&[0, 0, 0]), 1);
+
+        // Для геометрического распределения со средним 9: p = 0.9, m = ⌈log(1.9) / -log(0.9)⌉ = 7
+        assert_eq!(estimate_golomb_parameter(&[9; 10]), 7);
+
+        assert_eq!(estimate_rice_parameter(&[0, 1, 0, 1]), 0);
+        assert_eq!(estimate_rice_parameter(&[1000; 10]), 9);
+    }
+
+    #[test]
+    fn test_integer_codes_metrics() {
+        // Для распределения p(n) = 2^-n гамма-код не оптимален, а код Голомба с m = 1 (унарный) совпадает
+        // с энтропией
+        let values = (0..20).collect::<Vec<u64>>();
+        let probabilities = values.iter().map(|&n| 0.5f64.powi(n as i32 + 1)).collect::<Vec<_>>();
+
+        let unary = IntegerCodes::new(UniversalCode::Golomb(1), values.clone(), probabilities.clone()).unwrap();
+        assert!(unary.absolute_redundancy().abs() < 1e-4);
+        assert!(unary.kraft_sum() <= 1.0);
+
+        let gamma = IntegerCodes::new(
+            UniversalCode::EliasGamma,
+            values.iter().map(|n| n + 1).collect(),
+            probabilities,
+        )
+        .unwrap();
+        assert!(gamma.mean_code_length() > unary.mean_code_length());
+        assert!(gamma.relative_efficiency_ratio() < 1.0);
+
+        let samples = IntegerCodes::from_samples(UniversalCode::Rice(1), &[0, 0, 1, 3]).unwrap();
+        assert_eq!(samples.values(), [0, 1, 3]);
+        assert_eq!(samples.probabilities(), [0.5, 0.25, 0.25]);
+        assert_eq!(samples.codes(), ["00", "01", "101"]);
+    }
+}
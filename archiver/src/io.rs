@@ -6,12 +6,14 @@ use std::{
     str::FromStr,
 };
 
-use crate::Codes;
+use crate::{Codes, Word};
 
 mod choice_encoder;
+mod progress_bar;
 pub use choice_encoder::EncoderChoice;
+pub use progress_bar::ProgressBar;
 
-pub fn print_codes(name: &str, codes: &Codes) {
+pub fn print_codes<W: Word>(name: &str, codes: &Codes<W>) {
     println!("{}:", name);
     println!("Probabilities: {:?}", codes.probabilities());
     println!("Codes: {:?}", codes.codes());
@@ -21,6 +23,17 @@ pub fn print_codes(name: &str, codes: &Codes) {
         "Statistical compression ratio: {}",
         codes.statistical_compression_ratio()
     );
+    println!(
+        "Kraft sum: {} (prefix-free: {}, complete: {})",
+        codes.kraft_sum(),
+        codes.is_prefix_free(),
+        codes.is_complete()
+    );
+    println!(
+        "Redundancy: absolute {}, relative {}",
+        codes.absolute_redundancy(),
+        codes.relative_redundancy()
+    );
     print!("\n");
 }
 
@@ -0,0 +1,105 @@
+use std::io::Write;
+
+use crate::progress::{Phase, Progress, ProgressObserver};
+
+/// Полоса прогресса в терминале, рисуется в stderr.
+#[derive(Debug)]
+pub struct ProgressBar {
+    width: usize,
+    /// Последнее нарисованное состояние, чтобы не перерисовывать полосу без изменений.
+    last: Option<(Phase, usize)>,
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        Self { width: 40, last: None }
+    }
+}
+
+impl ProgressBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Завершает строку полосы, чтобы следующий вывод начинался с новой строки.
+    pub fn finish(&mut self) {
+        if self.last.take().is_some() {
+            eprintln!();
+        }
+    }
+
+    fn render(&self, progress: &Progress) -> String {
+        let filled = (progress.fraction() * self.width as f64).round() as usize;
+        let ratio = progress
+            .compression_ratio
+            .map(|ratio| format!(" ratio {:.2}%", ratio * 100.0))
+            .unwrap_or_default();
+
+        format!(
+            "[{}{}] {:>3}% {:<20}{}",
+            "#".repeat(filled),
+            " ".repeat(self.width - filled),
+            (progress.fraction() * 100.0).floor(),
+            progress.phase.to_string(),
+            ratio
+        )
+    }
+}
+
+impl ProgressObserver for ProgressBar {
+    fn on_progress(&mut self, progress: &Progress) {
+        let percent = (progress.fraction() * 100.0) as usize;
+        if self.last == Some((progress.phase, percent)) {
+            return;
+        }
+
+        // Новый этап начинается с новой строки
+        if self.last.is_some_and(|(phase, _)| phase != progress.phase) {
+            eprintln!();
+        }
+        self.last = Some((progress.phase, percent));
+
+        eprint!("\r{}", self.render(progress));
+        std::io::stderr().flush().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let bar = ProgressBar::new().with_width(10);
+        let progress = Progress {
+            phase: Phase::Encoding,
+            processed: 50,
+            total: 200,
+            compression_ratio: Some(0.625),
+        };
+
+        assert_eq!(
+            bar.render(&progress),
+            "[###       ]  25% Encoding             ratio 62.50%"
+        );
+    }
+
+    #[test]
+    fn test_render_overflow() {
+        // Обработано больше оценки: полоса заполнена, но не выходит за ширину
+        let bar = ProgressBar::new().with_width(10);
+        let progress = Progress {
+            phase: Phase::Reading,
+            processed: 300,
+            total: 200,
+            compression_ratio: None,
+        };
+
+        assert_eq!(bar.render(&progress), "[##########] 100% Reading             ");
+    }
+}
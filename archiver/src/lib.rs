@@ -1,29 +1,51 @@
 use std::collections::HashMap;
 
+pub mod analyzer;
+pub mod bench;
+pub mod bits;
+mod code_tree;
 mod codes;
 mod decoder;
+pub mod dedup;
+mod dictionary;
 mod encoder;
+mod filters;
 mod freq_map;
+mod hu_tucker;
 mod huffman;
+pub mod integer_codes;
 pub mod io;
+pub mod progress;
+pub mod report;
+mod rle;
+mod seekable;
 mod shannon_fano;
 mod state_saver;
+mod utf8;
 pub mod utils;
 
+pub use code_tree::CodeTree;
 pub use codes::Codes;
 pub use decoder::{Decoder, FileDecoder};
+pub use dictionary::Dictionary;
 pub use encoder::{Encoder, FileEncoder};
+pub use filters::{Filter, FilteredArchiver};
 pub(crate) use freq_map::FrequencyMap;
-pub use huffman::HuffmanArchiver;
+pub use hu_tucker::HuTuckerEncoder;
+pub use huffman::{HuffmanArchiver, Word};
+pub use rle::{RleArchiver, RleHuffmanArchiver};
+pub use seekable::{BlockIndexEntry, SeekableArchiver, SeekableReader};
 pub use shannon_fano::ShannonFanoEncoder;
 pub(crate) use state_saver::StateSaver;
+pub use utf8::Utf8Archiver;
 pub use utils::create_probabilities_map;
 
 pub trait CodesBuilder {
     /// Строит оптимальный код на основе вероятностей вхождений символов.
     /// Сумма `probabilities` должна быть равна `1`.
     /// Возвращает вектор строк, где каждый элемент - код символа.
-    fn build_optimal_codes(words: Vec<u8>, probabilities: Vec<f64>) -> Codes;
+    /// Слова могут быть любого типа [`Word`], например номерами символов алфавита больше 256 символов.
+    fn build_optimal_codes<W: Word>(words: Vec<W>, probabilities: Vec<f64>) -> Codes<W>;
 
     fn build_optimal_codes_from_hashmap(words_probabilities: HashMap<u8, f64>) -> Codes {
         let (keys, values): (Vec<_>, Vec<_>) = words_probabilities.into_iter().unzip();
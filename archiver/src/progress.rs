@@ -0,0 +1,154 @@
+use anyhow::{Context, Result};
+use std::{
+    fmt,
+    fs::File,
+    io::{BufWriter, Read},
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+/// Этап операции архивации или распаковки.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Подсчет частот байтов исходного файла.
+    CountingFrequencies,
+    /// Чтение сжатых данных архива.
+    Reading,
+    Encoding,
+    Decoding,
+    /// Запись результата на диск.
+    Writing,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Phase::CountingFrequencies => "Counting frequencies",
+            Phase::Reading => "Reading",
+            Phase::Encoding => "Encoding",
+            Phase::Decoding => "Decoding",
+            Phase::Writing => "Writing",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Состояние операции, передаваемое наблюдателю.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub phase: Phase,
+    /// Количество обработанных на текущем этапе байтов.
+    pub processed: u64,
+    /// Общее количество байтов текущего этапа.
+    pub total: u64,
+    /// Отношение размера сжатых данных к размеру исходных на текущий момент, если оно известно.
+    pub compression_ratio: Option<f64>,
+}
+
+impl Progress {
+    /// Доля выполненной работы текущего этапа от `0` до `1`. Если обработано больше оценки `total`,
+    /// доля равна `1`.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.processed as f64 / self.total as f64).min(1.0)
+        }
+    }
+}
+
+/// Наблюдатель за ходом операции. Реализован для любого замыкания `FnMut(&Progress)`.
+pub trait ProgressObserver {
+    fn on_progress(&mut self, progress: &Progress);
+}
+
+impl<F> ProgressObserver for F
+where
+    F: FnMut(&Progress),
+{
+    fn on_progress(&mut self, progress: &Progress) {
+        self(progress)
+    }
+}
+
+/// Наблюдатель, игнорирующий все события.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressObserver for NoProgress {
+    fn on_progress(&mut self, _progress: &Progress) {}
+}
+
+/// Токен отмены операции. Клоны разделяют один флаг, поэтому операцию можно отменить из другого
+/// потока или обработчика сигнала.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Возвращает ошибку [`Cancelled`], если операция отменена.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() { Err(Cancelled) } else { Ok(()) }
+    }
+}
+
+/// Размер части файла, после обработки которой сообщается прогресс и проверяется отмена.
+pub(crate) const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Читает файл частями по [`CHUNK_SIZE`] байтов и передает их в `f` вместе с количеством
+/// прочитанных к этому моменту байтов.
+pub(crate) fn for_each_chunk(path: &Path, mut f: impl FnMut(&[u8], u64) -> Result<()>) -> Result<()> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut processed = 0;
+
+    loop {
+        let n = file.read(&mut buf).context("Failed to read file")?;
+        if n == 0 {
+            return Ok(());
+        }
+        processed += n as u64;
+        f(&buf[..n], processed)?;
+    }
+}
+
+/// Создает файл `path` и записывает его через `write`. Если запись завершилась ошибкой или была
+/// отменена, файл удаляется.
+pub(crate) fn remove_on_error(path: &Path, write: impl FnOnce(&mut BufWriter<File>) -> Result<()>) -> Result<()> {
+    let mut file = BufWriter::new(File::create(path).context("Failed to create file")?);
+    let result = write(&mut file);
+
+    if result.is_err() {
+        drop(file);
+        std::fs::remove_file(path).ok();
+    }
+    result
+}
+
+/// Ошибка отмененной операции. Проверяется через `error.is::<Cancelled>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Operation was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
@@ -0,0 +1,184 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::{Codes, Word, analyzer::csv_field};
+
+/// Характеристики построенного кода для сравнения способов кодирования.
+#[derive(Debug, Clone, Serialize)]
+pub struct CodesReport {
+    pub name: String,
+    pub words: Vec<u64>,
+    pub probabilities: Vec<f64>,
+    pub codes: Vec<String>,
+    pub entropy: f64,
+    pub mean_code_length: f64,
+    pub relative_efficiency_ratio: f64,
+    pub statistical_compression_ratio: f64,
+    pub kraft_sum: f64,
+    pub absolute_redundancy: f64,
+}
+
+impl CodesReport {
+    /// Заголовок CSV, соответствующий строкам [`CodesReport::to_csv_rows`].
+    pub const CSV_HEADER: &str = "name,word,probability,code,entropy,mean_code_length,relative_efficiency_ratio,\
+        statistical_compression_ratio,kraft_sum,absolute_redundancy";
+
+    pub fn new<W: Word>(name: &str, codes: &Codes<W>) -> Self {
+        Self {
+            name: name.to_string(),
+            words: codes.words().iter().map(|&word| word.into()).collect(),
+            probabilities: codes.probabilities().to_vec(),
+            codes: codes.codes().to_vec(),
+            entropy: codes.entropy(),
+            mean_code_length: codes.mean_code_length(),
+            relative_efficiency_ratio: codes.relative_efficiency_ratio(),
+            statistical_compression_ratio: codes.statistical_compression_ratio(),
+            kraft_sum: codes.kraft_sum(),
+            absolute_redundancy: codes.absolute_redundancy(),
+        }
+    }
+
+    /// Строки CSV без заголовка, по одной на каждый символ. Характеристики кода повторяются в каждой строке.
+    pub fn to_csv_rows(&self) -> Vec<String> {
+        (0..self.codes.len())
+            .map(|i| {
+                format!(
+                    "{},{},{},{},{},{},{},{},{},{}",
+                    csv_field(&self.name),
+                    self.words[i],
+                    self.probabilities[i],
+                    self.codes[i],
+                    self.entropy,
+                    self.mean_code_length,
+                    self.relative_efficiency_ratio,
+                    self.statistical_compression_ratio,
+                    self.kraft_sum,
+                    self.absolute_redundancy,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Таблица Markdown с выровненными столбцами, по одной строке на каждый код.
+pub fn to_markdown(reports: &[CodesReport]) -> String {
+    let rows = reports
+        .iter()
+        .map(|report| {
+            vec![
+                report.name.clone(),
+                join(&report.probabilities),
+                join(&report.codes),
+                report.mean_code_length.to_string(),
+                report.relative_efficiency_ratio.to_string(),
+                report.statistical_compression_ratio.to_string(),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    markdown_table(
+        &[
+            "Name",
+            "Probabilities",
+            "Codes",
+            "Mean length",
+            "Relative efficiency ratio",
+            "Statistical compression ratio",
+        ],
+        &rows,
+    )
+}
+
+/// Строит таблицу Markdown, дополняя ячейки пробелами до ширины самой длинной ячейки столбца.
+pub(crate) fn markdown_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths = header.iter().map(|cell| cell.chars().count()).collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &mut dyn Iterator<Item = String>| {
+        let cells = cells
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let mut table = format_row(&mut header.iter().map(|cell| cell.to_string()));
+    table.push_str(&format_row(&mut widths.iter().map(|&width| "-".repeat(width))));
+    for row in rows {
+        table.push_str(&format_row(&mut row.iter().cloned()));
+    }
+    table
+}
+
+/// CSV с заголовком [`CodesReport::CSV_HEADER`].
+pub fn to_csv(reports: &[CodesReport]) -> String {
+    let mut csv = format!("{}\n", CodesReport::CSV_HEADER);
+    for row in reports.iter().flat_map(CodesReport::to_csv_rows) {
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn to_json(reports: &[CodesReport]) -> Result<String> {
+    serde_json::to_string_pretty(reports).context("Failed to serialize report to JSON")
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values.iter().map(T::to_string).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CodesBuilder, HuffmanArchiver, ShannonFanoEncoder};
+
+    fn new_test_reports() -> Vec<CodesReport> {
+        let probabilities = vec![0.170, 0.168, 0.166, 0.140, 0.118, 0.110, 0.083, 0.045];
+        let words = (0..probabilities.len() as u8).collect::<Vec<_>>();
+
+        vec![
+            CodesReport::new(
+                "Shannon-Fano",
+                &ShannonFanoEncoder::build_optimal_codes(words.clone(), probabilities.clone()),
+            ),
+            CodesReport::new("Huffman", &HuffmanArchiver::build_optimal_codes(words, probabilities)),
+        ]
+    }
+
+    #[test]
+    fn test_markdown() {
+        let markdown = to_markdown(&new_test_reports());
+        let lines = markdown.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("| Name         | Probabilities"));
+        assert!(lines[1].starts_with("| ------------ | ----"));
+        assert!(lines[2].contains("| 0.17, 0.168, 0.166, 0.14, 0.118, 0.11, 0.083, 0.045 |"));
+        assert!(lines[3].starts_with("| Huffman      |"));
+
+        // Столбцы выровнены
+        let width = lines[0].chars().count();
+        assert!(lines.iter().all(|line| line.chars().count() == width));
+    }
+
+    #[test]
+    fn test_csv_and_json() {
+        let reports = new_test_reports();
+
+        let csv = to_csv(&reports);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1 + 2 * 8);
+        assert_eq!(lines[0], CodesReport::CSV_HEADER);
+        assert!(lines[1].starts_with("Shannon-Fano,0,0.17,00,"));
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&reports).unwrap()).unwrap();
+        assert_eq!(json[1]["name"], "Huffman");
+        assert_eq!(json[1]["codes"].as_array().unwrap().len(), 8);
+        assert_eq!(json[1]["mean_code_length"], reports[1].mean_code_length);
+    }
+}
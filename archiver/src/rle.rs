@@ -0,0 +1,347 @@
+use anyhow::{Context, Result};
+use std::{cell::RefCell, collections::HashMap, fmt, fs::File, path::Path};
+
+use crate::{
+    Decoder, Encoder, FileEncoder, StateSaver,
+    huffman::{HuffmanArchiver, HuffmanDecoder, MAX_SYMBOL_CODE_LENGTH, load_symbol_table, save_symbol_table},
+    utils::convert_to_string,
+};
+
+/// Максимальная длина серии и блока литералов PackBits.
+const MAX_PACKBITS_RUN: usize = 128;
+
+/// Максимальная длина серии в паре (байт, длина серии): длина хранится в младшем байте символа.
+const MAX_PAIR_RUN: usize = 256;
+
+/// Длина серии одинаковых байтов в начале `bytes`, не больше `max_run`.
+fn run_length(bytes: &[u8], max_run: usize) -> usize {
+    bytes.iter().take(max_run).take_while(|&&b| b == bytes[0]).count()
+}
+
+/// Кодирует данные схемой PackBits. Каждый блок начинается с управляющего байта `n`:
+///
+/// - `0..=127` - следующие `n + 1` байтов копируются без изменений;
+/// - `129..=255` - следующий байт повторяется `257 - n` раз (от 2 до 128);
+/// - `128` не используется.
+pub fn pack_bits(bytes: &[u8]) -> Vec<u8> {
+    let mut packed = Vec::with_capacity(bytes.len() / 2);
+    let mut literal_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let run = run_length(&bytes[i..], MAX_PACKBITS_RUN);
+
+        // Серия из двух байтов занимает столько же, сколько литералы, но разрывает блок литералов
+        if run >= 3 {
+            push_literals(&bytes[literal_start..i], &mut packed);
+            packed.push((257 - run) as u8);
+            packed.push(bytes[i]);
+            literal_start = i + run;
+        }
+        i += run;
+    }
+
+    push_literals(&bytes[literal_start..], &mut packed);
+    packed
+}
+
+fn push_literals(literals: &[u8], packed: &mut Vec<u8>) {
+    for chunk in literals.chunks(MAX_PACKBITS_RUN) {
+        packed.push((chunk.len() - 1) as u8);
+        packed.extend_from_slice(chunk);
+    }
+}
+
+/// Восстанавливает данные, закодированные [`pack_bits`].
+pub fn unpack_bits(packed: &[u8]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(packed.len() * 2);
+    let mut i = 0;
+
+    while i < packed.len() {
+        let header = packed[i] as usize;
+        i += 1;
+
+        match header {
+            0..=127 => {
+                let literals = packed
+                    .get(i..i + header + 1)
+                    .with_context(|| format!("Truncated literal block at byte {}", i - 1))?;
+                bytes.extend_from_slice(literals);
+                i += header + 1;
+            }
+            128 => {}
+            _ => {
+                let &byte = packed
+                    .get(i)
+                    .with_context(|| format!("Truncated run at byte {}", i - 1))?;
+                bytes.resize(bytes.len() + 257 - header, byte);
+                i += 1;
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Архиватор, кодирующий серии одинаковых байтов схемой PackBits. Серия длиной до 128 байтов
+/// занимает два байта, поэтому файлы из длинных серий сжимаются сильнее, чем кодом Хаффмана,
+/// который тратит на каждый байт хотя бы один бит.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RleArchiver;
+
+impl RleArchiver {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Архивирует файл в формате [`FileEncoder`].
+    pub fn archive<P: AsRef<Path>>(target: P, destination: P) -> Result<()> {
+        Self.encode_file(&target.as_ref().to_path_buf(), &destination.as_ref().to_path_buf())
+    }
+}
+
+impl Encoder for RleArchiver {
+    fn convert_to_string(&self, bytes: &[u8]) -> Result<String> {
+        Ok(convert_to_string(&pack_bits(bytes)))
+    }
+
+    fn encode_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        Ok(pack_bits(bytes))
+    }
+}
+
+impl Decoder for RleArchiver {
+    fn decode_string(&self, bit_string: &str) -> Result<Vec<u8>> {
+        unpack_bits(&<Self as Encoder>::convert_to_bytes(bit_string.to_string()))
+    }
+
+    fn decode_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        unpack_bits(bytes)
+    }
+}
+
+impl StateSaver for RleArchiver {
+    fn save_state(self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn load_state(state: Vec<u8>) -> Result<Self> {
+        anyhow::ensure!(state.is_empty(), "RLE archive has no state, got {} bytes", state.len());
+        Ok(Self)
+    }
+}
+
+/// Разбивает данные на пары (байт, длина серии). Пара хранится в символе `byte << 8 | (run - 1)`,
+/// серии длиннее 256 байтов разбиваются на несколько пар.
+pub fn to_pairs(bytes: &[u8]) -> Vec<u32> {
+    let mut pairs = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let run = run_length(&bytes[i..], MAX_PAIR_RUN);
+        pairs.push((bytes[i] as u32) << 8 | (run - 1) as u32);
+        i += run;
+    }
+    pairs
+}
+
+/// Восстанавливает данные из пар, построенных [`to_pairs`].
+pub fn from_pairs(pairs: &[u32]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    for &pair in pairs {
+        anyhow::ensure!(pair <= 0xFFFF, "Invalid run pair: {:#x}", pair);
+        let byte = (pair >> 8) as u8;
+        let run = (pair & 0xFF) as usize + 1;
+        bytes.resize(bytes.len() + run, byte);
+    }
+    Ok(bytes)
+}
+
+/// Архиватор, кодирующий кодом Хаффмана пары (байт, длина серии) из [`to_pairs`].
+///
+/// Частые пары, например длинные серии фона в битовой маске, получают короткие коды, поэтому
+/// серия из сотен байтов может занимать несколько битов. Таблица кодов хранит пары.
+pub struct RleHuffmanArchiver {
+    word_code: HashMap<u32, String>,
+    decoder: RefCell<Option<HuffmanDecoder<u32>>>,
+}
+
+impl Clone for RleHuffmanArchiver {
+    fn clone(&self) -> Self {
+        Self::from_word_code(self.word_code.clone())
+    }
+}
+
+impl fmt::Debug for RleHuffmanArchiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RleHuffmanArchiver")
+            .field("word_code", &self.word_code)
+            .finish()
+    }
+}
+
+impl RleHuffmanArchiver {
+    /// Строит коды по частотам пар (байт, длина серии) в `bytes`.
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut counts = HashMap::new();
+        for pair in to_pairs(bytes) {
+            *counts.entry(pair).or_insert(0) += 1;
+        }
+        Self::from_word_code(HuffmanArchiver::build_length_limited_codes(&counts, MAX_SYMBOL_CODE_LENGTH).into())
+    }
+
+    fn from_word_code(word_code: HashMap<u32, String>) -> Self {
+        Self {
+            word_code,
+            decoder: RefCell::new(None),
+        }
+    }
+
+    pub fn word_code(&self) -> &HashMap<u32, String> {
+        &self.word_code
+    }
+
+    /// Архивирует файл в формате [`FileEncoder`].
+    pub fn archive<P: AsRef<Path>>(target: P, destination: P) -> Result<()> {
+        let bytes = std::fs::read(target).context("Failed to read file")?;
+        let mut file = File::create(destination).context("Failed to create file")?;
+        Self::new(&bytes).encode_to_writer(&bytes, &mut file)
+    }
+
+    fn decoder(&self) -> Result<std::cell::Ref<'_, HuffmanDecoder<u32>>> {
+        if self.decoder.borrow().is_none() {
+            let decoder = HuffmanDecoder::try_from(&self.word_code)?;
+            *self.decoder.borrow_mut() = Some(decoder);
+        }
+
+        Ok(std::cell::Ref::map(self.decoder.borrow(), |opt| opt.as_ref().unwrap()))
+    }
+}
+
+impl Encoder for RleHuffmanArchiver {
+    fn convert_to_string(&self, bytes: &[u8]) -> Result<String> {
+        let mut bit_string = String::new();
+
+        for pair in to_pairs(bytes) {
+            let code = self
+                .word_code
+                .get(&pair)
+                .with_context(|| format!("Unknown run pair: {:#x}", pair))?;
+            bit_string.push_str(code);
+        }
+        Ok(bit_string)
+    }
+}
+
+impl Decoder for RleHuffmanArchiver {
+    fn decode_string(&self, bit_string: &str) -> Result<Vec<u8>> {
+        if self.word_code.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let pairs = self.decoder()?.decode_symbols(bit_string)?;
+        from_pairs(&pairs)
+    }
+}
+
+impl StateSaver for RleHuffmanArchiver {
+    fn save_state(self) -> Result<Vec<u8>> {
+        Ok(save_symbol_table(self.word_code))
+    }
+
+    fn load_state(state: Vec<u8>) -> Result<Self> {
+        let word_code = load_symbol_table(&state, |pair| pair <= 0xFFFF)?;
+        Ok(Self::from_word_code(word_code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileDecoder;
+    use std::io::Cursor;
+
+    fn round_trip<E: FileEncoder + FileDecoder>(encoder: E, bytes: &[u8]) -> usize {
+        let mut archive = Vec::new();
+        encoder.encode_to_writer(bytes, &mut archive).unwrap();
+        assert_eq!(E::decode_from_reader(&mut Cursor::new(&archive)).unwrap(), bytes);
+        archive.len()
+    }
+
+    fn test_inputs() -> Vec<Vec<u8>> {
+        vec![
+            Vec::new(),
+            vec![7],
+            vec![b'a'; 1000],
+            b"abcabcabc".to_vec(),
+            (0..=255).cycle().take(700).collect(),
+            [vec![0; 300], b"xy".to_vec(), vec![1; 2], vec![0; 129]].concat(),
+        ]
+    }
+
+    #[test]
+    fn test_pack_bits() {
+        // Пример из описания PackBits Apple Technical Note TN1023
+        let bytes = [
+            0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0xAA, 0xAA, 0xAA, 0xAA, 0x80, 0x00, 0x2A, 0x22, 0xAA, 0xAA, 0xAA, 0xAA,
+            0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA,
+        ];
+        let packed = [
+            0xFE, 0xAA, 0x02, 0x80, 0x00, 0x2A, 0xFD, 0xAA, 0x03, 0x80, 0x00, 0x2A, 0x22, 0xF7, 0xAA,
+        ];
+        assert_eq!(pack_bits(&bytes), packed);
+        assert_eq!(unpack_bits(&packed).unwrap(), bytes);
+
+        // Управляющий байт 128 пропускается, обрезанные блоки - ошибка
+        assert_eq!(unpack_bits(&[0x80, 0x00, 0x05]).unwrap(), [0x05]);
+        assert!(unpack_bits(&[0x02, 0x01]).is_err());
+        assert!(unpack_bits(&[0xFE]).is_err());
+    }
+
+    #[test]
+    fn test_rle_round_trip() {
+        for bytes in test_inputs() {
+            round_trip(RleArchiver::new(), &bytes);
+        }
+
+        // 1000 одинаковых байтов - 8 серий по 2 байта
+        let size = round_trip(RleArchiver::new(), &[b'a'; 1000]);
+        assert_eq!(size, 2 * std::mem::size_of::<usize>() + 16);
+    }
+
+    #[test]
+    fn test_pairs() {
+        let bytes = [vec![5; 300], vec![6]].concat();
+        let pairs = to_pairs(&bytes);
+        assert_eq!(pairs, [5 << 8 | 255, 5 << 8 | 43, 6 << 8]);
+        assert_eq!(from_pairs(&pairs).unwrap(), bytes);
+        assert!(from_pairs(&[0x10000]).is_err());
+    }
+
+    #[test]
+    fn test_rle_huffman_round_trip() {
+        for bytes in test_inputs() {
+            round_trip(RleHuffmanArchiver::new(&bytes), &bytes);
+        }
+
+        // Единственная пара кодируется одним битом
+        let archiver = RleHuffmanArchiver::new(&[b'a'; 200]);
+        assert_eq!(archiver.word_code()[&(u32::from(b'a') << 8 | 199)], "0");
+    }
+
+    #[test]
+    fn test_rle_huffman_less_than_bit_per_byte() {
+        // Битовая маска: длинные серии фона и короткие серии объекта
+        let mask = [vec![0u8; 200], vec![255u8; 56]].concat().repeat(100);
+        let encoded = RleHuffmanArchiver::new(&mask).encode_bytes(&mask).unwrap();
+        assert!(encoded.len() * 8 < mask.len() / 10, "{} bytes", encoded.len());
+
+        // Серии другой длины нет в таблице кодов
+        assert!(RleHuffmanArchiver::new(&mask).encode_bytes(&[0u8; 7]).is_err());
+
+        let state = RleHuffmanArchiver::new(&mask).save_state().unwrap();
+        assert!(RleHuffmanArchiver::load_state(state).is_ok());
+        assert!(RleArchiver::load_state(vec![1]).is_err());
+    }
+}
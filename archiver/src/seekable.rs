@@ -0,0 +1,503 @@
+use anyhow::{Context, Result};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::Path,
+};
+
+use crate::{
+    FrequencyMap, HuffmanArchiver, StateSaver,
+    bits::{BitReader, BitWriter},
+    huffman::MAX_CODE_LENGTH,
+};
+
+/// Сигнатура в начале и в конце архива с произвольным доступом.
+const MAGIC: &[u8; 4] = b"HSEK";
+
+/// Размер записи индекса: смещение в исходных данных, смещение в сжатых данных и номер бита.
+const INDEX_ENTRY_SIZE: usize = 8 + 8 + 1;
+
+/// Размер окончания архива: смещение индекса и сигнатура.
+const TRAILER_SIZE: usize = 8 + MAGIC.len();
+
+/// Архиватор Хаффмана с произвольным доступом.
+///
+/// Таблица кодов общая для всего файла, а блоки по `block_size` байтов кодируются независимо
+/// и записываются подряд без выравнивания. В конце архива записывается индекс, по которому можно
+/// распаковать любой диапазон байтов, декодируя только пересекающиеся с ним блоки.
+///
+/// Формат архива:
+///
+/// `[HSEK][state_len][state][original_size u64][block_size u64][payload][index][index_offset u64][HSEK]`
+///
+/// где индекс - количество блоков (u64) и для каждого блока смещение в исходных данных (u64),
+/// смещение первого байта блока в `payload` (u64) и номер бита в этом байте (u8).
+#[derive(Debug, Clone)]
+pub struct SeekableArchiver {
+    block_size: usize,
+}
+
+impl Default for SeekableArchiver {
+    fn default() -> Self {
+        Self {
+            block_size: Self::DEFAULT_BLOCK_SIZE,
+        }
+    }
+}
+
+impl SeekableArchiver {
+    pub const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Panics
+    /// Паникует, если `block_size == 0`.
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        assert!(block_size > 0, "Block size must be positive");
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Архивирует файл в формате с произвольным доступом.
+    pub fn archive<P: AsRef<Path>>(&self, target: P, destination: P) -> Result<()> {
+        let bytes = std::fs::read(target).context("Failed to read file")?;
+        let mut file = BufWriter::new(File::create(destination).context("Failed to create file")?);
+        self.encode_to_writer(&bytes, &mut file)?;
+        file.flush().context("Failed to write archive")
+    }
+
+    pub fn encode_to_writer<W: Write>(&self, bytes: &[u8], writer: &mut W) -> Result<()> {
+        let mut freq_map = FrequencyMap::new();
+        freq_map.consume(bytes);
+
+        // Коды ограничены по длине, чтобы таблицу всегда можно было сохранить
+        let codes = HuffmanArchiver::build_length_limited_codes(freq_map.counts(), MAX_CODE_LENGTH);
+        let archiver = HuffmanArchiver::from_codes(codes);
+
+        let mut bits = BitWriter::new();
+        let mut index = Vec::with_capacity(bytes.len().div_ceil(self.block_size));
+
+        for (i, block) in bytes.chunks(self.block_size).enumerate() {
+            index.push(BlockIndexEntry {
+                uncompressed_offset: (i * self.block_size) as u64,
+                compressed_offset: (bits.len() / 8) as u64,
+                bit_offset: (bits.len() % 8) as u8,
+            });
+            archiver.write_bits(block, &mut bits)?;
+        }
+
+        let state = archiver.save_state()?;
+        let payload = bits.into_bytes();
+
+        let mut header = Vec::new();
+        header.extend_from_slice(MAGIC);
+        HuffmanArchiver::write_state(&state, &mut header)?;
+        header.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        header.extend_from_slice(&(self.block_size as u64).to_le_bytes());
+
+        let mut trailer = Vec::with_capacity(8 + index.len() * INDEX_ENTRY_SIZE + TRAILER_SIZE);
+        trailer.extend_from_slice(&(index.len() as u64).to_le_bytes());
+        for entry in &index {
+            trailer.extend_from_slice(&entry.uncompressed_offset.to_le_bytes());
+            trailer.extend_from_slice(&entry.compressed_offset.to_le_bytes());
+            trailer.push(entry.bit_offset);
+        }
+        trailer.extend_from_slice(&((header.len() + payload.len()) as u64).to_le_bytes());
+        trailer.extend_from_slice(MAGIC);
+
+        writer.write_all(&header).context("Failed to write header")?;
+        writer.write_all(&payload).context("Failed to write encoded data")?;
+        writer.write_all(&trailer).context("Failed to write block index")?;
+        Ok(())
+    }
+}
+
+/// Положение начала блока в исходных и сжатых данных.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockIndexEntry {
+    pub uncompressed_offset: u64,
+    /// Смещение байта в `payload`, с которого начинается блок.
+    pub compressed_offset: u64,
+    /// Номер бита в этом байте, начиная со старшего.
+    pub bit_offset: u8,
+}
+
+/// Чтение диапазонов байтов из архива [`SeekableArchiver`].
+pub struct SeekableReader<R: Read + Seek> {
+    reader: R,
+    archiver: HuffmanArchiver,
+    original_size: u64,
+    block_size: u64,
+    payload_offset: u64,
+    index_offset: u64,
+    index: Vec<BlockIndexEntry>,
+}
+
+impl SeekableReader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path).context("Failed to open archive")?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> SeekableReader<R> {
+    /// Проверяет сигнатуру в начале архива, не изменяя позицию чтения.
+    pub fn is_seekable_archive(reader: &mut R) -> Result<bool> {
+        let position = reader.stream_position().context("Failed to get position")?;
+
+        let mut magic = [0u8; MAGIC.len()];
+        let is_seekable = reader.read_exact(&mut magic).is_ok() && &magic == MAGIC;
+
+        reader
+            .seek(SeekFrom::Start(position))
+            .context("Failed to restore position")?;
+        Ok(is_seekable)
+    }
+
+    /// Читает заголовок и индекс блоков. Сжатые данные не читаются.
+    pub fn new(mut reader: R) -> Result<Self> {
+        reader.rewind().context("Failed to seek to archive start")?;
+
+        let mut magic = [0u8; MAGIC.len()];
+        reader.read_exact(&mut magic).context("Failed to read signature")?;
+        anyhow::ensure!(&magic == MAGIC, "Not a seekable archive: invalid signature");
+
+        let state = HuffmanArchiver::read_state(&mut reader)?;
+        let archiver = HuffmanArchiver::load_state(state)?;
+        let original_size = read_u64(&mut reader).context("Failed to read original size")?;
+        let block_size = read_u64(&mut reader).context("Failed to read block size")?;
+        let payload_offset = reader.stream_position().context("Failed to get position")?;
+
+        anyhow::ensure!(block_size > 0, "Corrupted archive: block size is zero");
+
+        // Окончание архива
+        let archive_size = reader.seek(SeekFrom::End(0)).context("Failed to seek to archive end")?;
+        anyhow::ensure!(
+            archive_size >= payload_offset + TRAILER_SIZE as u64,
+            "Corrupted archive: missing block index"
+        );
+        reader
+            .seek(SeekFrom::End(-(TRAILER_SIZE as i64)))
+            .context("Failed to seek to block index")?;
+        let index_offset = read_u64(&mut reader).context("Failed to read index offset")?;
+        reader.read_exact(&mut magic).context("Failed to read signature")?;
+        anyhow::ensure!(&magic == MAGIC, "Corrupted archive: invalid trailing signature");
+        anyhow::ensure!(
+            (payload_offset..=archive_size - TRAILER_SIZE as u64).contains(&index_offset),
+            "Corrupted archive: index offset {} is out of bounds",
+            index_offset
+        );
+
+        // Каждый байт кодируется хотя бы одним битом, поэтому исходный размер ограничен размером данных
+        let payload_size = index_offset - payload_offset;
+        anyhow::ensure!(
+            original_size / 8 <= payload_size,
+            "Corrupted archive: {} bytes cannot be encoded in {} bytes",
+            original_size,
+            payload_size
+        );
+
+        // Индекс блоков
+        reader
+            .seek(SeekFrom::Start(index_offset))
+            .context("Failed to seek to block index")?;
+        let count = read_u64(&mut reader).context("Failed to read block count")?;
+        anyhow::ensure!(
+            count == original_size.div_ceil(block_size),
+            "Corrupted archive: {} blocks for {} bytes with block size {}",
+            count,
+            original_size,
+            block_size
+        );
+        let index_size = count
+            .checked_mul(INDEX_ENTRY_SIZE as u64)
+            .and_then(|size| size.checked_add(8));
+        anyhow::ensure!(
+            index_size == Some(archive_size - TRAILER_SIZE as u64 - index_offset),
+            "Corrupted archive: index of {} blocks does not fit between offset {} and the archive end",
+            count,
+            index_offset
+        );
+
+        let mut index = Vec::with_capacity(count as usize);
+        let mut previous_position = 0;
+        for i in 0..count {
+            let mut entry = [0u8; INDEX_ENTRY_SIZE];
+            reader
+                .read_exact(&mut entry)
+                .with_context(|| format!("Failed to read index entry {}", i))?;
+
+            let entry = BlockIndexEntry {
+                uncompressed_offset: u64::from_le_bytes(entry[0..8].try_into().unwrap()),
+                compressed_offset: u64::from_le_bytes(entry[8..16].try_into().unwrap()),
+                bit_offset: entry[16],
+            };
+            // Блоки идут подряд с начала данных: позиции в битах не убывают, а последний байт блока
+            // находится внутри данных
+            anyhow::ensure!(
+                entry.uncompressed_offset == i * block_size
+                    && entry.bit_offset < 8
+                    && entry.compressed_offset + (entry.bit_offset > 0) as u64 <= payload_size,
+                "Corrupted archive: invalid index entry {}",
+                i
+            );
+            let position = entry.compressed_offset * 8 + entry.bit_offset as u64;
+            anyhow::ensure!(
+                if i == 0 {
+                    position == 0
+                } else {
+                    position >= previous_position
+                },
+                "Corrupted archive: index entry {} is out of order",
+                i
+            );
+            previous_position = position;
+            index.push(entry);
+        }
+
+        Ok(Self {
+            reader,
+            archiver,
+            original_size,
+            block_size,
+            payload_offset,
+            index_offset,
+            index,
+        })
+    }
+
+    /// Размер исходных данных.
+    pub fn len(&self) -> u64 {
+        self.original_size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.original_size == 0
+    }
+
+    pub fn block_size(&self) -> u64 {
+        self.block_size
+    }
+
+    pub fn index(&self) -> &[BlockIndexEntry] {
+        &self.index
+    }
+
+    /// Номера блоков, которые нужно декодировать для диапазона `range`.
+    pub fn blocks_for(&self, range: &Range<u64>) -> Range<usize> {
+        if range.is_empty() {
+            return 0..0;
+        }
+        (range.start / self.block_size) as usize..range.end.div_ceil(self.block_size) as usize
+    }
+
+    /// Распаковывает диапазон байтов исходных данных, читая и декодируя только нужные блоки.
+    pub fn read_range(&mut self, range: Range<u64>) -> Result<Vec<u8>> {
+        anyhow::ensure!(
+            range.start <= range.end && range.end <= self.original_size,
+            "Range {}..{} is out of bounds for {} bytes",
+            range.start,
+            range.end,
+            self.original_size
+        );
+
+        let blocks = self.blocks_for(&range);
+        if blocks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Сжатые байты от начала первого блока до конца последнего
+        let start = self.index[blocks.start].compressed_offset;
+        let end = match self.index.get(blocks.end) {
+            Some(next) => next.compressed_offset + (next.bit_offset > 0) as u64,
+            None => self.index_offset - self.payload_offset,
+        };
+
+        let mut compressed = vec![0u8; (end - start) as usize];
+        self.reader
+            .seek(SeekFrom::Start(self.payload_offset + start))
+            .context("Failed to seek to block")?;
+        self.reader
+            .read_exact(&mut compressed)
+            .context("Failed to read blocks")?;
+
+        let mut reader = BitReader::new(&compressed);
+        reader.read_bits(self.index[blocks.start].bit_offset as u32)?;
+
+        let capacity = (blocks.len() as u64)
+            .saturating_mul(self.block_size)
+            .min(self.original_size);
+        let mut decoded = Vec::with_capacity(capacity as usize);
+        for i in blocks.clone() {
+            let entry = self.index[i];
+            let block_len = self.block_size.min(self.original_size - entry.uncompressed_offset);
+
+            anyhow::ensure!(
+                (reader.position() as u64) == (entry.compressed_offset - start) * 8 + entry.bit_offset as u64,
+                "Corrupted archive: block {} does not start at its index position",
+                i
+            );
+
+            let block = self
+                .archiver
+                .read_words(&mut reader, block_len as usize)
+                .with_context(|| format!("Failed to decode block {}", i))?;
+            decoded.extend_from_slice(&block);
+        }
+
+        // Обрезаем до запрошенного диапазона
+        let first = self.index[blocks.start].uncompressed_offset;
+        decoded.truncate((range.end - first) as usize);
+        decoded.drain(..(range.start - first) as usize);
+        Ok(decoded)
+    }
+
+    /// Распаковывает все данные.
+    pub fn read_all(&mut self) -> Result<Vec<u8>> {
+        self.read_range(0..self.original_size)
+    }
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn archive(bytes: &[u8], block_size: usize) -> SeekableReader<Cursor<Vec<u8>>> {
+        let mut archive = Vec::new();
+        SeekableArchiver::new()
+            .with_block_size(block_size)
+            .encode_to_writer(bytes, &mut archive)
+            .unwrap();
+
+        let mut cursor = Cursor::new(archive);
+        assert!(SeekableReader::is_seekable_archive(&mut cursor).unwrap());
+        SeekableReader::new(cursor).unwrap()
+    }
+
+    #[test]
+    fn test_read_range() {
+        let bytes = (0..10_000u32).map(|i| (i * i % 251) as u8 % 17).collect::<Vec<_>>();
+        let mut reader = archive(&bytes, 1000);
+
+        assert_eq!(reader.len(), 10_000);
+        assert_eq!(reader.index().len(), 10);
+        assert_eq!(reader.read_all().unwrap(), bytes);
+
+        for range in [
+            0..1,
+            999..1001,
+            2500..2600,
+            3000..4000,
+            9999..10_000,
+            5000..5000,
+            1234..8765,
+        ] {
+            assert_eq!(
+                reader.read_range(range.clone()).unwrap(),
+                bytes[range.start as usize..range.end as usize],
+                "{:?}",
+                range
+            );
+        }
+
+        assert_eq!(reader.blocks_for(&(2500..2600)), 2..3);
+        assert_eq!(reader.blocks_for(&(999..1001)), 0..2);
+        assert!(reader.read_range(9000..10_001).is_err());
+    }
+
+    #[test]
+    fn test_blocks_are_not_byte_aligned() {
+        let bytes = b"abracadabra".repeat(100);
+        let reader = archive(&bytes, 7);
+
+        assert!(reader.index().iter().any(|entry| entry.bit_offset != 0));
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let mut empty = archive(&[], 16);
+        assert!(empty.is_empty());
+        assert!(empty.read_all().unwrap().is_empty());
+
+        // Единственный символ кодируется одним битом
+        let mut single = archive(&[42; 100], 16);
+        assert_eq!(single.read_range(10..50).unwrap(), [42; 40]);
+    }
+
+    #[test]
+    fn test_corrupted_index() {
+        let bytes = (0..1000u32).map(|i| (i * 7 % 13) as u8).collect::<Vec<_>>();
+        let mut archive = Vec::new();
+        SeekableArchiver::new()
+            .with_block_size(100)
+            .encode_to_writer(&bytes, &mut archive)
+            .unwrap();
+
+        let index_offset = u64::from_le_bytes(archive[archive.len() - TRAILER_SIZE..][..8].try_into().unwrap());
+        let entry = |i: usize| index_offset as usize + 8 + i * INDEX_ENTRY_SIZE;
+        let corrupt = |f: &dyn Fn(&mut Vec<u8>)| {
+            let mut archive = archive.clone();
+            f(&mut archive);
+            SeekableReader::new(Cursor::new(archive))
+        };
+
+        assert!(corrupt(&|_| {}).is_ok());
+
+        // Первый блок начинается не с начала данных
+        assert!(corrupt(&|archive| archive[entry(0) + 8] = 1).is_err());
+        // Смещения блоков убывают
+        assert!(
+            corrupt(&|archive| {
+                let third = archive[entry(2) + 8..entry(2) + 17].to_vec();
+                archive[entry(5) + 8..entry(5) + 17].copy_from_slice(&third);
+            })
+            .is_err()
+        );
+        // Количество блоков не помещается в файл
+        assert!(
+            corrupt(&|archive| {
+                let offset = index_offset as usize;
+                archive[offset..offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+            })
+            .is_err()
+        );
+        // Огромный исходный размер или размер блока
+        let state_len = usize::from_le_bytes(archive[MAGIC.len()..MAGIC.len() + 8].try_into().unwrap());
+        let sizes = MAGIC.len() + 8 + state_len;
+        for field in [sizes, sizes + 8] {
+            assert!(corrupt(&|archive| archive[field..field + 8].fill(0xFF)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_not_seekable_archive() {
+        let mut archive = Vec::new();
+        crate::FileEncoder::encode_to_writer(
+            HuffmanArchiver::from_codes(HuffmanArchiver::build_length_limited_codes(
+                &[(1, 1), (2, 1)].into(),
+                MAX_CODE_LENGTH,
+            )),
+            &[1, 2],
+            &mut archive,
+        )
+        .unwrap();
+
+        let mut cursor = Cursor::new(archive);
+        assert!(!SeekableReader::is_seekable_archive(&mut cursor).unwrap());
+        assert!(SeekableReader::new(cursor).is_err());
+    }
+}
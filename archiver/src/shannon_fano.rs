@@ -1,6 +1,4 @@
-use std::collections::VecDeque;
-
-use super::{Codes, CodesBuilder, utils::sort_words_and_probabilities};
+use super::{CodeTree, Codes, CodesBuilder, Word, utils::sort_words_and_probabilities};
 
 #[derive(Debug, Default)]
 pub struct ShannonFanoEncoder {}
@@ -11,44 +9,53 @@ impl ShannonFanoEncoder {
     pub fn new() -> Self {
         ShannonFanoEncoder {}
     }
-}
 
-impl CodesBuilder for ShannonFanoEncoder {
-    fn build_optimal_codes(words: Vec<u8>, probabilities: Vec<f64>) -> Codes {
-        match probabilities.len() {
-            0 => return Codes::default(),
-            1 => return Codes::new(words, probabilities, vec!["0".into()]),
-            _ => {}
+    /// Строит дерево последовательных разбиений Шеннона-Фано для визуализации: каждый узел - группа
+    /// символов, левая подгруппа получает `0`, правая - `1`. Для пустого набора вероятностей возвращает `None`.
+    pub fn build_code_tree<W: Word>(words: Vec<W>, probabilities: Vec<f64>) -> Option<CodeTree<W>> {
+        if probabilities.is_empty() {
+            return None;
         }
 
         let (words, probabilities) = sort_words_and_probabilities(words, probabilities);
+        Some(build_split_tree(&words, &probabilities, String::new()))
+    }
+}
 
-        let mut codes = vec![String::new(); probabilities.len()];
-        let mut queue: VecDeque<(&[f64], &mut [String])> = VecDeque::new();
+impl CodesBuilder for ShannonFanoEncoder {
+    /// Коды - листья дерева разбиений [`ShannonFanoEncoder::build_code_tree`] слева направо, то есть
+    /// в порядке убывания вероятностей.
+    fn build_optimal_codes<W: Word>(words: Vec<W>, probabilities: Vec<f64>) -> Codes<W> {
+        if probabilities.is_empty() {
+            return Codes::default();
+        }
 
-        queue.push_back((probabilities.as_ref(), &mut codes));
+        let (words, probabilities) = sort_words_and_probabilities(words, probabilities);
+        let codes = build_split_tree(&words, &probabilities, String::new()).codes();
 
-        while let Some((probabilities, codes)) = queue.pop_front() {
-            if probabilities.len() > 1 {
-                // Делим по середине
-                let mid = find_index_equal_groups(&probabilities);
-                let (l_codes, r_codes) = codes.split_at_mut(mid);
-                let (l_probabilities, r_probabilities) = probabilities.split_at(mid);
+        Codes::new(words, probabilities, codes)
+    }
+}
 
-                // Добавляем коды к группам
-                for code in l_codes.iter_mut() {
-                    code.push('0');
-                }
-                for code in r_codes.iter_mut() {
-                    code.push('1');
-                }
+/// Рекурсивно делит группу символов на две части с близкими суммарными вероятностями.
+/// Единственный символ получает код `0`.
+fn build_split_tree<W: Word>(words: &[W], probabilities: &[f64], code: String) -> CodeTree<W> {
+    if probabilities.len() == 1 {
+        return CodeTree::Leaf {
+            probability: probabilities[0],
+            word: words[0],
+            code: if code.is_empty() { "0".into() } else { code },
+        };
+    }
 
-                queue.push_back((l_probabilities, l_codes));
-                queue.push_back((r_probabilities, r_codes));
-            }
-        }
+    let mid = find_index_equal_groups(probabilities);
+    let (l_words, r_words) = words.split_at(mid);
+    let (l_probabilities, r_probabilities) = probabilities.split_at(mid);
 
-        Codes::new(words, probabilities, codes)
+    CodeTree::Node {
+        probability: probabilities.iter().sum(),
+        left: Box::new(build_split_tree(l_words, l_probabilities, format!("{}0", code))),
+        right: Box::new(build_split_tree(r_words, r_probabilities, format!("{}1", code))),
     }
 }
 
@@ -108,10 +115,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_code_tree() {
+        let tree = ShannonFanoEncoder::build_code_tree(vec![1u8, 2, 3, 4], vec![0.125, 0.5, 0.125, 0.25]).unwrap();
+
+        let leaf = |probability, word, code: &str| {
+            Box::new(CodeTree::Leaf {
+                probability,
+                word,
+                code: code.into(),
+            })
+        };
+
+        assert_eq!(
+            tree,
+            CodeTree::Node {
+                probability: 1.0,
+                left: leaf(0.5, 2, "0"),
+                right: Box::new(CodeTree::Node {
+                    probability: 0.5,
+                    left: leaf(0.25, 4, "10"),
+                    right: Box::new(CodeTree::Node {
+                        probability: 0.25,
+                        left: leaf(0.125, 1, "110"),
+                        right: leaf(0.125, 3, "111"),
+                    }),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_wide_alphabet() {
+        // Больше 256 символов: номера не должны совпадать
+        let words = (0..300u16).collect::<Vec<_>>();
+        let codes = ShannonFanoEncoder::build_optimal_codes(words, vec![1.0 / 300.0; 300]);
+
+        let mut sorted = codes.words().to_vec();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..300).collect::<Vec<_>>());
+        assert!(codes.is_complete());
+    }
+
     fn build_optimal_codes_test_helper(expected: Vec<&str>, probabilities: Vec<f64>) {
         assert_eq!(
             expected,
-            ShannonFanoEncoder::build_optimal_codes(vec![0; probabilities.len()], probabilities).codes()
+            ShannonFanoEncoder::build_optimal_codes(vec![0u8; probabilities.len()], probabilities).codes()
         );
     }
 
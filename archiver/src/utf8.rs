@@ -0,0 +1,272 @@
+use anyhow::{Context, Result};
+use std::{cell::RefCell, collections::HashMap, fmt, fs::File, path::Path};
+
+use crate::{
+    Decoder, Encoder, FileEncoder, StateSaver,
+    huffman::{HuffmanArchiver, HuffmanDecoder, MAX_SYMBOL_CODE_LENGTH, load_symbol_table, save_symbol_table},
+};
+
+/// Начало диапазона escape-символов: байт `b` недопустимой последовательности UTF-8 кодируется
+/// символом `ESCAPE_BASE + b`, который не пересекается с кодовыми точками Unicode.
+pub const ESCAPE_BASE: u32 = 0x110000;
+
+/// Разбивает данные на кодовые точки UTF-8. Байты недопустимых последовательностей заменяются
+/// escape-символами [`ESCAPE_BASE`]` + byte`, поэтому преобразование обратимо для любых данных.
+pub fn to_symbols(bytes: &[u8]) -> Vec<u32> {
+    let mut symbols = Vec::with_capacity(bytes.len());
+    let mut rest = bytes;
+
+    while !rest.is_empty() {
+        match std::str::from_utf8(rest) {
+            Ok(text) => {
+                symbols.extend(text.chars().map(u32::from));
+                break;
+            }
+            Err(err) => {
+                let (valid, invalid) = rest.split_at(err.valid_up_to());
+                let text = std::str::from_utf8(valid).unwrap();
+                symbols.extend(text.chars().map(u32::from));
+
+                // Обрезанная последовательность в конце данных тоже экранируется
+                let invalid_len = err.error_len().unwrap_or(invalid.len());
+                symbols.extend(invalid[..invalid_len].iter().map(|&b| ESCAPE_BASE + b as u32));
+                rest = &invalid[invalid_len..];
+            }
+        }
+    }
+
+    symbols
+}
+
+/// Восстанавливает данные из кодовых точек и escape-символов.
+pub fn from_symbols(symbols: &[u32]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(symbols.len());
+    let mut buf = [0u8; 4];
+
+    for &symbol in symbols {
+        if symbol >= ESCAPE_BASE {
+            anyhow::ensure!(symbol - ESCAPE_BASE <= 0xFF, "Invalid escape symbol: {:#x}", symbol);
+            bytes.push((symbol - ESCAPE_BASE) as u8);
+        } else {
+            let c = char::from_u32(symbol).with_context(|| format!("Invalid code point: {:#x}", symbol))?;
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Архиватор Хаффмана, кодирующий кодовые точки UTF-8 вместо байтов.
+///
+/// Для текста на кириллице каждый символ занимает два байта, и побайтовый код не видит настоящей
+/// статистики символов. Таблица кодов хранит кодовые точки, байты недопустимых последовательностей
+/// кодируются escape-символами.
+pub struct Utf8Archiver {
+    word_code: HashMap<u32, String>,
+    decoder: RefCell<Option<HuffmanDecoder<u32>>>,
+}
+
+impl Clone for Utf8Archiver {
+    fn clone(&self) -> Self {
+        Self::from_word_code(self.word_code.clone())
+    }
+}
+
+impl fmt::Debug for Utf8Archiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Utf8Archiver")
+            .field("word_code", &self.word_code)
+            .finish()
+    }
+}
+
+impl Utf8Archiver {
+    /// Строит коды по частотам кодовых точек `bytes`.
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut counts = HashMap::new();
+        for symbol in to_symbols(bytes) {
+            *counts.entry(symbol).or_insert(0) += 1;
+        }
+        Self::from_counts(&counts)
+    }
+
+    /// Строит коды по количеству вхождений символов.
+    pub fn from_counts(counts: &HashMap<u32, usize>) -> Self {
+        Self::from_word_code(HuffmanArchiver::build_length_limited_codes(counts, MAX_SYMBOL_CODE_LENGTH).into())
+    }
+
+    fn from_word_code(word_code: HashMap<u32, String>) -> Self {
+        Self {
+            word_code,
+            decoder: RefCell::new(None),
+        }
+    }
+
+    /// Количество различных символов, включая escape-символы.
+    pub fn alphabet_size(&self) -> usize {
+        self.word_code.len()
+    }
+
+    pub fn word_code(&self) -> &HashMap<u32, String> {
+        &self.word_code
+    }
+
+    /// Архивирует файл в формате [`FileEncoder`].
+    pub fn archive<P: AsRef<Path>>(target: P, destination: P) -> Result<()> {
+        let bytes = std::fs::read(target).context("Failed to read file")?;
+        let mut file = File::create(destination).context("Failed to create file")?;
+        Self::new(&bytes).encode_to_writer(&bytes, &mut file)
+    }
+
+    fn decoder(&self) -> Result<std::cell::Ref<'_, HuffmanDecoder<u32>>> {
+        if self.decoder.borrow().is_none() {
+            let decoder = HuffmanDecoder::try_from(&self.word_code)?;
+            *self.decoder.borrow_mut() = Some(decoder);
+        }
+
+        Ok(std::cell::Ref::map(self.decoder.borrow(), |opt| opt.as_ref().unwrap()))
+    }
+}
+
+impl Encoder for Utf8Archiver {
+    fn convert_to_string(&self, bytes: &[u8]) -> Result<String> {
+        let mut bit_string = String::with_capacity(bytes.len() * 4);
+
+        for symbol in to_symbols(bytes) {
+            let code = self
+                .word_code
+                .get(&symbol)
+                .with_context(|| format!("Unknown symbol: {:#x}", symbol))?;
+            bit_string.push_str(code);
+        }
+        Ok(bit_string)
+    }
+}
+
+impl Decoder for Utf8Archiver {
+    fn decode_string(&self, bit_string: &str) -> Result<Vec<u8>> {
+        if self.word_code.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let symbols = self.decoder()?.decode_symbols(bit_string)?;
+        from_symbols(&symbols)
+    }
+}
+
+impl StateSaver for Utf8Archiver {
+    fn save_state(self) -> Result<Vec<u8>> {
+        Ok(save_symbol_table(self.word_code))
+    }
+
+    fn load_state(state: Vec<u8>) -> Result<Self> {
+        let word_code = load_symbol_table(&state, |symbol| {
+            symbol <= ESCAPE_BASE + 0xFF && (symbol >= ESCAPE_BASE || char::from_u32(symbol).is_some())
+        })?;
+        Ok(Self::from_word_code(word_code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileDecoder, FrequencyMap, HuffmanArchiver};
+
+    #[test]
+    fn test_symbols_round_trip() {
+        let text = "Привет, мир! Hello 🌍";
+        let symbols = to_symbols(text.as_bytes());
+        assert_eq!(symbols.len(), text.chars().count());
+        assert_eq!(from_symbols(&symbols).unwrap(), text.as_bytes());
+
+        // Недопустимые последовательности: одиночный байт продолжения, обрезанный символ в конце
+        let mut invalid = b"ab\x80c".to_vec();
+        invalid.extend_from_slice(&"ж".as_bytes()[..1]);
+        let symbols = to_symbols(&invalid);
+        assert_eq!(
+            symbols,
+            [
+                b'a' as u32,
+                b'b' as u32,
+                ESCAPE_BASE + 0x80,
+                b'c' as u32,
+                ESCAPE_BASE + 0xD0
+            ]
+        );
+        assert_eq!(from_symbols(&symbols).unwrap(), invalid);
+
+        assert!(from_symbols(&[0xD800]).is_err());
+        assert!(from_symbols(&[ESCAPE_BASE + 0x100]).is_err());
+    }
+
+    #[test]
+    fn test_encode_and_decode() {
+        let mut bytes = "Съешь же ещё этих мягких французских булок, да выпей чаю. "
+            .repeat(20)
+            .into_bytes();
+        bytes.extend_from_slice(&[0xFF, 0xFE, 0x00]);
+
+        let archiver = Utf8Archiver::new(&bytes);
+        let encoded = archiver.encode_bytes(&bytes).unwrap();
+
+        let restored = Utf8Archiver::load_state(archiver.save_state().unwrap()).unwrap();
+        let mut decoded = restored.decode_bytes(&encoded).unwrap();
+        decoded.truncate(bytes.len());
+        assert_eq!(decoded, bytes);
+    }
+
+    #[test]
+    fn test_better_than_bytes_for_cyrillic() {
+        let bytes = "Архиватор кодирует кодовые точки, а не байты. ".repeat(50).into_bytes();
+
+        let utf8_bits = Utf8Archiver::new(&bytes).convert_to_string(&bytes).unwrap().len();
+
+        let mut freq_map = FrequencyMap::new();
+        freq_map.consume(&bytes);
+        let byte_bits = HuffmanArchiver::new(freq_map.build())
+            .convert_to_string(&bytes)
+            .unwrap()
+            .len();
+
+        assert!(utf8_bits < byte_bits, "{} >= {}", utf8_bits, byte_bits);
+    }
+
+    #[test]
+    fn test_single_symbol_and_empty() {
+        let archiver = Utf8Archiver::new("ыыыы".as_bytes());
+        assert_eq!(archiver.word_code()[&('ы' as u32)], "0");
+
+        let empty = Utf8Archiver::load_state(Utf8Archiver::new(&[]).save_state().unwrap()).unwrap();
+        assert_eq!(empty.alphabet_size(), 0);
+        assert!(empty.decode_bytes(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_empty_file_round_trip() {
+        let target = std::env::temp_dir().join("utf8_empty_test.txt");
+        let archived = target.with_extension("huff");
+        let extracted = target.with_extension("extract");
+        std::fs::write(&target, []).unwrap();
+
+        Utf8Archiver::archive(&target, &archived).unwrap();
+        Utf8Archiver::decode_file(&archived, &extracted).unwrap();
+        assert!(std::fs::read(&extracted).unwrap().is_empty());
+
+        for path in [target, archived, extracted] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_load_invalid_state() {
+        // Код длиннее своей длины
+        let mut state = 'a'.to_string().into_bytes();
+        state.extend_from_slice(&[0, 0, 0, 1, 0b11, 0, 0, 0]);
+        assert!(Utf8Archiver::load_state(state).is_err());
+
+        // Суррогатная пара не является кодовой точкой
+        let mut state = 0xD800u32.to_le_bytes().to_vec();
+        state.extend_from_slice(&[1, 0, 0, 0, 0]);
+        assert!(Utf8Archiver::load_state(state).is_err());
+    }
+}
@@ -8,7 +8,7 @@ use std::{
 
 use super::FrequencyMap;
 
-pub fn sort_words_and_probabilities(words: Vec<u8>, probabilities: Vec<f64>) -> (Vec<u8>, Vec<f64>) {
+pub fn sort_words_and_probabilities<W>(words: Vec<W>, probabilities: Vec<f64>) -> (Vec<W>, Vec<f64>) {
     let mut word_probability = words.into_iter().zip(probabilities.into_iter()).collect::<Vec<_>>();
 
     word_probability.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap().reverse());
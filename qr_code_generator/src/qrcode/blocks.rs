@@ -21,7 +21,6 @@ pub struct BlocksInfo {
 
 impl Block {
     pub fn apply_reed_solomon<T: ReedSolomonEncoder>(&mut self, reed_solomon: &T) -> Result<()> {
-        // FIXME: Контрольная сумма должна находиться в конце блока
         self.data = reed_solomon.apply(&self.data)?;
         Ok(())
     }
@@ -105,6 +104,7 @@ impl BlocksInfo {
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use reed_solomon::{ReedSolomon, gf::FastGF256};
     use rstest::{fixture, rstest};
 
     use super::*;
@@ -124,4 +124,18 @@ mod tests {
             expected
         );
     }
+
+    #[test]
+    fn test_apply_reed_solomon_appends_correction_bytes() {
+        // Версия 1-M, ISO/IEC 18004: "HELLO WORLD"
+        let data = vec![32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17];
+        let encoder = <ReedSolomon<FastGF256> as ReedSolomonEncoder>::new(Version::new(1), CorrectionLevel::M).unwrap();
+
+        let mut block = Block::from(data.clone());
+        block.apply_reed_solomon(&encoder).unwrap();
+
+        let mut expected = data;
+        expected.extend([196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+        assert_eq!(block.as_slice(), expected.as_slice());
+    }
 }
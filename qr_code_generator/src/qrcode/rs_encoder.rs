@@ -1,5 +1,5 @@
 use anyhow::Result;
-use reed_solomon::{self, Coder, ReedSolomon, gf::FastGF256, new_reed_solomon};
+use reed_solomon::{self, Coder, Layout, ReedSolomon, gf::FastGF256, new_reed_solomon};
 
 use super::{Block, CorrectionLevel, Version, tables};
 
@@ -32,6 +32,7 @@ impl ReedSolomonEncoder for ReedSolomon<FastGF256> {
                 version.num(),
                 corr_level.index()
             ))? as usize;
-        Ok(new_reed_solomon(control_count))
+        // Байты коррекции записываются после данных блока
+        Ok(new_reed_solomon(control_count).with_layout(Layout::ControlLast))
     }
 }
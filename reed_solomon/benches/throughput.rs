@@ -0,0 +1,75 @@
+//! Пропускная способность кодирования и декодирования блоков RS(255, 223).
+//!
+//! Запуск: `cargo bench -p reed_solomon`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use reed_solomon::{
+    DecodeBuffers, KeySolver, ReedSolomon,
+    gf::{FastGF256, GF256Poly, SimpleGF256},
+};
+
+const BLOCK_LEN: usize = 255;
+const CONTROL_COUNT: usize = 32;
+const DATA_LEN: usize = BLOCK_LEN - CONTROL_COUNT;
+
+fn message() -> Vec<u8> {
+    (0..DATA_LEN).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+/// Искажает `count` символов, равномерно распределенных по кодовому слову.
+fn corrupt(encoded: &[u8], count: usize) -> Vec<u8> {
+    let mut corrupted = encoded.to_vec();
+    for i in 0..count {
+        corrupted[i * BLOCK_LEN / count.max(1)] ^= 0x5A;
+    }
+    corrupted
+}
+
+fn bench_field<T: GF256Poly>(c: &mut Criterion, name: &str, gf: impl Fn() -> T) {
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(BLOCK_LEN as u64));
+
+    let rs = ReedSolomon::new(CONTROL_COUNT, gf());
+    let data = message();
+    let mut encoded = Vec::with_capacity(BLOCK_LEN);
+
+    group.bench_function("encode", |b| {
+        b.iter(|| rs.encode_symbols_into(black_box(&data), &mut encoded).unwrap())
+    });
+
+    let encoded = rs.encode_symbols(&data).unwrap();
+    for errors in [0, 1, CONTROL_COUNT / 2] {
+        let corrupted = corrupt(&encoded, errors);
+
+        for solver in [KeySolver::BerlekampMassey, KeySolver::Euclidean] {
+            let rs = ReedSolomon::new(CONTROL_COUNT, gf()).with_solver(solver);
+            let id = BenchmarkId::new(format!("decode/{solver:?}"), format!("{errors} errors"));
+
+            group.bench_with_input(id, &corrupted, |b, corrupted| {
+                b.iter(|| rs.decode_symbols(black_box(corrupted)).unwrap())
+            });
+        }
+
+        let mut buffers = DecodeBuffers::new();
+        let mut decoded = Vec::with_capacity(BLOCK_LEN);
+        let id = BenchmarkId::new("decode_into/BerlekampMassey", format!("{errors} errors"));
+        group.bench_with_input(id, &corrupted, |b, corrupted| {
+            b.iter(|| {
+                rs.decode_symbols_into(black_box(corrupted), &[], &mut buffers, &mut decoded)
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn throughput(c: &mut Criterion) {
+    bench_field(c, "FastGF256", FastGF256::new);
+    bench_field(c, "SimpleGF256", SimpleGF256::new);
+}
+
+criterion_group!(benches, throughput);
+criterion_main!(benches);
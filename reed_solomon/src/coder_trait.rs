@@ -1,4 +1,5 @@
 use super::*;
+use crate::interleaver::Interleaver;
 
 pub trait Coder {
     fn encode(&self, data: &[u8]) -> Result<Vec<u8>>;
@@ -44,4 +45,35 @@ pub trait BlockCoder: Coder {
             Err(e) => Err(e),
         }
     }
+
+    /// Кодирует блоки по `block_size` байт данных и перемежает кодовые слова, чтобы пакет ошибок
+    /// распределился между ними.
+    fn encode_interleaved(&self, data: &[u8], block_size: usize, interleaver: &impl Interleaver) -> Result<Vec<u8>> {
+        let blocks = self.encode_blocks(data, block_size)?;
+        Ok(interleaver.interleave(&blocks))
+    }
+
+    /// Восстанавливает кодовые слова по `block_size` байт (данные + контрольные) из перемеженного потока
+    /// и декодирует их.
+    fn decode_interleaved(&self, data: &[u8], block_size: usize, interleaver: &impl Interleaver) -> Result<Vec<u8>> {
+        anyhow::ensure!(block_size > 0, "Block size must be positive");
+        let Some(total) = data.len().checked_sub(interleaver.overhead()) else {
+            anyhow::bail!(
+                "Interleaved data ({}) is shorter than interleaver overhead ({})",
+                data.len(),
+                interleaver.overhead()
+            );
+        };
+
+        let mut lengths = vec![block_size; total / block_size];
+        if total % block_size > 0 {
+            lengths.push(total % block_size);
+        }
+
+        let mut decoded = Vec::with_capacity(total);
+        for codeword in interleaver.deinterleave(data, &lengths)? {
+            decoded.extend(self.decode(&codeword)?);
+        }
+        Ok(decoded)
+    }
 }
@@ -1,10 +1,16 @@
-use crate::{Poly, RefPoly};
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::BitXor,
+};
+
+use anyhow::Result;
 
 mod fast_gf256;
 mod simple_gf256;
 
-pub use fast_gf256::FastGF256;
-pub use simple_gf256::SimpleGF256;
+pub use fast_gf256::{FastGF, FastGF256};
+pub use simple_gf256::{SimpleGF, SimpleGF256};
 
 /// Примитивный полином: x⁸ + x⁴ + x³ + x² + 1 = 0x11D или 285 в десятичной.
 pub const PRIMITIVE_POLY_FULL: u16 = 0x11D;
@@ -12,92 +18,206 @@ pub const PRIMITIVE_POLY_FULL: u16 = 0x11D;
 /// Возьмем младшие степени примитивного полинома: x⁴ + x³ + x² + 1 = 0x1D или 29 в десятичной.
 pub const PRIMITIVE_POLY: u8 = 0x1D;
 
-/// Определяет арифметические операции над элементами поля GF(256).
+/// Примитивный полином Data Matrix (ISO/IEC 16022): x⁸ + x⁵ + x³ + x² + 1 = 0x12D.
+pub const DATA_MATRIX_POLY: u32 = 0x12D;
+
+/// Примитивный полином CCSDS (CCSDS 131.0-B): x⁸ + x⁷ + x² + x + 1 = 0x187.
+pub const CCSDS_POLY: u32 = 0x187;
+
+/// Примитивные полиномы полей GF(2^m) по умолчанию, индекс - степень `m` от 3 до 16.
+pub const PRIMITIVE_POLYS: [u32; 17] = [
+    0, 0, 0, 0xB, 0x13, 0x25, 0x43, 0x89, 0x11D, 0x211, 0x409, 0x805, 0x1053, 0x201B, 0x4443, 0x8003, 0x1100B,
+];
+
+/// Тип элементов поля GF(2^m): `u8` для `m <= 8` и `u16` для `m <= 16`.
+pub trait Symbol: Copy + Eq + Ord + Hash + Default + Debug + Display + BitXor<Output = Self> {
+    /// Количество битов в типе - максимальная степень поля.
+    const BITS: u32;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Элемент по его двоичному представлению. Лишние старшие биты отбрасываются.
+    fn from_index(index: usize) -> Self;
+
+    /// Двоичное представление элемента.
+    fn index(self) -> usize;
+}
+
+impl Symbol for u8 {
+    const BITS: u32 = u8::BITS;
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn from_index(index: usize) -> Self {
+        index as u8
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Symbol for u16 {
+    const BITS: u32 = u16::BITS;
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn from_index(index: usize) -> Self {
+        index as u16
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Определяет арифметические операции над элементами поля GF(2^m), `3 <= m <= 16`.
 ///
-/// Примитивный элемент поля GF(256) — это элемент, который порождает мультипликативную группу поля
-/// GF(256), то есть при возведении его в степени от 1 до 255 (исключая 0) можно получить все ненулевые
+/// Примитивный элемент поля GF(2^m) — это элемент, который порождает мультипликативную группу поля,
+/// то есть при возведении его в степени от 1 до 2^m - 1 (исключая 0) можно получить все ненулевые
 /// элементы этого поля.
-pub trait GF256 {
-    fn _div(&self, a: u8, b: u8) -> u8;
-    fn _mul(&self, a: u8, b: u8) -> u8;
-    fn _pow(&self, a: u8, n: u8) -> u8;
-    fn _inverse(&self, a: u8) -> u8;
+pub trait GF2m {
+    type Symbol: Symbol;
+
+    fn _div(&self, a: Self::Symbol, b: Self::Symbol) -> Self::Symbol;
+    fn _mul(&self, a: Self::Symbol, b: Self::Symbol) -> Self::Symbol;
+    fn _pow(&self, a: Self::Symbol, n: usize) -> Self::Symbol;
+    fn _inverse(&self, a: Self::Symbol) -> Self::Symbol;
+
+    /// Степень поля `m`: элементы - многочлены степени меньше `m`.
+    fn degree(&self) -> u32;
+
+    /// Количество элементов поля `2^m`.
+    fn size(&self) -> usize {
+        1 << self.degree()
+    }
 
-    /// Примерный элемент поля.
-    fn alpha() -> u8 {
-        2
+    /// Порядок мультипликативной группы `2^m - 1` - максимальная длина кодового слова Рида-Соломона.
+    fn order(&self) -> usize {
+        self.size() - 1
+    }
+
+    /// Примитивный элемент поля, степени которого используются для корней порождающего многочлена
+    /// и локаторов ошибок.
+    fn alpha(&self) -> Self::Symbol {
+        Self::Symbol::from_index(2)
     }
 
     /// Возвести в степень примитивный элемент.
-    fn alpha_pow(&self, n: u8) -> u8 {
-        self.pow(Self::alpha(), n)
+    fn alpha_pow(&self, n: usize) -> Self::Symbol {
+        self.pow(self.alpha(), n)
     }
 
     /// Увеличить число на 1.
-    fn inc(&self, a: u8) -> u8 {
-        self.add(a, 1)
+    fn inc(&self, a: Self::Symbol) -> Self::Symbol {
+        self.add(a, Self::Symbol::ONE)
     }
 
     /// Уменьшить число на 1.
-    fn dec(&self, a: u8) -> u8 {
-        self.sub(a, 1)
+    fn dec(&self, a: Self::Symbol) -> Self::Symbol {
+        self.sub(a, Self::Symbol::ONE)
     }
 
     /// Возвести число a в степень n.
-    fn pow(&self, a: u8, n: u8) -> u8 {
+    fn pow(&self, a: Self::Symbol, n: usize) -> Self::Symbol {
         if n == 0 {
-            return 1;
+            return Self::Symbol::ONE;
         }
-        if a == 0 {
-            return 0;
+        if a == Self::Symbol::ZERO {
+            return Self::Symbol::ZERO;
         }
-        Self::_pow(&self, a, n)
+        Self::_pow(self, a, n)
     }
 
-    /// Найти a^(-1) в поле GF(256).
-    fn inverse(&self, a: u8) -> u8 {
-        if a == 0 {
+    /// Найти a^(-1) в поле GF(2^m).
+    fn inverse(&self, a: Self::Symbol) -> Self::Symbol {
+        if a == Self::Symbol::ZERO {
             panic!("Zero has no inverse");
         }
-        Self::_inverse(&self, a)
+        Self::_inverse(self, a)
     }
 
-    fn mul(&self, a: u8, b: u8) -> u8 {
-        if a == 0 || b == 0 {
-            return 0;
+    fn mul(&self, a: Self::Symbol, b: Self::Symbol) -> Self::Symbol {
+        if a == Self::Symbol::ZERO || b == Self::Symbol::ZERO {
+            return Self::Symbol::ZERO;
         }
-        Self::_mul(&self, a, b)
+        Self::_mul(self, a, b)
     }
 
-    fn div(&self, a: u8, b: u8) -> u8 {
-        if b == 0 {
+    fn div(&self, a: Self::Symbol, b: Self::Symbol) -> Self::Symbol {
+        if b == Self::Symbol::ZERO {
             panic!("Division by zero");
         }
-        if a == 0 {
-            return 0;
+        if a == Self::Symbol::ZERO {
+            return Self::Symbol::ZERO;
         }
-        Self::_div(&self, a, b)
+        Self::_div(self, a, b)
     }
 
-    /// Операция сложения в поле GF(256) определена как a XOR b.
-    fn add(&self, a: u8, b: u8) -> u8 {
+    /// Операция сложения в поле GF(2^m) определена как a XOR b.
+    fn add(&self, a: Self::Symbol, b: Self::Symbol) -> Self::Symbol {
         a ^ b
     }
 
-    /// Операция вычитания в поле GF(256) определена как a XOR b.
-    fn sub(&self, a: u8, b: u8) -> u8 {
+    /// Операция вычитания в поле GF(2^m) определена как a XOR b.
+    fn sub(&self, a: Self::Symbol, b: Self::Symbol) -> Self::Symbol {
         a ^ b
     }
 }
 
-impl<T> GF256Poly for T where T: GF256 {}
+/// Поле GF(256) с элементами-байтами, для которого определены [`crate::Coder`] и многочлены [`Poly`].
+pub trait GF256: GF2m<Symbol = u8> {}
+
+impl<T> GF256 for T where T: GF2m<Symbol = u8> {}
+
+/// Операции над многочленами в поле GF(256).
+pub trait GF256Poly: GF2mPoly<Symbol = u8> {}
+
+impl<T> GF256Poly for T where T: GF2mPoly<Symbol = u8> {}
+
+/// Проверяет примитивный полином поля с элементами типа `S` и возвращает его степень `m`.
+fn poly_degree<S: Symbol>(primitive_poly: u32) -> Result<u32> {
+    let degree = u32::BITS - 1 - primitive_poly.leading_zeros().min(u32::BITS - 1);
+    anyhow::ensure!(
+        (3..=16).contains(&degree),
+        "Primitive polynomial must have degree from 3 to 16, actual: {primitive_poly:#x}"
+    );
+    anyhow::ensure!(
+        degree <= S::BITS,
+        "Field of degree {degree} does not fit into {}-bit symbols",
+        S::BITS
+    );
+    Ok(degree)
+}
+
+/// Умножение многочленов над GF(2) по модулю `poly` степени `degree` без таблиц.
+fn mul_mod(mut a: u32, mut b: u32, poly: u32, degree: u32) -> u32 {
+    let mut result = 0;
+
+    while b > 0 {
+        if b & 1 == 1 {
+            result ^= a;
+        }
+        b >>= 1;
+
+        // Умножение на x и взятие по модулю, если степень стала равна m
+        a <<= 1;
+        if a >> degree != 0 {
+            a ^= poly;
+        }
+    }
+    result
+}
+
+impl<T> GF2mPoly for T where T: GF2m {}
 
-/// Определяет операции над полиномами в поле GF(256).
-pub trait GF256Poly: GF256 {
-    /// Складывает многочлены с учетом правил сложения GF256
-    fn add_poly(&self, a: RefPoly, b: RefPoly) -> Poly {
+/// Определяет операции над полиномами в поле GF(2^m). Коэффициент под индексом `i` - коэффициент при `x^i`.
+pub trait GF2mPoly: GF2m {
+    /// Складывает многочлены с учетом правил сложения поля
+    fn add_poly(&self, a: &[Self::Symbol], b: &[Self::Symbol]) -> Vec<Self::Symbol> {
         let len = a.len().max(b.len());
-        let mut result = vec![0u8; len];
+        let mut result = vec![Self::Symbol::ZERO; len];
 
         for i in 0..len {
             let a_val = a.get(i).copied().unwrap_or_default();
@@ -108,9 +228,9 @@ pub trait GF256Poly: GF256 {
         result
     }
 
-    /// Умножает многочлены с учетом правил GF256
-    fn mul_poly(&self, a: RefPoly, b: RefPoly) -> Poly {
-        let mut result = vec![0u8; a.len() + b.len() - 1];
+    /// Умножает многочлены с учетом правил поля
+    fn mul_poly(&self, a: &[Self::Symbol], b: &[Self::Symbol]) -> Vec<Self::Symbol> {
+        let mut result = vec![Self::Symbol::ZERO; a.len() + b.len() - 1];
 
         for (i, &coef_a) in a.iter().enumerate() {
             for (j, &coef_b) in b.iter().enumerate() {
@@ -122,50 +242,46 @@ pub trait GF256Poly: GF256 {
         result
     }
 
-    /// Функция для вычисления значения полинома в точке
-    fn eval_poly(&self, poly: RefPoly, x: u8) -> u8 {
-        let mut result = 0u8;
-
-        for (i, &coef) in poly.iter().enumerate() {
-            let term = self.mul(coef, self.pow(x, i as u8));
-            result = self.add(result, term);
-        }
-
-        result
+    /// Вычисляет значение полинома в точке по схеме Горнера:
+    /// `p(x) = (...(p[n]·x + p[n-1])·x + ...)·x + p[0]` - `n` умножений без возведения в степень.
+    fn eval_poly(&self, poly: &[Self::Symbol], x: Self::Symbol) -> Self::Symbol {
+        poly.iter()
+            .rev()
+            .fold(Self::Symbol::ZERO, |acc, &coef| self.add(self.mul(acc, x), coef))
     }
 
     /// Умножает коэффициенты многочлена на скаляр
-    fn scale_poly(&self, poly: RefPoly, scalar: u8) -> Poly {
+    fn scale_poly(&self, poly: &[Self::Symbol], scalar: Self::Symbol) -> Vec<Self::Symbol> {
         poly.iter().map(|&coef| self.mul(coef, scalar)).collect()
     }
 
     /// Сдвигает многочлен на n
-    fn shift_poly(&self, poly: RefPoly, shift: usize) -> Poly {
-        let mut result = vec![0u8; shift];
+    fn shift_poly(&self, poly: &[Self::Symbol], shift: usize) -> Vec<Self::Symbol> {
+        let mut result = vec![Self::Symbol::ZERO; shift];
         result.extend_from_slice(poly);
         result
     }
 
     /// Вычисляет остаток от деления многочлена a на многочлен b
-    fn mod_poly(&self, dividend: RefPoly, divisor: RefPoly) -> Poly {
+    fn mod_poly(&self, dividend: &[Self::Symbol], divisor: &[Self::Symbol]) -> Vec<Self::Symbol> {
         let (_quotient, remainder) = self._div_poly(dividend, divisor);
         remainder
     }
 
     /// Вычисляет частное от деления многочлена a на многочлен b
-    fn div_poly(&self, dividend: RefPoly, divisor: RefPoly) -> Poly {
+    fn div_poly(&self, dividend: &[Self::Symbol], divisor: &[Self::Symbol]) -> Vec<Self::Symbol> {
         let (quotient, _remainder) = self._div_poly(dividend, divisor);
         quotient
     }
 
     /// Вычисляет частное и остаток от деления
-    fn _div_poly(&self, dividend: RefPoly, divisor: RefPoly) -> (Poly, Poly) {
+    fn _div_poly(&self, dividend: &[Self::Symbol], divisor: &[Self::Symbol]) -> (Vec<Self::Symbol>, Vec<Self::Symbol>) {
         if divisor.is_empty() {
             panic!("Division by zero: {:?}", divisor);
         }
 
         let leader = divisor[divisor.len() - 1];
-        if leader == 0 {
+        if leader == Self::Symbol::ZERO {
             panic!("Divisor has zero leading coefficient");
         }
 
@@ -173,22 +289,22 @@ pub trait GF256Poly: GF256 {
         if dividend.len() < divisor.len()
             || (dividend.len() == divisor.len() && dividend[dividend.len() - 1] < divisor[divisor.len() - 1])
         {
-            return (vec![0], dividend.to_vec());
+            return (vec![Self::Symbol::ZERO], dividend.to_vec());
         }
 
-        let mut quotient = vec![0u8; dividend.len() - divisor.len() + 1];
+        let mut quotient = vec![Self::Symbol::ZERO; dividend.len() - divisor.len() + 1];
         let mut remainder = dividend.to_vec();
         let quotient_len = quotient.len();
 
         for i in 0..quotient.len() {
-            if remainder[remainder.len() - i - 1] != 0 {
+            if remainder[remainder.len() - i - 1] != Self::Symbol::ZERO {
                 // Вычисляем коэффициент частного
                 let coef = self.div(remainder[remainder.len() - i - 1], leader);
                 quotient[quotient_len - i - 1] = coef;
 
                 // Вычитаем (коэффициент * делитель) из остатка
                 for j in 0..divisor.len() {
-                    if divisor[divisor.len() - j - 1] != 0 {
+                    if divisor[divisor.len() - j - 1] != Self::Symbol::ZERO {
                         let curr_idx = remainder.len() - i - j - 1;
 
                         let product = self.mul(coef, divisor[divisor.len() - j - 1]);
@@ -202,8 +318,8 @@ pub trait GF256Poly: GF256 {
         let mut actual_remainder = remainder[..divisor.len() - 1].to_vec();
 
         // Если остаток пустой или все коэффициенты нулевые, возвращаем [0]
-        if actual_remainder.is_empty() || actual_remainder.iter().all(|&x| x == 0) {
-            actual_remainder = vec![0];
+        if actual_remainder.is_empty() || actual_remainder.iter().all(|&x| x == Self::Symbol::ZERO) {
+            actual_remainder = vec![Self::Symbol::ZERO];
         }
 
         (quotient, actual_remainder)
@@ -1,30 +1,94 @@
-use super::{GF256, PRIMITIVE_POLY, PRIMITIVE_POLY_FULL};
-
-pub struct FastGF256 {
-    exp_table: [u8; 256],
-    log_table: [u8; 256],
+use anyhow::Result;
+
+use super::{GF2m, PRIMITIVE_POLY_FULL, PRIMITIVE_POLYS, Symbol, mul_mod, poly_degree};
+
+/// Поле GF(2^m) на таблицах экспонент и логарифмов. Таблица экспонент удвоена до `2·(2^m - 1)`
+/// элементов, чтобы сумма логарифмов при умножении и делении не бралась по модулю.
+pub struct FastGF<S: Symbol = u8> {
+    exp_table: Vec<S>,
+    log_table: Vec<u16>,
+    primitive_poly: u32,
+    degree: u32,
+    generator: S,
 }
 
-impl FastGF256 {
+/// Поле GF(256) с байтовыми элементами.
+pub type FastGF256 = FastGF<u8>;
+
+impl FastGF<u8> {
+    /// Поле с примитивным полиномом 0x11D и примитивным элементом `2`, как в QR-кодах.
     pub fn new() -> Self {
-        let mut exp_table = [0u8; 256];
-        let mut log_table = [0u8; 256];
+        Self::build(PRIMITIVE_POLY_FULL as u32, 2).unwrap()
+    }
+}
 
-        let mut x = 1;
+impl<S: Symbol> FastGF<S> {
+    /// Поле GF(2^m) степени `degree` от 3 до 16 с примитивным полиномом из [`PRIMITIVE_POLYS`]
+    /// и примитивным элементом `2`.
+    pub fn with_degree(degree: u32) -> Result<Self> {
+        anyhow::ensure!(
+            (3..=16).contains(&degree),
+            "Field degree must be from 3 to 16, actual: {degree}"
+        );
+        Self::build(PRIMITIVE_POLYS[degree as usize], S::from_index(2))
+    }
 
-        for i in 0..256 {
-            exp_table[i] = x as u8;
-            log_table[x as usize] = i as u8;
+    /// Строит поле по примитивному полиному `primitive_poly` степени `m` от 3 до 16 (например, 0x11D, 0x12D,
+    /// 0x187 для GF(256) или 0x1100B для GF(65536)) и примитивному элементу `generator`, степени которого
+    /// образуют таблицу экспонент.
+    ///
+    /// Выбор `generator` равносилен параметру `prim` других реализаций: корни порождающего многочлена
+    /// CCSDS `a^(11j)` - это степени элемента `a^11`.
+    ///
+    /// Возвращает ошибку, если степень полинома вне диапазона или не помещается в тип `S`, или степени
+    /// `generator` не пробегают все `2^m - 1` ненулевых элементов (полином приводим или элемент не примитивный).
+    pub fn build(primitive_poly: u32, generator: S) -> Result<Self> {
+        let degree = poly_degree::<S>(primitive_poly)?;
+        let order = (1usize << degree) - 1;
+
+        anyhow::ensure!(
+            generator.index() <= order,
+            "Element {generator} does not belong to the field with polynomial {primitive_poly:#x}"
+        );
+
+        let mut exp_table = vec![S::ZERO; 2 * order];
+        let mut log_table = vec![0u16; order + 1];
+        let mut seen = vec![false; order + 1];
+
+        let mut x = 1u32;
+
+        for i in 0..order {
+            if x == 0 || seen[x as usize] {
+                anyhow::bail!(
+                    "Element {generator} does not generate the field with polynomial {primitive_poly:#x}: \
+                    its order is {i}"
+                );
+            }
+            seen[x as usize] = true;
 
-            x <<= 1;
+            exp_table[i] = S::from_index(x as usize);
+            exp_table[i + order] = exp_table[i];
+            log_table[x as usize] = i as u16;
 
-            // 0x100 или 0b1_0000_0000 или 256
-            if x >= 256 {
-                x ^= PRIMITIVE_POLY_FULL;
-            }
+            x = mul_mod(x, generator.index() as u32, primitive_poly, degree);
         }
+        // Мультипликативная группа циклическая: a^(i + 2^m - 1) = a^i
+
+        Ok(FastGF {
+            exp_table,
+            log_table,
+            primitive_poly,
+            degree,
+            generator,
+        })
+    }
+
+    pub fn primitive_poly(&self) -> u32 {
+        self.primitive_poly
+    }
 
-        FastGF256 { exp_table, log_table }
+    fn log(&self, a: S) -> usize {
+        self.log_table[a.index()] as usize
     }
 
     /// проверка таблицы логарифмов/экспонент
@@ -38,7 +102,7 @@ impl FastGF256 {
             );
         }
         println!("...");
-        for i in 250..256 {
+        for i in self.order() - 5..=self.order() {
             println!(
                 "exp_table[{}] = {}, log_table[{}] = {}",
                 i, self.exp_table[i], i, self.log_table[i]
@@ -46,24 +110,11 @@ impl FastGF256 {
         }
 
         println!();
-
-        // Проверим конкретные значения
-        assert_eq!(self.log_table[15], 75, "15 в log_table");
-        assert_eq!(self.log_table[6], 26, "6 в log_table");
-
-        println!();
-
-        let log_15 = self.log_table[15] as usize;
-        let log_6 = self.log_table[6] as usize;
-        println!("log(15) = {}, log(6) = {}", log_15, log_6);
-        println!("exp_table[{}] = {}", log_15 + log_6, self.exp_table[log_15 + log_6]);
-
-        println!()
     }
 
     #[cfg(test)]
-    pub fn pow_primitive_poly(&self, n: u8) -> u8 {
-        self.exp_table[n as usize]
+    pub fn pow_primitive_poly(&self, n: usize) -> S {
+        self.exp_table[n]
     }
 }
 
@@ -73,52 +124,56 @@ impl Default for FastGF256 {
     }
 }
 
-impl GF256 for FastGF256 {
-    fn alpha() -> u8 {
-        2
+impl<S: Symbol> GF2m for FastGF<S> {
+    type Symbol = S;
+
+    fn degree(&self) -> u32 {
+        self.degree
     }
 
-    fn _div(&self, a: u8, b: u8) -> u8 {
-        let log_a = self.log_table[a as usize] as i16;
-        let log_b = self.log_table[b as usize] as i16;
-        let res = (log_a - log_b + 255) % 255;
-        self.exp_table[res as usize]
+    fn alpha(&self) -> S {
+        self.generator
+    }
+
+    fn _div(&self, a: S, b: S) -> S {
+        self.exp_table[self.log(a) + self.order() - self.log(b)]
     }
 
     /// Так как любой элемент представим в виде степени примитивного многочлена, если
     /// `a=x^n`, `b=x^m`, то `a*b=x^(n+m)` - произведение элементов можно представить в виде степени примитивного.
     /// - Подсчитаем таблицу степеней x т.е. `exp[i] = x^i`
     /// - Подсчитаем таблицу логарифмов x т.е. `x^log[a] = a`
-    /// - Получаем `a * b = exp[log[a] + log[b] % 255]`
-    fn _mul(&self, a: u8, b: u8) -> u8 {
-        let log_a = self.log_table[a as usize] as usize;
-        let log_b = self.log_table[b as usize] as usize;
-        let res = (log_a + log_b) % 255;
-        self.exp_table[res]
+    /// - Получаем `a * b = exp[log[a] + log[b] % (2^m - 1)]`, где взятие по модулю заменяет удвоенная таблица
+    fn _mul(&self, a: S, b: S) -> S {
+        self.exp_table[self.log(a) + self.log(b)]
     }
 
-    fn _pow(&self, a: u8, n: u8) -> u8 {
-        let log_a = self.log_table[a as usize] as usize;
-        let result = (log_a * n as usize) % 255;
+    fn _pow(&self, a: S, n: usize) -> S {
+        let order = self.order();
+        let result = (self.log(a) * (n % order)) % order;
         self.exp_table[result]
     }
 
-    fn _inverse(&self, a: u8) -> u8 {
-        let log_a = self.log_table[a as usize] as usize;
-        self.exp_table[255 - log_a]
+    fn _inverse(&self, a: S) -> S {
+        self.exp_table[self.order() - self.log(a)]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::tests as gf_tests;
-    use super::FastGF256;
+    use super::{FastGF, FastGF256};
+    use crate::gf::{GF2m, GF2mPoly, PRIMITIVE_POLYS};
 
     #[test]
     fn test_gf256() {
         let gf = FastGF256::new();
         gf.debug_tables();
 
+        // Проверим конкретные значения
+        assert_eq!(gf.log_table[15], 75, "15 в log_table");
+        assert_eq!(gf.log_table[6], 26, "6 в log_table");
+
         gf_tests::arithmetic_operations::test_gf256(gf);
     }
 
@@ -137,4 +192,56 @@ mod tests {
     fn test_poly_operations() {
         gf_tests::poly_operations::test_poly_operations(FastGF256::new());
     }
+
+    #[test]
+    fn test_custom_fields() {
+        for (poly, generator) in [(0x11D, 2), (0x12D, 2), (0x187, 2), (0x11B, 3), (0x187, 0x87)] {
+            let gf = FastGF256::build(poly, generator).unwrap();
+            assert_eq!(gf.alpha_pow(1), generator);
+            gf_tests::arithmetic_operations::test_gf256(gf);
+        }
+
+        // В поле AES (0x11B) элемент 2 не примитивный, его порядок 51
+        assert!(FastGF256::build(0x11B, 2).is_err());
+        // Приводимый полином x⁸ + 1
+        assert!(FastGF256::build(0x101, 2).is_err());
+        assert!(FastGF256::build(0x1D, 2).is_err());
+        assert!(FastGF256::build(0x11D, 0).is_err());
+    }
+
+    #[test]
+    fn test_gf2m_fields() {
+        for degree in 3..=8 {
+            let gf = FastGF::<u8>::with_degree(degree).unwrap();
+            assert_eq!(gf.order(), (1 << degree) - 1);
+            gf_tests::arithmetic_operations::test_gf2m(&gf);
+        }
+        for degree in 3..=16 {
+            let gf = FastGF::<u16>::with_degree(degree).unwrap();
+            assert_eq!(gf.primitive_poly(), PRIMITIVE_POLYS[degree as usize]);
+            gf_tests::arithmetic_operations::test_gf2m(&gf);
+        }
+
+        // Поле GF(2^9) не помещается в байты
+        assert!(FastGF::<u8>::with_degree(9).is_err());
+        assert!(FastGF::<u16>::with_degree(17).is_err());
+        // x⁴ + 1 = (x + 1)⁴ приводим
+        assert!(FastGF::<u8>::build(0x11, 2).is_err());
+        // Элемент не из поля GF(16)
+        assert!(FastGF::<u8>::build(0x13, 16).is_err());
+    }
+
+    #[test]
+    fn test_gf65536_poly() {
+        let gf = FastGF::<u16>::with_degree(16).unwrap();
+        assert_eq!(gf.alpha_pow(65535), 1);
+        assert_eq!(gf.mul(0x8000, 2), 0x100B);
+
+        // (x - a)(x - b) обращается в ноль в точках a и b
+        let (a, b) = (gf.alpha_pow(1000), gf.alpha_pow(60000));
+        let poly = gf.mul_poly(&[a, 1], &[b, 1]);
+        assert_eq!(gf.eval_poly(&poly, a), 0);
+        assert_eq!(gf.eval_poly(&poly, b), 0);
+        assert_ne!(gf.eval_poly(&poly, gf.alpha_pow(7)), 0);
+    }
 }
@@ -1,78 +1,119 @@
-use super::{GF256, PRIMITIVE_POLY};
-
-/// Упрощенная реализация поля GF(256), не использующая таблицы экспонент и логарифмов.
-pub struct SimpleGF256 {}
-
-impl SimpleGF256 {
-    pub fn mul(mut a: u8, mut b: u8) -> u8 {
-        let mut result = 0;
-
-        while a > 0 {
-            result ^= b * (a & 1);
-            a >>= 1;
-            // b << 1 соответствует домножению на многочлена на x
-            // (b >> 7) соответветствует проверки, что b
-            // многочлен степени 7.
-            // ^ (irreducible_poly * (b >> 7)) соответствует
-            // взятию по модулю в этом случае. В противном случаем
-            // для взятия по модулю ничего не нужно делать
-            b = (b << 1) ^ (PRIMITIVE_POLY * (b >> 7));
-        }
-        result
+use std::marker::PhantomData;
+
+use anyhow::Result;
+
+use super::{GF2m, PRIMITIVE_POLY_FULL, PRIMITIVE_POLYS, Symbol, mul_mod, poly_degree};
+
+/// Упрощенная реализация поля GF(2^m), не использующая таблицы экспонент и логарифмов.
+pub struct SimpleGF<S: Symbol = u8> {
+    primitive_poly: u32,
+    degree: u32,
+    symbol: PhantomData<S>,
+}
+
+/// Поле GF(256) с байтовыми элементами.
+pub type SimpleGF256 = SimpleGF<u8>;
+
+impl SimpleGF<u8> {
+    /// Поле с примитивным полиномом 0x11D.
+    pub fn new() -> Self {
+        Self::build(PRIMITIVE_POLY_FULL as u32).unwrap()
     }
+}
 
-    pub fn pow(mut a: u8, mut n: u8) -> u8 {
-        let mut result = 1;
+impl<S: Symbol> SimpleGF<S> {
+    /// Поле GF(2^m) степени `degree` от 3 до 16 с примитивным полиномом из [`PRIMITIVE_POLYS`].
+    pub fn with_degree(degree: u32) -> Result<Self> {
+        anyhow::ensure!(
+            (3..=16).contains(&degree),
+            "Field degree must be from 3 to 16, actual: {degree}"
+        );
+        Self::build(PRIMITIVE_POLYS[degree as usize])
+    }
 
-        while n > 0 {
-            if n & 1 > 0 {
-                result = Self::mul(result, a);
-            }
-            a = Self::mul(a, a);
-            n >>= 1;
-        }
-        result
+    /// Поле по примитивному полиному `primitive_poly`. Неприводимость полинома не проверяется,
+    /// для проверки можно построить [`super::FastGF`].
+    pub fn build(primitive_poly: u32) -> Result<Self> {
+        let degree = poly_degree::<S>(primitive_poly)?;
+        Ok(SimpleGF {
+            primitive_poly,
+            degree,
+            symbol: PhantomData,
+        })
     }
 }
 
-impl GF256 for SimpleGF256 {
-    fn _div(&self, a: u8, b: u8) -> u8 {
+impl Default for SimpleGF256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Symbol> GF2m for SimpleGF<S> {
+    type Symbol = S;
+
+    fn degree(&self) -> u32 {
+        self.degree
+    }
+
+    fn _div(&self, a: S, b: S) -> S {
         let b_inverse = self.inverse(b);
         self.mul(a, b_inverse)
     }
 
-    fn _mul(&self, a: u8, b: u8) -> u8 {
-        Self::mul(a, b)
+    /// Умножение "в столбик": домножение многочлена на x - сдвиг влево, а если степень
+    /// стала равна m, берем по модулю примитивного полинома.
+    fn _mul(&self, a: S, b: S) -> S {
+        S::from_index(mul_mod(a.index() as u32, b.index() as u32, self.primitive_poly, self.degree) as usize)
     }
 
-    fn _pow(&self, a: u8, n: u8) -> u8 {
-        Self::pow(a, n)
+    /// Быстрое возведение в степень.
+    fn _pow(&self, mut a: S, mut n: usize) -> S {
+        let mut result = S::ONE;
+
+        while n > 0 {
+            if n & 1 > 0 {
+                result = self._mul(result, a);
+            }
+            a = self._mul(a, a);
+            n >>= 1;
+        }
+        result
     }
 
-    /// Найти a^(-1) в поле GF(256) можно как a^(254).
-    fn _inverse(&self, a: u8) -> u8 {
-        self.pow(a, 254)
+    /// Найти a^(-1) в поле GF(2^m) можно как a^(2^m - 2).
+    fn _inverse(&self, a: S) -> S {
+        self.pow(a, self.order() - 1)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::tests as gf_tests;
-    use super::SimpleGF256;
+    use super::{SimpleGF, SimpleGF256};
 
     #[test]
     fn test_gf256() {
-        gf_tests::arithmetic_operations::test_gf256(SimpleGF256 {});
+        gf_tests::arithmetic_operations::test_gf256(SimpleGF256::new());
     }
 
     #[test]
     #[ignore]
     fn test_gf256_performance() {
-        gf_tests::arithmetic_operations::test_gf256_performance(SimpleGF256 {}, std::time::Duration::from_secs(70));
+        gf_tests::arithmetic_operations::test_gf256_performance(SimpleGF256::new(), std::time::Duration::from_secs(70));
     }
 
     #[test]
     fn test_gf256_exceptions() {
-        gf_tests::arithmetic_operations::test_gf256_exceptions(SimpleGF256 {});
+        gf_tests::arithmetic_operations::test_gf256_exceptions(SimpleGF256::new());
+    }
+
+    #[test]
+    fn test_gf2m_fields() {
+        for degree in [3, 4, 7, 10, 13, 16] {
+            gf_tests::arithmetic_operations::test_gf2m(&SimpleGF::<u16>::with_degree(degree).unwrap());
+        }
+        assert!(SimpleGF::<u16>::build(0x2000B).is_err());
+        assert!(SimpleGF::<u8>::build(0x211).is_err());
     }
 }
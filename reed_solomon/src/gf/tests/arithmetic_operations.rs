@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use super::GF256;
+use super::{GF2m, GF256, Symbol};
 
 pub fn test_gf256<T: GF256>(gf: T) {
     println!("Testing GF256 implementation...");
@@ -253,7 +253,7 @@ pub fn test_gf256_performance<T: GF256>(gf: T, expected_duration: Duration) {
             for b in 1..=255u8 {
                 result ^= gf.mul(a, b);
                 result ^= gf.div(a, b);
-                result ^= gf.pow(a, b % 16); // Ограничиваем степень для скорости
+                result ^= gf.pow(a, (b % 16) as usize); // Ограничиваем степень для скорости
             }
         }
     }
@@ -282,3 +282,51 @@ pub fn test_gf256_exceptions<T: GF256 + std::panic::RefUnwindSafe>(gf: T) {
     });
     assert!(result.is_err(), "Inverse of zero should panic");
 }
+
+/// Проверка аксиом поля GF(2^m) произвольной степени. Для больших полей берется выборка элементов.
+pub fn test_gf2m<T: GF2m>(gf: &T) {
+    let step = (gf.size() / 256).max(1);
+    let elements = (0..gf.size())
+        .step_by(step)
+        .chain([gf.order()])
+        .map(T::Symbol::from_index)
+        .collect::<Vec<_>>();
+    let zero = T::Symbol::ZERO;
+    let one = T::Symbol::ONE;
+
+    for &a in &elements {
+        assert_eq!(gf.mul(a, one), a, "Multiplicative identity failed for {a}");
+        assert_eq!(gf.mul(a, zero), zero, "Multiplication by zero failed for {a}");
+
+        if a != zero {
+            assert_eq!(gf.mul(a, gf.inverse(a)), one, "Inverse failed for {a}");
+            assert_eq!(gf.pow(a, gf.order()), one, "Fermat's little theorem failed for {a}");
+        }
+
+        for &b in elements.iter().step_by(7) {
+            let product = gf.mul(a, b);
+            assert_eq!(product, gf.mul(b, a), "Mul commutativity failed for {a} * {b}");
+            assert!(product.index() < gf.size(), "Product {product} is out of field");
+            if b != zero {
+                assert_eq!(gf.div(product, b), a, "Division failed for {a} * {b} / {b}");
+            }
+
+            let c = gf.alpha_pow(a.index() + b.index());
+            assert_eq!(
+                gf.mul(a, gf.add(b, c)),
+                gf.add(gf.mul(a, b), gf.mul(a, c)),
+                "Distributive property failed for {a}, {b}, {c}"
+            );
+            assert_eq!(
+                gf.mul(gf.mul(a, b), c),
+                gf.mul(a, gf.mul(b, c)),
+                "Mul associativity failed for {a}, {b}, {c}"
+            );
+        }
+    }
+
+    // Степени примитивного элемента пробегают все ненулевые элементы
+    assert_eq!(gf.alpha_pow(0), one);
+    assert_eq!(gf.alpha_pow(gf.order()), one);
+    assert_eq!(gf.alpha_pow(gf.order() + 5), gf.alpha_pow(5));
+}
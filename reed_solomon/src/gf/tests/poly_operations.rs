@@ -1,7 +1,7 @@
 use rand;
 
 use super::super::GF256Poly;
-use crate::{Poly, RefPoly};
+use crate::Poly;
 
 pub fn test_poly_operations<T: GF256Poly>(gf: T) {
     test_div(&gf);
@@ -0,0 +1,271 @@
+//! Перемежение кодовых слов для защиты от пакетов ошибок.
+//!
+//! Без перемежения пакет ошибок длиннее `t = control_count / 2` символов целиком попадает в одно кодовое
+//! слово и делает его неисправимым. Перемежитель глубины `I` передает рядом символы разных кодовых слов,
+//! поэтому пакет длины до `I·t` искажает каждое слово не более чем в `t` символах.
+
+use anyhow::Result;
+
+/// Перемежает кодовые слова в один поток и восстанавливает их из потока.
+pub trait Interleaver {
+    /// Объединяет кодовые слова в поток длины `сумма длин + overhead()`.
+    fn interleave(&self, codewords: &[Vec<u8>]) -> Vec<u8>;
+
+    /// Восстанавливает кодовые слова с длинами `lengths` из потока, полученного [`Interleaver::interleave`].
+    fn deinterleave(&self, data: &[u8], lengths: &[usize]) -> Result<Vec<Vec<u8>>>;
+
+    /// Количество служебных символов, которые перемежитель добавляет к потоку.
+    fn overhead(&self) -> usize {
+        0
+    }
+}
+
+/// Блочный перемежитель: кодовые слова объединяются в группы по `depth` штук, и символ `j` всех слов
+/// группы передается подряд, как при записи матрицы по строкам и чтении по столбцам.
+///
+/// Пакет длины до `depth·t` исправляется в полных группах одинаковых по длине слов. В последней неполной
+/// группе или при укороченном последнем слове столбцы короче, и защита от пакетов слабее.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockInterleaver {
+    depth: usize,
+}
+
+impl BlockInterleaver {
+    pub fn build(depth: usize) -> Result<Self> {
+        anyhow::ensure!(depth > 0, "Interleaving depth must be positive");
+        Ok(Self { depth })
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Обходит символы в порядке передачи: пары (номер кодового слова, позиция в слове).
+    fn order(&self, lengths: &[usize]) -> impl Iterator<Item = (usize, usize)> {
+        lengths
+            .chunks(self.depth)
+            .enumerate()
+            .flat_map(move |(group, group_lengths)| {
+                let first = group * self.depth;
+                let max_len = group_lengths.iter().copied().max().unwrap_or(0);
+
+                (0..max_len).flat_map(move |j| {
+                    group_lengths
+                        .iter()
+                        .enumerate()
+                        .filter(move |&(_, &len)| j < len)
+                        .map(move |(i, _)| (first + i, j))
+                })
+            })
+    }
+}
+
+impl Interleaver for BlockInterleaver {
+    fn interleave(&self, codewords: &[Vec<u8>]) -> Vec<u8> {
+        let lengths = codewords.iter().map(Vec::len).collect::<Vec<_>>();
+        self.order(&lengths).map(|(i, j)| codewords[i][j]).collect()
+    }
+
+    fn deinterleave(&self, data: &[u8], lengths: &[usize]) -> Result<Vec<Vec<u8>>> {
+        let total = lengths.iter().sum::<usize>();
+        anyhow::ensure!(
+            data.len() == total,
+            "Interleaved data length {} does not match codewords length {total}",
+            data.len()
+        );
+
+        let mut codewords = lengths.iter().map(|&len| Vec::with_capacity(len)).collect::<Vec<_>>();
+        // Внутри слова символы идут по возрастанию позиции, поэтому достаточно дописывать в конец
+        for ((i, _), &symbol) in self.order(lengths).zip(data) {
+            codewords[i].push(symbol);
+        }
+        Ok(codewords)
+    }
+}
+
+/// Сверточный перемежитель Форни: символы потока по очереди распределяются по `branches` ветвям,
+/// ветвь `i` задерживает символы на `i·delay` своих тактов. Используется, например, в DVB
+/// (`branches = 12`, `delay = 17` для слов RS(204, 188)).
+///
+/// Задержка меньше, чем у блочного перемежителя той же глубины, и не требует деления на группы, но поток
+/// удлиняется на `branches·(branches - 1)·delay` символов, пока ветви опустошаются. Пакет длины до
+/// `branches·t` исправляется, если длина кодового слова не больше `branches·delay`, а начало каждого
+/// слова приходится на ветвь 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvolutionalInterleaver {
+    branches: usize,
+    delay: usize,
+}
+
+impl ConvolutionalInterleaver {
+    pub fn build(branches: usize, delay: usize) -> Result<Self> {
+        anyhow::ensure!(branches > 0, "Number of branches must be positive");
+        anyhow::ensure!(delay > 0, "Branch delay must be positive");
+        Ok(Self { branches, delay })
+    }
+
+    pub fn branches(&self) -> usize {
+        self.branches
+    }
+
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+
+    /// Позиция в перемеженном потоке символа с номером `k` во входном потоке.
+    fn position(&self, k: usize) -> usize {
+        k + (k % self.branches) * self.delay * self.branches
+    }
+}
+
+impl Interleaver for ConvolutionalInterleaver {
+    fn interleave(&self, codewords: &[Vec<u8>]) -> Vec<u8> {
+        let total = codewords.iter().map(Vec::len).sum::<usize>();
+        // Такты, в которые ветви еще не заполнены или уже опустошены, передают нули
+        let mut result = vec![0; total + self.overhead()];
+
+        for (k, &symbol) in codewords.iter().flatten().enumerate() {
+            result[self.position(k)] = symbol;
+        }
+        result
+    }
+
+    fn deinterleave(&self, data: &[u8], lengths: &[usize]) -> Result<Vec<Vec<u8>>> {
+        let total = lengths.iter().sum::<usize>();
+        anyhow::ensure!(
+            data.len() == total + self.overhead(),
+            "Interleaved data length {} does not match codewords length {total} with overhead {}",
+            data.len(),
+            self.overhead()
+        );
+
+        let mut k = 0;
+        let codewords = lengths
+            .iter()
+            .map(|&len| {
+                let codeword = (k..k + len).map(|k| data[self.position(k)]).collect();
+                k += len;
+                codeword
+            })
+            .collect();
+        Ok(codewords)
+    }
+
+    fn overhead(&self) -> usize {
+        self.branches * (self.branches - 1) * self.delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{BlockCoder, new_reed_solomon};
+
+    fn random_data(len: usize) -> Vec<u8> {
+        (0..len).map(|_| rand::random()).collect()
+    }
+
+    /// Искажает `len` подряд идущих символов, начиная с `start`.
+    fn burst(data: &[u8], start: usize, len: usize) -> Vec<u8> {
+        let mut corrupted = data.to_vec();
+        for symbol in &mut corrupted[start..start + len] {
+            *symbol ^= rand::random_range(1..=255);
+        }
+        corrupted
+    }
+
+    #[test]
+    fn test_block_interleaver_order() {
+        let interleaver = BlockInterleaver::build(2).unwrap();
+        let codewords = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8]];
+
+        let interleaved = interleaver.interleave(&codewords);
+        assert_eq!(interleaved, vec![1, 4, 2, 5, 3, 6, 7, 8]);
+        assert_eq!(interleaver.deinterleave(&interleaved, &[3, 3, 2]).unwrap(), codewords);
+
+        assert!(interleaver.deinterleave(&interleaved, &[3, 3, 3]).is_err());
+        assert!(BlockInterleaver::build(0).is_err());
+    }
+
+    #[test]
+    fn test_convolutional_interleaver_order() {
+        let interleaver = ConvolutionalInterleaver::build(3, 1).unwrap();
+        let codewords = vec![vec![1, 2, 3, 4, 5, 6]];
+
+        let interleaved = interleaver.interleave(&codewords);
+        assert_eq!(interleaved.len(), 6 + interleaver.overhead());
+        assert_eq!(interleaved, vec![1, 0, 0, 4, 2, 0, 0, 5, 3, 0, 0, 6]);
+        assert_eq!(interleaver.deinterleave(&interleaved, &[6]).unwrap(), codewords);
+
+        let codewords = vec![random_data(10), random_data(7)];
+        let interleaved = interleaver.interleave(&codewords);
+        assert_eq!(interleaver.deinterleave(&interleaved, &[10, 7]).unwrap(), codewords);
+        assert!(interleaver.deinterleave(&interleaved[1..], &[10, 7]).is_err());
+    }
+
+    #[test]
+    fn test_block_interleaver_bursts() {
+        // t = 4, глубина 5: пакеты до 20 символов
+        let (control_count, depth, block_size) = (8, 5, 20);
+        let rs = new_reed_solomon(control_count);
+        let interleaver = BlockInterleaver::build(depth).unwrap();
+        let codeword_size = block_size + control_count;
+
+        let data = random_data(block_size * depth * 3);
+        let encoded = rs.encode_interleaved(&data, block_size, &interleaver).unwrap();
+        let max_burst = depth * control_count / 2;
+
+        for start in (0..encoded.len() - max_burst).step_by(7) {
+            let corrupted = burst(&encoded, start, max_burst);
+            let decoded = rs.decode_interleaved(&corrupted, codeword_size, &interleaver).unwrap();
+            assert_eq!(decoded, data, "Burst at {start}");
+        }
+
+        // Без перемежения тот же пакет разрушает кодовое слово
+        let concatenated = rs.encode_blocks_to_vec(&data, block_size).unwrap();
+        let corrupted = burst(&concatenated, codeword_size, max_burst);
+        assert!(rs.decode_blocks_to_vec(&corrupted, codeword_size).is_err());
+    }
+
+    #[test]
+    fn test_convolutional_interleaver_bursts() {
+        // Слово из 24 символов = branches·delay, t = 3: пакеты до 12 символов
+        let (control_count, branches, delay) = (6, 4, 6);
+        let codeword_size = branches * delay;
+        let block_size = codeword_size - control_count;
+        let rs = new_reed_solomon(control_count);
+        let interleaver = ConvolutionalInterleaver::build(branches, delay).unwrap();
+
+        let data = random_data(block_size * 10);
+        let encoded = rs.encode_interleaved(&data, block_size, &interleaver).unwrap();
+        assert_eq!(encoded.len(), codeword_size * 10 + interleaver.overhead());
+        let max_burst = branches * control_count / 2;
+
+        for start in 0..encoded.len() - max_burst {
+            let corrupted = burst(&encoded, start, max_burst);
+            let decoded = rs.decode_interleaved(&corrupted, codeword_size, &interleaver).unwrap();
+            assert_eq!(decoded, data, "Burst at {start}");
+        }
+    }
+
+    #[test]
+    fn test_interleaved_round_trip() {
+        let rs = new_reed_solomon(4);
+        let block = BlockInterleaver::build(3).unwrap();
+        let convolutional = ConvolutionalInterleaver::build(3, 2).unwrap();
+
+        for len in [0, 1, 10, 11, 37, 100] {
+            let data = random_data(len);
+
+            let encoded = rs.encode_interleaved(&data, 10, &block).unwrap();
+            assert_eq!(rs.decode_interleaved(&encoded, 14, &block).unwrap(), data);
+
+            let encoded = rs.encode_interleaved(&data, 10, &convolutional).unwrap();
+            assert_eq!(rs.decode_interleaved(&encoded, 14, &convolutional).unwrap(), data);
+        }
+
+        assert!(rs.decode_interleaved(&[0; 3], 14, &convolutional).is_err());
+    }
+}
@@ -2,10 +2,12 @@ use anyhow::Result;
 
 mod coder_trait;
 pub mod gf;
+pub mod interleaver;
 mod reed_solomon;
+pub mod shard;
 
 pub use coder_trait::{BlockCoder, Coder};
-pub use reed_solomon::ReedSolomon;
+pub use reed_solomon::{DecodeBuffers, DecodeError, DecodeReport, KeySolver, Layout, ReedSolomon};
 
 /// Представление полинома в поле GF(256). Старший индекс - старший коэффициент.
 type Poly = Vec<u8>;
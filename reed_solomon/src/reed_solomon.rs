@@ -1,8 +1,16 @@
 use anyhow::Result;
 
+mod buffers;
+mod report;
+mod solver;
+
+pub use buffers::DecodeBuffers;
+pub use report::{DecodeError, DecodeReport};
+pub use solver::KeySolver;
+
 use crate::{
     Coder, Poly, RefPoly,
-    gf::{GF256, GF256Poly},
+    gf::{GF2mPoly, GF256, GF256Poly, Symbol},
 };
 
 /// k – число информационных символов, подлежащих кодированию,
@@ -12,56 +20,132 @@ use crate::{
 /// Минимальное расстояние определяется следующим образом: dmin = n–k+1.
 pub struct ReedSolomon<T>
 where
-    T: GF256Poly,
+    T: GF2mPoly,
 {
     control_count: usize,
+    first_root: usize,
+    layout: Layout,
+    solver: KeySolver,
     gf: T,
-    gen_poly: Poly,
+    gen_poly: Vec<T::Symbol>,
+    /// Корни порождающего многочлена `a^(fcr+i)` для вычисления синдромов.
+    roots: Vec<T::Symbol>,
+    /// Шаги поиска Ченя `a^(-j)` для степеней локатора от 0 до `control_count`.
+    chien_steps: Vec<T::Symbol>,
+}
+
+/// Порядок символов в кодовом слове на входе кодировщика и выходе декодировщика.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// Символ под индексом `i` - коэффициент при `x^i`: сначала контрольные символы, затем данные.
+    #[default]
+    ControlFirst,
+
+    /// Старший коэффициент первым (big-endian), как в QR-кодах, CD и большинстве стандартов:
+    /// сначала данные, затем контрольные символы. Первый символ данных - коэффициент при старшей степени.
+    ControlLast,
 }
 
 impl<T> ReedSolomon<T>
 where
-    T: GF256Poly,
+    T: GF2mPoly,
 {
     /// Кодировщик строит порождающий многочлен для указанного количества контрольных символов.
     ///
     /// # Panics
-    /// Panics if `control_count` is greater than 255.
+    /// Panics if `control_count` is greater than `2^m - 1` (255 for GF(256)).
     ///
     /// Причина: при вычислении синдромов и локаторов ошибок используются степени примитивного элемента.
-    /// Если `i > 255`, то `a^i` начнет повторяться из за цикличности поля Галуа. Это нарушит уникальность
+    /// Если `i > 2^m - 1`, то `a^i` начнет повторяться из за цикличности поля Галуа. Это нарушит уникальность
     /// синдромов и сделает невозможным корректное декодирование.
     pub fn new(control_count: usize, gf: T) -> Self {
-        if control_count > 255 {
+        if control_count > gf.order() {
             panic!(
-                "The number of control characters cannot exceed 255, actual: {}",
+                "The number of control characters cannot exceed {}, actual: {}",
+                gf.order(),
                 control_count
             );
         }
 
         Self {
             control_count,
-            gen_poly: Self::build_gen_poly(&gf, control_count),
+            first_root: 0,
+            layout: Layout::default(),
+            solver: KeySolver::default(),
+            gen_poly: Self::build_gen_poly(&gf, control_count, 0),
+            roots: Self::build_roots(&gf, control_count, 0),
+            chien_steps: (0..=control_count).map(|j| gf.inverse(gf.alpha_pow(j))).collect(),
             gf,
         }
     }
 
+    /// Задает степень первого из последовательных корней порождающего многочлена (first consecutive root, `fcr`):
+    /// корнями будут `a^fcr, a^(fcr+1), ..., a^(fcr+control_count-1)`. QR-коды и DVB используют `fcr = 0`,
+    /// Data Matrix - `fcr = 1`, CCSDS - `fcr = 112`.
+    pub fn with_first_root(mut self, first_root: usize) -> Self {
+        self.first_root = first_root;
+        self.gen_poly = Self::build_gen_poly(&self.gf, self.control_count, first_root);
+        self.roots = Self::build_roots(&self.gf, self.control_count, first_root);
+        self
+    }
+
+    /// Задает порядок символов в кодовом слове. По умолчанию [`Layout::ControlFirst`].
+    ///
+    /// Для [`Layout::ControlLast`] позиции стираний и ошибок в [`DecodeReport`] отсчитываются от начала
+    /// кодового слова в том же порядке, а синдромы и локатор не меняются.
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Задает алгоритм решения ключевого уравнения. По умолчанию [`KeySolver::BerlekampMassey`].
+    pub fn with_solver(mut self, solver: KeySolver) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    pub fn solver(&self) -> KeySolver {
+        self.solver
+    }
+
+    pub fn first_root(&self) -> usize {
+        self.first_root
+    }
+
+    pub fn control_count(&self) -> usize {
+        self.control_count
+    }
+
+    /// Корень порождающего многочлена `a^(fcr+i)`.
+    fn root(gf: &T, first_root: usize, i: usize) -> T::Symbol {
+        gf.alpha_pow((first_root + i) % gf.order())
+    }
+
+    /// Все корни порождающего многочлена `a^fcr, ..., a^(fcr+control_count-1)`.
+    fn build_roots(gf: &T, control_count: usize, first_root: usize) -> Vec<T::Symbol> {
+        (0..control_count).map(|i| Self::root(gf, first_root, i)).collect()
+    }
+
     /// Конструирует порождающий многочлен следующим образом:
     ///
-    /// `g(x) = (x + a^0)(x + a^1)...(x + a^(control_count-1))`
+    /// `g(x) = (x + a^fcr)(x + a^(fcr+1))...(x + a^(fcr+control_count-1))`
     ///
-    /// где `a` - примитивный элемент.
-    fn build_gen_poly(gf: &T, control_count: usize) -> Poly {
-        let mut gen_poly = vec![1];
+    /// где `a` - примитивный элемент, `fcr` - степень первого корня.
+    fn build_gen_poly(gf: &T, control_count: usize, first_root: usize) -> Vec<T::Symbol> {
+        let mut gen_poly = vec![T::Symbol::ONE];
 
-        // Умножаем на (x + α^i)
+        // Умножаем на (x + α^(fcr+i))
         // По правилу a * (b + c) = a * b + a * c
         for i in 0..control_count {
             // Сперва умножаем на x, сдвигая коэффициенты
             let shifted_poly = gf.shift_poly(&gen_poly, 1);
 
-            // Затем умножаем на α^i
-            let alpha_i = gf.alpha_pow(i as u8);
+            // Затем умножаем на α^(fcr+i)
+            let alpha_i = Self::root(gf, first_root, i);
             gen_poly = gf.mul_poly(&gen_poly, &vec![alpha_i]);
 
             // Складываем с результатом умножения
@@ -78,14 +162,25 @@ where
     /// Нетрудно убедиться, что если бы сообщение не было искажено, то все коэффициенты Si оказались
     /// бы равны нулю: ведь неискажённое сообщение `C(x)` кратно порождающему многочлену `g(x)`,
     /// для которого числа `a1 , a2, ..., aN-K` являются корнями.
-    fn calculate_syndromes(&self, data: RefPoly) -> Poly {
-        let mut syndromes = vec![0u8; self.control_count];
-
-        for i in 0..self.control_count {
-            let point = self.gf.alpha_pow(i as u8); // Используем α^i
-            syndromes[i] = self.gf.eval_poly(data, point);
+    ///
+    /// Все синдромы вычисляются за один проход по сообщению по схеме Горнера: на каждом символе
+    /// `Si = Si·a^(fcr+i) + c`. Цепочки умножений для разных синдромов независимы, поэтому процессор
+    /// выполняет их параллельно. Корни `a^(fcr+i)` вычисляются один раз при создании кодировщика.
+    fn calculate_syndromes_into(&self, data: &[T::Symbol], syndromes: &mut Vec<T::Symbol>) {
+        syndromes.clear();
+        syndromes.resize(self.control_count, T::Symbol::ZERO);
+
+        for &coef in data.iter().rev() {
+            for (syndrome, &root) in syndromes.iter_mut().zip(&self.roots) {
+                *syndrome = self.gf.add(self.gf.mul(*syndrome, root), coef);
+            }
         }
+    }
 
+    #[cfg(test)]
+    fn calculate_syndromes(&self, data: &[T::Symbol]) -> Vec<T::Symbol> {
+        let mut syndromes = Vec::new();
+        self.calculate_syndromes_into(data, &mut syndromes);
         syndromes
     }
 
@@ -109,28 +204,76 @@ where
     /// значение локатора на момент последнего корректирования, `b` - копия последнего расхождения `d`
     ///
     /// Если степень локатора <= шагу `i`, то нужно обновить `B(x)`. Итераций проходит столько, сколько синдромов.
-    fn find_error_locator(&self, syndromes: RefPoly) -> Result<Poly> {
-        // C(x) - текущий полином локатора ошибок
-        let mut locator = vec![1u8]; // C(x) = 1
-        let mut old_locator = vec![1u8]; // B(x) — копия последнего C(x) на момент обновления L
-        let mut locator_degree = 0; // L - текущая степень C(x)
+    #[cfg(test)]
+    fn find_error_locator(&self, syndromes: &[T::Symbol]) -> Result<Vec<T::Symbol>, DecodeError> {
+        let (mut locator, mut old_locator, mut scratch) = (Vec::new(), Vec::new(), Vec::new());
+        self.find_errata_locator_into(syndromes, &[], &mut locator, &mut old_locator, &mut scratch)?;
+        Ok(locator)
+    }
+
+    /// Строит многочлен локаторов стираний `Г(x) = (1+xX1)(1+xX2)…(1+xXe)`, где `Xj = a^pos` - локаторы
+    /// известных позиций стираний. Множители `1+xXj` умножаются на месте, от старших коэффициентов к младшим.
+    fn find_erasure_locator_into(&self, erasures: &[usize], locator: &mut Vec<T::Symbol>) {
+        locator.clear();
+        locator.push(T::Symbol::ONE);
+
+        for &pos in erasures {
+            let root = self.gf.alpha_pow(pos);
+            locator.push(T::Symbol::ZERO);
+            for i in (1..locator.len()).rev() {
+                locator[i] = self.gf.add(locator[i], self.gf.mul(locator[i - 1], root));
+            }
+        }
+    }
+
+    fn find_erasure_locator(&self, erasures: &[usize]) -> Vec<T::Symbol> {
+        let mut locator = Vec::new();
+        self.find_erasure_locator_into(erasures, &mut locator);
+        locator
+    }
+
+    /// Находит многочлен локаторов ошибок и стираний алгоритмом Берлекэмпа-Месси.
+    ///
+    /// Без стираний алгоритм совпадает с описанным в [`Self::find_error_locator`]. Если известны `e` позиций
+    /// стираний, то `C(x)` и `B(x)` инициализируются локатором стираний `Г(x)`, `L = e`, а итерации начинаются
+    /// с синдрома `Se`. Это равносильно работе обычного алгоритма с модифицированными синдромами
+    /// `Г(x)·S(x)`: найденный многочлен делится на `Г(x)` и дополнительно содержит локаторы неизвестных ошибок.
+    ///
+    /// Исправить удается `v` ошибок и `e` стираний, если `2v + e <= control_count`.
+    ///
+    /// Локатор `C(x)` исправляется на месте в `locator`, `B(x)` хранится в `old_locator`, а `scratch`
+    /// нужен для копии `C(x)` перед исправлением, поэтому при переиспользовании буферов память не выделяется.
+    fn find_errata_locator_into(
+        &self,
+        syndromes: &[T::Symbol],
+        erasures: &[usize],
+        locator: &mut Vec<T::Symbol>,
+        old_locator: &mut Vec<T::Symbol>,
+        scratch: &mut Vec<T::Symbol>,
+    ) -> Result<(), DecodeError> {
+        let erasure_count = erasures.len();
+
+        // C(x) - текущий полином локатора ошибок, C(x) = Г(x)
+        self.find_erasure_locator_into(erasures, locator);
+        // B(x) — копия последнего C(x) на момент обновления L
+        old_locator.clear();
+        old_locator.extend_from_slice(locator);
+        let mut locator_degree = erasure_count; // L - текущая степень C(x)
         let mut m = 1; // сдвиг или номер итерации, прошедших с обновления L
-        let mut old_discrepancy = 1u8; // значение расхождения d на предыдущем шаге, когда мы обновляли L и сохраняли старый локатор или последнее ненулевое расхождение discrepancy
+        let mut old_discrepancy = T::Symbol::ONE; // значение расхождения d на предыдущем шаге, когда мы обновляли L и сохраняли старый локатор или последнее ненулевое расхождение discrepancy
 
-        for n in 0..self.control_count {
+        for n in erasure_count..self.control_count {
             // В little-endian: locator[i] соответствует коэффициенту при x^i
 
             // Вычисляем расхождение d = Sn + C₁ * S{n-1} + C₂ * S{n-2} + ... + CL * S{n-L}
             let mut discrepancy = syndromes[n];
-            for i in 1..=locator_degree {
-                if i < locator.len() && i <= n {
-                    let product = self.gf.mul(locator[i], syndromes[n - i]);
-                    discrepancy = self.gf.add(discrepancy, product);
-                }
+            for i in 1..locator.len().min(n + 1) {
+                let product = self.gf.mul(locator[i], syndromes[n - i]);
+                discrepancy = self.gf.add(discrepancy, product);
             }
 
             // Если d равно нулю, это значит C(x) и L на данный момент верны, достаточно инкрементировать m и продолжить итерации.
-            if discrepancy == 0 {
+            if discrepancy == T::Symbol::ZERO {
                 m += 1;
                 continue;
             }
@@ -138,50 +281,53 @@ where
             // Если d ненулевое, алгоритм поправляет C(x) так, чтобы его обнулить:
             // C(x) = C(x) - (d/b)·B(x)·x^m, где B(x) – предыдущий C(x), b - копия последнего расхождения d
 
-            // Масштабируем old_locator на (d / b)
-            let scale = self.gf.div(discrepancy, old_discrepancy);
-            let scaled_old = self.gf.scale_poly(&old_locator, scale);
+            // Если 2L ≤ n + e, то после корректировки B(x) станет текущим C(x): сохраняем его копию
+            let update = 2 * locator_degree <= n + erasure_count;
+            if update {
+                scratch.clear();
+                scratch.extend_from_slice(locator);
+            }
 
-            // Умножение на x^m — это, по сути, сдвиг коэффициентов B(x) на m
-            let shifted_scaled_old = self.gf.shift_poly(&scaled_old, m);
+            // Корректируем локатор: C(x) += (d/b) * B(x) * x^m. Умножение на x^m — сдвиг коэффициентов
+            // B(x) на m, а сложение и вычитание - одно и то же
+            let scale = self.gf.div(discrepancy, old_discrepancy);
+            if locator.len() < old_locator.len() + m {
+                locator.resize(old_locator.len() + m, T::Symbol::ZERO);
+            }
+            for (i, &coef) in old_locator.iter().enumerate() {
+                locator[i + m] = self.gf.add(locator[i + m], self.gf.mul(coef, scale));
+            }
 
-            // Если 2L ≤ n: Обновляем степень и предыдущий локатор
-            if 2 * locator_degree <= n {
+            if update {
                 // Обновляем L и B(x)
-                locator_degree = n + 1 - locator_degree;
-                old_locator = locator.clone();
+                locator_degree = n + 1 + erasure_count - locator_degree;
+                std::mem::swap(old_locator, scratch);
                 old_discrepancy = discrepancy;
                 m = 1;
 
-            // Если 2L > n, то после корректировки локатора ничего не меняем
+            // Если 2L > n + e, то после корректировки локатора ничего не меняем
             } else {
                 m += 1;
             }
 
-            // Корректируем локатор: C(x) += (d/b) * B(x) * x^m
-            locator = self.gf.add_poly(&locator, &shifted_scaled_old); // Сложение и вычитание - одно и то же
-
             // Обрезаем ведущие нули (в little-endian нули в конце)
-            while locator.len() > 1 && *locator.last().unwrap() == 0 {
+            while locator.len() > 1 && *locator.last().unwrap() == T::Symbol::ZERO {
                 locator.pop();
             }
         }
 
         // Проверяем, что можем исправить найденное количество ошибок
-        // Можем исправить максимум control_count / 2 ошибок
-        if locator_degree > self.control_count / 2 {
-            anyhow::bail!(
-                "Failed to find error locator: too many errors to correct \
-                (locator degree: {locator_degree}, control count: {}, max correctable: {}). \n\
-                Syndromes:\t{syndromes:?} \n\
-                Locator:\t{locator:?} \n\
-                Old Loc:\t{old_locator:?}",
-                self.control_count,
-                self.control_count / 2
-            );
+        // Можем исправить v ошибок и e стираний при 2v + e <= control_count
+        let error_count = locator_degree.saturating_sub(erasure_count);
+        if 2 * error_count + erasure_count > self.control_count {
+            return Err(DecodeError::TooManyErrors {
+                locator_degree,
+                erasures: erasure_count,
+                control_count: self.control_count,
+            });
         }
 
-        Ok(locator)
+        Ok(())
     }
 
     /// Находит корни полинома локатора L(x) – они будут обратны к локаторам ошибок.
@@ -193,75 +339,121 @@ where
     /// проб и ошибок, получивший название метод Ченя. Для всех ненулевых элементов a GF(2m),
     /// которые генерируются в порядке `1, a, а2,... a14` проверяется условие `L(a^(-1))=0`.
     /// Если элемент i обращает локатор в 0, то на его месте находится ошибка.
-    fn find_error_positions(&self, error_locator: RefPoly, data_len: usize) -> Result<Vec<usize>> {
-        let mut positions = Vec::new();
+    ///
+    /// Значение вычисляется инкрементально: слагаемое `Lj·a^(-ij)` для позиции `i + 1` получается из
+    /// слагаемого для позиции `i` умножением на `a^(-j)`, поэтому на позицию приходится `deg L` умножений
+    /// без возведения в степень. Множители `a^(-j)` вычисляются один раз при создании кодировщика.
+    /// Поиск заканчивается, когда найдено `deg L` корней - больше их быть не может.
+    ///
+    /// Количество корней должно совпасть со степенью локатора, иначе часть локаторов лежит за пределами
+    /// кодового слова и ошибок больше, чем можно исправить.
+    fn find_error_positions_into(
+        &self,
+        error_locator: &[T::Symbol],
+        data_len: usize,
+        terms: &mut Vec<T::Symbol>,
+        positions: &mut Vec<usize>,
+    ) -> Result<(), DecodeError> {
         let expected_errors = error_locator.len() - 1;
+        // Решатели ключевого уравнения не возвращают локатор степени больше control_count
+        if error_locator.len() > self.chien_steps.len() {
+            return Err(DecodeError::TooManyErrors {
+                locator_degree: expected_errors,
+                erasures: 0,
+                control_count: self.control_count,
+            });
+        }
+        positions.clear();
+
+        // terms[j] = Lj·α^(-ij), chien_steps[j] = α^(-j)
+        terms.clear();
+        terms.extend_from_slice(error_locator);
 
-        // Проверяем все возможные позиции ошибок методом Чена
         // L(x) имеет корни в обратных значениях локаторов ошибок
         // Если L(α^(-i)) = 0, то ошибка в позиции i
         for i in 0..data_len {
-            let alpha_i = self.gf.alpha_pow(i as u8); // α^i
-            let alpha_inv = self.gf.inverse(alpha_i); // α^(-i)
-            let value = self.gf.eval_poly(error_locator, alpha_inv);
+            if positions.len() == expected_errors {
+                break;
+            }
 
-            if value == 0 {
+            let value = terms.iter().fold(T::Symbol::ZERO, |acc, &term| self.gf.add(acc, term));
+            if value == T::Symbol::ZERO {
                 positions.push(i);
             }
+
+            for (term, &step) in terms.iter_mut().zip(&self.chien_steps) {
+                *term = self.gf.mul(*term, step);
+            }
         }
 
-        if positions.len() > expected_errors {
-            anyhow::bail!(
-                "Found more roots ({}) than expected errors ({}). \n\
-                Locator: {:?}, \n\
-                Data length: {}, Found positions: {:?}",
-                positions.len(),
-                expected_errors,
-                error_locator,
-                data_len,
-                positions
-            );
+        if positions.len() != expected_errors {
+            return Err(DecodeError::LocatorRootMismatch {
+                locator_degree: expected_errors,
+                roots: positions.len(),
+            });
         }
 
-        Ok(positions)
+        Ok(())
     }
 
-    /// 1. Вычисляется `W(x) = L(x)*S(x)`, коэффициенты старшие чем N-k должны быть обнулены.
+    /// Вычисляет многочлен значений ошибок `W(x) = L(x)*S(x) mod x^(N-k)`: коэффициенты старшие чем N-k
+    /// не вычисляются.
+    fn find_error_evaluator_into(&self, syndromes: &[T::Symbol], locator: &[T::Symbol], omega: &mut Vec<T::Symbol>) {
+        omega.clear();
+        omega.resize(
+            self.control_count.min(locator.len() + syndromes.len() - 1),
+            T::Symbol::ZERO,
+        );
+
+        for (i, &coef) in locator.iter().enumerate().take(omega.len()) {
+            for (j, &syndrome) in syndromes.iter().enumerate().take(omega.len() - i) {
+                omega[i + j] = self.gf.add(omega[i + j], self.gf.mul(coef, syndrome));
+            }
+        }
+    }
+
+    /// 1. Берется многочлен значений ошибок `W(x) = L(x)*S(x) mod x^(N-k)`, см. [`Self::find_error_evaluator_into`].
     /// 2. Вычисляется производная локатора ошибок `L'(x)`.
-    /// 3. Далее вычисляются значения ошибок по формуле `Yi = W( Xi^(-1) )/L'( Xi^(-1) )`,
-    /// где x – это примитивный элемент в степени равной позиции ошибки.
+    /// 3. Далее вычисляются значения ошибок по формуле `Yi = Xi^(1-fcr) * W( Xi^(-1) )/L'( Xi^(-1) )`,
+    ///    где `Xi` – это примитивный элемент в степени равной позиции ошибки, `fcr` - степень первого корня
+    ///    порождающего многочлена.
     /// Таким образом, составляется полином ошибки. Его коэффициентами являются значения ошибок Yi
     /// стоящие в позициях, определяемых локаторами ошибок.
-    fn find_error_magnitudes(&self, syndromes: RefPoly, locator: RefPoly, error_positions: &[usize]) -> Vec<u8> {
-        // W(x) = L(x)*S(x) mod x^{control_count}
-        let mut omega = self.gf.mul_poly(locator, syndromes);
-        omega.truncate(self.control_count);
-
+    fn find_error_magnitudes_into(
+        &self,
+        omega: &[T::Symbol],
+        locator: &[T::Symbol],
+        error_positions: &[usize],
+        locator_derivative: &mut Vec<T::Symbol>,
+        magnitudes: &mut Vec<T::Symbol>,
+    ) {
         // Вычисляем производную локатора ошибок
-        let locator_derivative = self.find_locator_derivative(&locator);
+        self.find_locator_derivative_into(locator, locator_derivative);
 
-        let mut magnitudes = Vec::new();
+        magnitudes.clear();
 
         for &err_pos in error_positions.iter() {
-            let alpha_i = self.gf.alpha_pow(err_pos as u8);
+            let alpha_i = self.gf.alpha_pow(err_pos);
             let alpha_inv = self.gf.inverse(alpha_i);
 
-            let numerator = self.gf.eval_poly(&omega, alpha_inv);
-            let denominator = self.gf.eval_poly(&locator_derivative, alpha_inv);
+            let numerator = self.gf.eval_poly(omega, alpha_inv);
+            let denominator = self.gf.eval_poly(locator_derivative, alpha_inv);
 
+            // Множитель Xi^(1-fcr) по модулю порядка мультипликативной группы
             let division = self.gf.div(numerator, denominator);
-            let magnitude = self.gf.mul(division, alpha_i);
+            let order = self.gf.order();
+            let scale = self.gf.pow(alpha_i, (order + 1 - self.first_root % order) % order);
+            let magnitude = self.gf.mul(division, scale);
 
             magnitudes.push(magnitude);
         }
-
-        magnitudes
     }
 
     /// Вычисляет производную L'(x) следующим образом – для чётных степеней производная равна нулю,
     /// для нечётных - степени, как обычно, уменьшенной на 1: `(x^2)' = 0, (x^3)' = x^2`
-    fn find_locator_derivative(&self, locator: RefPoly) -> Poly {
-        let mut locator_derivative = vec![0; locator.len()];
+    fn find_locator_derivative_into(&self, locator: &[T::Symbol], locator_derivative: &mut Vec<T::Symbol>) {
+        locator_derivative.clear();
+        locator_derivative.resize(locator.len(), T::Symbol::ZERO);
 
         // Производная для x^0 = 0, поэтому начинаем с 1
         for i in 1..locator.len() {
@@ -273,23 +465,26 @@ where
 
         // Убираем нулевые коэффициенты
         for i in (1..locator_derivative.len()).rev() {
-            if locator_derivative[i] == 0 {
+            if locator_derivative[i] == T::Symbol::ZERO {
                 locator_derivative.pop();
             } else {
                 break;
             }
         }
+    }
 
+    #[cfg(test)]
+    fn find_locator_derivative(&self, locator: &[T::Symbol]) -> Vec<T::Symbol> {
+        let mut locator_derivative = Vec::new();
+        self.find_locator_derivative_into(locator, &mut locator_derivative);
         locator_derivative
     }
 
-    /// Исправляет ошибки в сообщении. Ошибка на позиции err_pos[i] с magnitude[i] вычитается из сообщения.
+    /// Исправляет ошибки в сообщении на месте. Ошибка на позиции err_pos[i] с magnitude[i] вычитается из сообщения.
     ///
     /// # Panics
     /// Паникует, если err_pos[i] >= message.len()
-    fn correct_errors(&self, data: RefPoly, error_positions: &[usize], error_magnitudes: &[u8]) -> Poly {
-        let mut corrected = data.to_vec();
-
+    fn correct_errors(&self, corrected: &mut [T::Symbol], error_positions: &[usize], error_magnitudes: &[T::Symbol]) {
         for (&pos, &magnitude) in error_positions.iter().zip(error_magnitudes.iter()) {
             if pos < corrected.len() {
                 corrected[pos] = self.gf.sub(corrected[pos], magnitude);
@@ -301,13 +496,252 @@ where
                     magnitudes: {:?}",
                     pos,
                     corrected.len(),
-                    data,
+                    corrected,
                     error_positions,
                     error_magnitudes
                 );
             }
         }
-        corrected
+    }
+
+    /// Проверяет, что все символы - элементы поля: для GF(2^m) с `m < 8` или `m < 16` старшие биты
+    /// типа символа должны быть нулевыми.
+    fn check_symbols(&self, data: &[T::Symbol]) -> Result<(), DecodeError> {
+        match data.iter().find(|s| s.index() >= self.gf.size()) {
+            Some(symbol) => Err(DecodeError::SymbolOutOfField {
+                symbol: symbol.index(),
+                degree: self.gf.degree(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Кодирует сообщение из символов поля GF(2^m). Длина кодового слова не превышает `2^m - 1`,
+    /// шаги описаны в [`Coder::encode`].
+    pub fn encode_symbols(&self, data: &[T::Symbol]) -> Result<Vec<T::Symbol>> {
+        let mut encoded = Vec::with_capacity(data.len() + self.control_count);
+        self.encode_symbols_into(data, &mut encoded)?;
+        Ok(encoded)
+    }
+
+    /// Кодирует сообщение, как [`Self::encode_symbols`], в буфер `encoded`. Предыдущее содержимое буфера
+    /// удаляется, а выделенная память переиспользуется, поэтому при кодировании потока блоков память
+    /// выделяется один раз.
+    pub fn encode_symbols_into(&self, data: &[T::Symbol], encoded: &mut Vec<T::Symbol>) -> Result<()> {
+        if data.len() + self.control_count > self.gf.order() {
+            anyhow::bail!("Message too long and cannot be encoded with GF(2^{})", self.gf.degree());
+        }
+        self.check_symbols(data)?;
+
+        encoded.clear();
+        match self.layout {
+            Layout::ControlFirst => {
+                encoded.resize(self.control_count, T::Symbol::ZERO);
+                encoded.extend_from_slice(data);
+                // Старший коэффициент многочлена данных - последний символ
+                self.encode_remainder(data.iter().rev().copied(), &mut encoded[..self.control_count]);
+            }
+            Layout::ControlLast => {
+                encoded.extend_from_slice(data);
+                encoded.resize(data.len() + self.control_count, T::Symbol::ZERO);
+                let control = &mut encoded[data.len()..];
+                self.encode_remainder(data.iter().copied(), control);
+                control.reverse();
+            }
+        }
+        Ok(())
+    }
+
+    /// Вычисляет контрольные символы `x^(N-k)·m(x) mod g(x)` сдвиговым регистром с обратной связью (LFSR)
+    /// вместо деления многочленов. Регистр `control[i]` хранит коэффициент остатка при `x^i`.
+    ///
+    /// Символы данных поступают от старшего коэффициента к младшему. На каждом такте обратная связь
+    /// `f = d + control[N-k-1]` умножается на коэффициенты `g(x)` и добавляется к регистру, сдвинутому
+    /// на одну позицию вверх: это один шаг деления столбиком на унитарный `g(x)`, но без выделения памяти
+    /// и хранения частного.
+    fn encode_remainder(&self, data: impl Iterator<Item = T::Symbol>, control: &mut [T::Symbol]) {
+        if control.is_empty() {
+            return;
+        }
+
+        for symbol in data {
+            let feedback = self.gf.add(symbol, control[self.control_count - 1]);
+            control.copy_within(..self.control_count - 1, 1);
+            control[0] = T::Symbol::ZERO;
+
+            if feedback != T::Symbol::ZERO {
+                for (reg, &coef) in control.iter_mut().zip(&self.gen_poly) {
+                    *reg = self.gf.add(*reg, self.gf.mul(feedback, coef));
+                }
+            }
+        }
+    }
+
+    /// Декодирует сообщение из символов поля GF(2^m), шаги описаны в [`Coder::decode`].
+    pub fn decode_symbols(&self, data: &[T::Symbol]) -> Result<Vec<T::Symbol>> {
+        self.decode_with_erasures(data, &[])
+    }
+
+    /// Декодирует сообщение, в котором известны позиции стираний - символов, значения которых потеряны
+    /// (например, пакет не дошел или сектор диска не читается). Позиция - индекс в `data`, значения на
+    /// стертых позициях могут быть любыми.
+    ///
+    /// Стирание требует одного контрольного символа, а ошибка в неизвестной позиции - двух, поэтому
+    /// исправляются `v` ошибок и `e` стираний, если `2v + e <= control_count`.
+    ///
+    /// Шаги совпадают с [`Coder::decode`], но Берлекэмп-Месси начинается с локатора стираний,
+    /// а значения ошибок и стираний вычисляются вместе алгоритмом Форни по общему локатору.
+    pub fn decode_with_erasures(&self, data: &[T::Symbol], erasures: &[usize]) -> Result<Vec<T::Symbol>> {
+        Ok(self.decode_with_report(data, erasures)?.data)
+    }
+
+    /// Декодирует сообщение со стираниями, как [`Self::decode_with_erasures`], и возвращает отчет:
+    /// синдромы, локатор, исправленные позиции и значения ошибок, количество ошибок и стираний.
+    ///
+    /// В отличие от остальных методов декодирования ошибка типизирована [`DecodeError`], что позволяет
+    /// отличить неисправимое сообщение от неверных аргументов.
+    pub fn decode_with_report(
+        &self,
+        data: &[T::Symbol],
+        erasures: &[usize],
+    ) -> Result<DecodeReport<T::Symbol>, DecodeError> {
+        let mut buffers = DecodeBuffers::new();
+        let mut decoded = Vec::with_capacity(data.len());
+        self.decode_symbols_into(data, erasures, &mut buffers, &mut decoded)?;
+
+        let DecodeBuffers {
+            syndromes,
+            erasures,
+            locator,
+            positions: mut error_positions,
+            magnitudes: mut error_magnitudes,
+            ..
+        } = buffers;
+
+        // Позиции в буферах - степени x, для ControlLast переводим их обратно в индексы data
+        if self.layout == Layout::ControlLast {
+            error_positions.reverse();
+            error_magnitudes.reverse();
+            for pos in error_positions.iter_mut() {
+                *pos = data.len() - 1 - *pos;
+            }
+        }
+
+        Ok(DecodeReport {
+            data: decoded,
+            syndromes,
+            error_count: (locator.len() - 1).saturating_sub(erasures.len()),
+            erasure_count: erasures.len(),
+            error_locator: locator,
+            error_positions,
+            error_magnitudes,
+        })
+    }
+
+    /// Декодирует сообщение со стираниями, как [`Self::decode_with_report`], в буфер `decoded`.
+    /// Промежуточные многочлены хранятся в `buffers`: при декодировании потока блоков одними и теми же
+    /// буферами память выделяется только на первых блоках. Алгоритм Евклида ([`KeySolver::Euclidean`])
+    /// по-прежнему выделяет память на каждом поврежденном блоке.
+    ///
+    /// Предыдущее содержимое `decoded` удаляется. При ошибке содержимое `decoded` и `buffers` не определено.
+    pub fn decode_symbols_into(
+        &self,
+        data: &[T::Symbol],
+        erasures: &[usize],
+        buffers: &mut DecodeBuffers<T::Symbol>,
+        decoded: &mut Vec<T::Symbol>,
+    ) -> Result<(), DecodeError> {
+        decoded.clear();
+        buffers.erasures.clear();
+        match self.layout {
+            Layout::ControlFirst => {
+                decoded.extend_from_slice(data);
+                buffers.erasures.extend_from_slice(erasures);
+            }
+            Layout::ControlLast => {
+                // Разворачиваем в многочлен, позиции вне кодового слова оставляем как есть для сообщения об ошибке
+                let len = data.len();
+                decoded.extend(data.iter().rev());
+                buffers
+                    .erasures
+                    .extend(erasures.iter().map(|&pos| if pos < len { len - 1 - pos } else { pos }));
+            }
+        }
+
+        self.correct_poly(decoded, buffers)?;
+
+        decoded.drain(..self.control_count);
+        if self.layout == Layout::ControlLast {
+            decoded.reverse();
+        }
+        Ok(())
+    }
+
+    /// Исправляет многочлен на месте, позиции - степени `x`. Стирания берутся из `buffers.erasures`,
+    /// синдромы, локатор, позиции и значения ошибок остаются в `buffers`.
+    fn correct_poly(&self, data: &mut [T::Symbol], buffers: &mut DecodeBuffers<T::Symbol>) -> Result<(), DecodeError> {
+        if data.len() > self.gf.order() {
+            return Err(DecodeError::MessageTooLong {
+                len: data.len(),
+                max_len: self.gf.order(),
+            });
+        }
+        self.check_symbols(data)?;
+        if data.len() < self.control_count {
+            return Err(DecodeError::MessageTooShort {
+                len: data.len(),
+                control_count: self.control_count,
+            });
+        }
+
+        let erasures = &mut buffers.erasures;
+        erasures.sort_unstable();
+        erasures.dedup();
+        if let Some(&position) = erasures.iter().find(|&&pos| pos >= data.len()) {
+            return Err(DecodeError::ErasureOutOfBounds {
+                position,
+                len: data.len(),
+            });
+        }
+        if erasures.len() > self.control_count {
+            return Err(DecodeError::TooManyErasures {
+                erasures: erasures.len(),
+                control_count: self.control_count,
+            });
+        }
+
+        buffers.positions.clear();
+        buffers.magnitudes.clear();
+
+        // Если все синдромы равны нулю, то сообщение не повреждено
+        self.calculate_syndromes_into(data, &mut buffers.syndromes);
+
+        if buffers.syndromes.iter().all(|&s| s == T::Symbol::ZERO) {
+            buffers.locator.clear();
+            buffers.locator.push(T::Symbol::ONE);
+            return Ok(());
+        }
+
+        self.solve_key_equation_into(buffers)?;
+        self.find_error_positions_into(&buffers.locator, data.len(), &mut buffers.terms, &mut buffers.positions)?;
+        self.find_error_magnitudes_into(
+            &buffers.evaluator,
+            &buffers.locator,
+            &buffers.positions,
+            &mut buffers.derivative,
+            &mut buffers.magnitudes,
+        );
+
+        // Исправляем ошибки
+        self.correct_errors(data, &buffers.positions, &buffers.magnitudes);
+
+        // Проверяем синдромы после исправления
+        self.calculate_syndromes_into(data, &mut buffers.scratch);
+        if buffers.scratch.iter().any(|&s| s != T::Symbol::ZERO) {
+            return Err(DecodeError::Uncorrectable);
+        }
+
+        Ok(())
     }
 }
 
@@ -322,22 +756,10 @@ where
     /// увеличенный на `x^control_count` многочлен.
     /// 2. Делит полученный полином на порождающий многочлен g(x). Остаток от деления - и есть контрольные символы.
     /// 3. Записывает их в начало полинома.
+    ///
+    /// Для [`Layout::ControlLast`] данные и результат записаны старшим коэффициентом вперед.
     fn encode(&self, data: RefPoly) -> Result<Poly> {
-        if data.len() + self.control_count > 255 {
-            anyhow::bail!("Message too long and cannot be encoded with GF256");
-        }
-
-        // Полином сдвигается на n-k позиций для контрольных символов
-        let mut encoded = self.gf.shift_poly(data, self.control_count);
-
-        // Вычисляем остаток от деления message на gen_poly
-        let remainder = self.gf.mod_poly(&encoded, &self.gen_poly);
-
-        for (i, &n) in remainder.iter().enumerate().take(self.control_count) {
-            encoded[i] = n;
-        }
-
-        Ok(encoded)
+        self.encode_symbols(data)
     }
 
     /// # Шаги декодирования
@@ -352,40 +774,7 @@ where
     /// 9. Сформировать многочлен ошибок E(X) на основе локаторов и значений ошибок и
     /// скорректировать C(x) = C(x) + E(x).
     fn decode(&self, data: RefPoly) -> Result<Poly> {
-        if data.len() > 255 {
-            anyhow::bail!("Message too long and cannot be decoded with GF256");
-        }
-
-        // Если все синдромы равны нулю, то сообщение не повреждено
-        let syndromes = self.calculate_syndromes(data);
-
-        if syndromes.iter().all(|&s| s == 0) {
-            return Ok(data[self.control_count..].to_vec());
-        }
-
-        let error_locator = self.find_error_locator(&syndromes)?;
-        let error_positions = self.find_error_positions(&error_locator, data.len())?;
-        let error_magnitudes = self.find_error_magnitudes(&syndromes, &error_locator, &error_positions);
-
-        // Исправляем ошибки
-        let corrected = self.correct_errors(data, &error_positions, &error_magnitudes);
-
-        // Проверяем синдромы после исправления
-        let syndromes_after = self.calculate_syndromes(&corrected);
-        if syndromes_after.iter().any(|&s| s != 0) {
-            anyhow::bail!(
-                "Could not correct all errors. \n\
-                Original data:\t{data:?}, \n\
-                Corrected data:\t{corrected:?}, \n\
-                Error locator:\t{error_locator:?}, \n\
-                Error positions:\t{error_positions:?}, \n\
-                Error magnitudes:\t{error_magnitudes:?}, \n\
-                Syndromes before:\t{syndromes:?}, \n\
-                Syndromes after:\t{syndromes_after:?}",
-            );
-        }
-
-        Ok(corrected[self.control_count..].to_vec())
+        self.decode_symbols(data)
     }
 }
 
@@ -394,12 +783,17 @@ mod tests {
     use anyhow::Context;
 
     use super::*;
-    use crate::gf::FastGF256;
+    use crate::gf::{FastGF256, GF2m};
 
     mod build_gen_poly;
     mod decode;
     mod encode;
+    mod erasures;
+    mod gf2m;
+    mod known_vectors;
     mod locator;
+    mod report;
+    mod solver;
     mod syndromes;
     mod utils;
 
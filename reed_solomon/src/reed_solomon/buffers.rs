@@ -0,0 +1,39 @@
+/// Промежуточные многочлены декодирования для
+/// [`ReedSolomon::decode_symbols_into`](super::ReedSolomon::decode_symbols_into).
+///
+/// Буферы переиспользуются от блока к блоку, поэтому при декодировании потока решателем
+/// Берлекэмпа-Месси память выделяется только на первых блоках.
+#[derive(Debug, Clone, Default)]
+pub struct DecodeBuffers<S> {
+    /// Синдромы принятого кодового слова.
+    pub(super) syndromes: Vec<S>,
+    /// Позиции стираний в многочлене, отсортированные и без повторов.
+    pub(super) erasures: Vec<usize>,
+    /// Многочлен локаторов ошибок и стираний `C(x)`.
+    pub(super) locator: Vec<S>,
+    /// Предыдущий локатор `B(x)` алгоритма Берлекэмпа-Месси.
+    pub(super) old_locator: Vec<S>,
+    /// Многочлен значений ошибок `W(x)`.
+    pub(super) evaluator: Vec<S>,
+    /// Производная локатора `L'(x)`.
+    pub(super) derivative: Vec<S>,
+    /// Слагаемые локатора в поиске Ченя.
+    pub(super) terms: Vec<S>,
+    pub(super) positions: Vec<usize>,
+    pub(super) magnitudes: Vec<S>,
+    /// Временный многочлен: копия локатора в Берлекэмпе-Месси и синдромы после исправления.
+    pub(super) scratch: Vec<S>,
+}
+
+impl<S: Default> DecodeBuffers<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S> DecodeBuffers<S> {
+    /// Синдромы последнего декодированного кодового слова.
+    pub fn syndromes(&self) -> &[S] {
+        &self.syndromes
+    }
+}
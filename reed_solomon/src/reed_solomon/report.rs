@@ -0,0 +1,101 @@
+use std::fmt;
+
+/// Результат декодирования с подробностями об исправлениях.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeReport<S> {
+    /// Исправленное сообщение без контрольных символов.
+    pub data: Vec<S>,
+    /// Синдромы принятого кодового слова. Все нулевые, если искажений нет.
+    pub syndromes: Vec<S>,
+    /// Общий многочлен локаторов ошибок и стираний, `[1]` если искажений нет.
+    pub error_locator: Vec<S>,
+    /// Позиции в кодовом слове, найденные методом Ченя, включая стертые.
+    pub error_positions: Vec<usize>,
+    /// Значения, вычтенные из символов на позициях `error_positions`.
+    pub error_magnitudes: Vec<S>,
+    /// Количество ошибок в неизвестных позициях.
+    pub error_count: usize,
+    /// Количество различных стертых позиций.
+    pub erasure_count: usize,
+}
+
+impl<S: crate::gf::Symbol> DecodeReport<S> {
+    /// Количество действительно измененных символов: стертый символ мог оказаться верным.
+    pub fn corrected_count(&self) -> usize {
+        self.error_magnitudes.iter().filter(|&&m| m != S::ZERO).count()
+    }
+
+    /// Были ли в кодовом слове искажения.
+    pub fn is_corrupted(&self) -> bool {
+        self.corrected_count() > 0
+    }
+}
+
+/// Причина, по которой сообщение не удалось декодировать.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Кодовое слово длиннее `2^m - 1` символов.
+    MessageTooLong { len: usize, max_len: usize },
+    /// Кодовое слово короче количества контрольных символов.
+    MessageTooShort { len: usize, control_count: usize },
+    /// Символ не принадлежит полю GF(2^m).
+    SymbolOutOfField { symbol: usize, degree: u32 },
+    /// Позиция стирания за пределами кодового слова.
+    ErasureOutOfBounds { position: usize, len: usize },
+    /// Стираний больше, чем контрольных символов.
+    TooManyErasures { erasures: usize, control_count: usize },
+    /// Берлекэмп-Месси нашел локатор степени больше исправляемой: `2v + e > control_count`.
+    TooManyErrors {
+        locator_degree: usize,
+        erasures: usize,
+        control_count: usize,
+    },
+    /// Количество корней локатора среди позиций кодового слова не совпадает с его степенью.
+    LocatorRootMismatch { locator_degree: usize, roots: usize },
+    /// После исправления синдромы остались ненулевыми.
+    Uncorrectable,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::MessageTooLong { len, max_len } => {
+                write!(f, "Message too long and cannot be decoded: {len} > {max_len}")
+            }
+            DecodeError::MessageTooShort { len, control_count } => write!(
+                f,
+                "Message is shorter ({len}) than the number of control characters ({control_count})"
+            ),
+            DecodeError::SymbolOutOfField { symbol, degree } => {
+                write!(f, "Symbol {symbol} does not belong to GF(2^{degree})")
+            }
+            DecodeError::ErasureOutOfBounds { position, len } => {
+                write!(f, "Erasure position {position} is out of bounds (len: {len})")
+            }
+            DecodeError::TooManyErasures {
+                erasures,
+                control_count,
+            } => write!(
+                f,
+                "Too many erasures to correct ({erasures}), control count: {control_count}"
+            ),
+            DecodeError::TooManyErrors {
+                locator_degree,
+                erasures,
+                control_count,
+            } => write!(
+                f,
+                "Too many errors to correct (locator degree: {locator_degree}, erasures: {erasures}, \
+                control count: {control_count}, max correctable errors: {})",
+                (control_count - erasures) / 2
+            ),
+            DecodeError::LocatorRootMismatch { locator_degree, roots } => write!(
+                f,
+                "Error locator of degree {locator_degree} has {roots} roots in the codeword"
+            ),
+            DecodeError::Uncorrectable => f.write_str("Could not correct all errors"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
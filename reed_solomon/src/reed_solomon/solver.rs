@@ -0,0 +1,146 @@
+use super::*;
+
+/// Алгоритм решения ключевого уравнения `L(x)·S(x) = W(x) mod x^(N-k)`: по синдромам `S(x)` находит
+/// многочлен локаторов `L(x)` и многочлен значений ошибок `W(x)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeySolver {
+    /// Алгоритм Берлекэмпа-Месси: находит `L(x)`, а `W(x)` вычисляется умножением на синдромы.
+    #[default]
+    BerlekampMassey,
+
+    /// Алгоритм Сугиямы на основе расширенного алгоритма Евклида: находит `L(x)` и `W(x)` одновременно.
+    Euclidean,
+}
+
+/// Многочлены локаторов `L(x)` и значений ошибок `W(x)`.
+type KeyEquationSolution<S> = (Vec<S>, Vec<S>);
+
+impl<T> ReedSolomon<T>
+where
+    T: GF2mPoly,
+{
+    /// Находит многочлены локаторов и значений ошибок выбранным алгоритмом по синдромам и стираниям
+    /// из `buffers` и записывает их в `buffers.locator` и `buffers.evaluator`.
+    ///
+    /// Берлекэмп-Месси работает в буферах без выделения памяти, алгоритм Евклида выделяет
+    /// промежуточные многочлены на каждом вызове.
+    pub(super) fn solve_key_equation_into(&self, buffers: &mut DecodeBuffers<T::Symbol>) -> Result<(), DecodeError> {
+        match self.solver {
+            KeySolver::BerlekampMassey => {
+                self.find_errata_locator_into(
+                    &buffers.syndromes,
+                    &buffers.erasures,
+                    &mut buffers.locator,
+                    &mut buffers.old_locator,
+                    &mut buffers.scratch,
+                )?;
+                self.find_error_evaluator_into(&buffers.syndromes, &buffers.locator, &mut buffers.evaluator);
+                trim(&mut buffers.evaluator);
+            }
+            KeySolver::Euclidean => {
+                (buffers.locator, buffers.evaluator) =
+                    self.find_euclidean_locator(&buffers.syndromes, &buffers.erasures)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(super) fn solve_key_equation(
+        &self,
+        syndromes: &[T::Symbol],
+        erasures: &[usize],
+    ) -> Result<KeyEquationSolution<T::Symbol>, DecodeError> {
+        let mut buffers = DecodeBuffers {
+            syndromes: syndromes.to_vec(),
+            erasures: erasures.to_vec(),
+            ..Default::default()
+        };
+        self.solve_key_equation_into(&mut buffers)?;
+        Ok((buffers.locator, buffers.evaluator))
+    }
+
+    /// Алгоритм Сугиямы. Ключевое уравнение `L(x)·S(x) + A(x)·x^(N-k) = W(x)` - это соотношение Безу
+    /// для `x^(N-k)` и `S(x)`, поэтому `L(x)` и `W(x)` находятся расширенным алгоритмом Евклида:
+    ///
+    /// - `r₋₁ = x^(N-k)`, `r₀ = S(x)`, `t₋₁ = 0`, `t₀ = 1`;
+    /// - на каждом шаге `rᵢ = rᵢ₋₂ mod rᵢ₋₁`, а `tᵢ = tᵢ₋₂ - qᵢ·tᵢ₋₁`, где `qᵢ` - частное;
+    /// - деление останавливается, когда `2·deg rᵢ < N-k`: тогда `L(x) = tᵢ`, `W(x) = rᵢ` с точностью
+    ///   до множителя, который убирается нормировкой `L(0) = 1`.
+    ///
+    /// Со стираниями вместо `S(x)` берутся модифицированные синдромы `Г(x)·S(x) mod x^(N-k)`, остановка
+    /// происходит при `2·deg rᵢ < N-k+e`, а найденный многочлен умножается на локатор стираний `Г(x)`.
+    fn find_euclidean_locator(
+        &self,
+        syndromes: &[T::Symbol],
+        erasures: &[usize],
+    ) -> Result<KeyEquationSolution<T::Symbol>, DecodeError> {
+        let zero = T::Symbol::ZERO;
+        let erasure_locator = self.find_erasure_locator(erasures);
+        let erasure_count = erasures.len();
+
+        let mut modified = self.gf.mul_poly(&erasure_locator, syndromes);
+        modified.truncate(self.control_count);
+
+        // r₋₁ = x^(N-k)
+        let mut old_remainder = self.gf.shift_poly(&[T::Symbol::ONE], self.control_count);
+        let mut remainder = modified;
+        let mut old_coef = vec![zero];
+        let mut coef = vec![T::Symbol::ONE];
+        trim(&mut remainder);
+
+        while 2 * degree(&remainder) >= self.control_count + erasure_count && !is_zero(&remainder) {
+            // Деление rᵢ₋₂ на rᵢ₋₁ по одному старшему члену частного: rᵢ₋₂ -= c·x^s·rᵢ₋₁, tᵢ₋₂ -= c·x^s·tᵢ₋₁
+            while !is_zero(&old_remainder) && degree(&old_remainder) >= degree(&remainder) {
+                let shift = degree(&old_remainder) - degree(&remainder);
+                let scale = self.gf.div(*old_remainder.last().unwrap(), *remainder.last().unwrap());
+
+                let term = self.gf.shift_poly(&self.gf.scale_poly(&remainder, scale), shift);
+                old_remainder = self.gf.add_poly(&old_remainder, &term);
+                trim(&mut old_remainder);
+
+                let term = self.gf.shift_poly(&self.gf.scale_poly(&coef, scale), shift);
+                old_coef = self.gf.add_poly(&old_coef, &term);
+                trim(&mut old_coef);
+            }
+
+            std::mem::swap(&mut old_remainder, &mut remainder);
+            std::mem::swap(&mut old_coef, &mut coef);
+        }
+
+        // tᵢ(0) = 0 означает, что решения с L(0) = 1 нет
+        let normalizer = coef[0];
+        let error_count = degree(&coef);
+        if normalizer == zero || 2 * error_count + erasure_count > self.control_count {
+            return Err(DecodeError::TooManyErrors {
+                locator_degree: error_count + erasure_count,
+                erasures: erasure_count,
+                control_count: self.control_count,
+            });
+        }
+
+        let scale = self.gf.inverse(normalizer);
+        let mut locator = self.gf.mul_poly(&self.gf.scale_poly(&coef, scale), &erasure_locator);
+        let mut evaluator = self.gf.scale_poly(&remainder, scale);
+        trim(&mut locator);
+        trim(&mut evaluator);
+
+        Ok((locator, evaluator))
+    }
+}
+
+/// Убирает нулевые старшие коэффициенты, оставляя хотя бы один.
+fn trim<S: Symbol>(poly: &mut Vec<S>) {
+    while poly.len() > 1 && *poly.last().unwrap() == S::ZERO {
+        poly.pop();
+    }
+}
+
+/// Степень многочлена без нулевых старших коэффициентов. Для нулевого многочлена - 0.
+fn degree<S: Symbol>(poly: &[S]) -> usize {
+    poly.iter().rposition(|&c| c != S::ZERO).unwrap_or(0)
+}
+
+fn is_zero<S: Symbol>(poly: &[S]) -> bool {
+    poly.iter().all(|&c| c == S::ZERO)
+}
@@ -274,7 +274,7 @@ fn debug_simple_test() {
 
     // Проверим корни порождающего многочлена
     for i in 0..2 {
-        let root = encoder.gf.alpha_pow(i as u8);
+        let root = encoder.gf.alpha_pow(i);
         let value = encoder.gf.eval_poly(&encoder.gen_poly, root);
         println!("Root α^{} = {}, polynomial value = {}", i, root, value);
     }
@@ -317,3 +317,38 @@ fn test_random_stress() {
         }
     }
 }
+
+#[test]
+fn test_lfsr_matches_division() {
+    for control in [0, 1, 2, 7, 32] {
+        for layout in [Layout::ControlFirst, Layout::ControlLast] {
+            let encoder = create_encoder(control).with_layout(layout);
+            let mut buffer = vec![42; 300];
+
+            for len in [0, 1, 10, 100, 255 - control] {
+                let data: Vec<u8> = rand::random_iter().take(len).collect();
+                encoder.encode_symbols_into(&data, &mut buffer).unwrap();
+
+                // Контрольные символы - остаток от деления x^(N-k)·m(x) на g(x)
+                let mut poly = data.clone();
+                if layout == Layout::ControlLast {
+                    poly.reverse();
+                }
+                let shifted = encoder.gf.shift_poly(&poly, control);
+                let mut remainder = encoder.gf.mod_poly(&shifted, &encoder.gen_poly);
+                remainder.resize(control, 0);
+                if layout == Layout::ControlLast {
+                    remainder.reverse();
+                }
+
+                let (data_part, control_part) = match layout {
+                    Layout::ControlFirst => (&buffer[control..], &buffer[..control]),
+                    Layout::ControlLast => (&buffer[..len], &buffer[len..]),
+                };
+                assert_eq!(data_part, data.as_slice(), "control: {control}, len: {len}");
+                assert_eq!(control_part, remainder.as_slice(), "control: {control}, len: {len}");
+                assert_eq!(buffer, encoder.encode(&data).unwrap());
+            }
+        }
+    }
+}
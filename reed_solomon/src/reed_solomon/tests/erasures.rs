@@ -0,0 +1,100 @@
+use super::*;
+
+/// Позиции, в которых `err_encoded` отличается от `encoded`.
+fn changed_positions(encoded: RefPoly, err_encoded: RefPoly) -> Vec<usize> {
+    (0..encoded.len()).filter(|&i| encoded[i] != err_encoded[i]).collect()
+}
+
+/// Вносит `erasures + errors` искажений и сообщает декодеру позиции первых `erasures` из них.
+fn decode_errata_stress_test_helper(control_count: usize, erasures: usize, errors: usize) {
+    let mut cf = StressTestConfig::new_n_error_config(erasures + errors);
+    cf.min_control_count = control_count;
+    cf.max_control_count = control_count;
+    cf.min_data_len = (erasures + errors).saturating_sub(control_count).max(1);
+
+    stress_test_common(cf, |context, encoder, message, encoded, err_encoded| {
+        let erasure_positions = changed_positions(encoded, err_encoded)
+            .into_iter()
+            .take(erasures)
+            .collect::<Vec<_>>();
+        *context += &format!("\nErasures:\t{erasure_positions:?}");
+
+        let decoded = encoder
+            .decode_with_erasures(err_encoded, &erasure_positions)
+            .with_context(|| context.clone())
+            .unwrap();
+        *context += &format!("\nDecoded: {decoded:?}");
+
+        assert_eq!(message, decoded, "{}", context);
+    });
+}
+
+#[test]
+fn decode_only_erasures() {
+    // Стираний столько же, сколько контрольных символов, - вдвое больше, чем ошибок исправляет `decode`
+    decode_errata_stress_test_helper(10, 10, 0);
+    decode_errata_stress_test_helper(7, 7, 0);
+    decode_errata_stress_test_helper(1, 1, 0);
+}
+
+#[test]
+fn decode_errors_and_erasures() {
+    decode_errata_stress_test_helper(10, 6, 2);
+    decode_errata_stress_test_helper(10, 1, 4);
+    decode_errata_stress_test_helper(9, 3, 3);
+    decode_errata_stress_test_helper(10, 0, 5);
+}
+
+#[test]
+#[ignore]
+/// Большой тест ошибок и стираний при 2·errors + erasures = control_count
+fn decode_errata_random_stress() {
+    for errors in 0..=25 {
+        decode_errata_stress_test_helper(60, 60 - 2 * errors, errors);
+    }
+}
+
+#[test]
+fn decode_erasures_of_correct_symbols() {
+    // Стертый символ может оказаться верным: его значение ошибки равно нулю
+    let encoder = create_encoder(6);
+    let message = vec![12, 34, 56, 78, 90];
+    let encoded = encoder.encode(&message).unwrap();
+
+    let mut corrupted = encoded.clone();
+    corrupted[1] ^= 0x55;
+    corrupted[8] ^= 0x0F;
+
+    assert_eq!(
+        message,
+        encoder.decode_with_erasures(&corrupted, &[1, 8, 3, 5]).unwrap()
+    );
+    assert_eq!(message, encoder.decode_with_erasures(&encoded, &[0, 2, 4]).unwrap());
+    // Повторяющиеся позиции считаются одним стиранием
+    assert_eq!(
+        message,
+        encoder.decode_with_erasures(&corrupted, &[1, 8, 1, 8]).unwrap()
+    );
+}
+
+#[test]
+fn decode_too_many_errata() {
+    let encoder = create_encoder(4);
+    let message = vec![1, 2, 3, 4, 5, 6];
+    let encoded = encoder.encode(&message).unwrap();
+
+    let mut corrupted = encoded.clone();
+    for i in [0, 3, 6, 9] {
+        corrupted[i] ^= 0xAA;
+    }
+
+    // 4 стирания исправляются, а 3 стирания и одна неизвестная ошибка уже нет: 2·1 + 3 > 4
+    assert_eq!(
+        message,
+        encoder.decode_with_erasures(&corrupted, &[0, 3, 6, 9]).unwrap()
+    );
+    assert!(encoder.decode_with_erasures(&corrupted, &[0, 3, 6]).is_err());
+
+    assert!(encoder.decode_with_erasures(&encoded, &[0, 1, 2, 3, 4]).is_err());
+    assert!(encoder.decode_with_erasures(&encoded, &[encoded.len()]).is_err());
+}
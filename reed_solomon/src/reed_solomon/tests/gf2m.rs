@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::gf::{FastGF, SimpleGF};
+
+/// Искажает `errors` символов на случайных позициях значениями из поля размера `size`.
+fn corrupt<S: Symbol>(encoded: &[S], errors: usize, size: usize) -> Vec<S> {
+    let mut corrupted = encoded.to_vec();
+    for pos in rand::seq::index::sample(&mut rand::rng(), encoded.len(), errors) {
+        corrupted[pos] = corrupted[pos] ^ S::from_index(rand::random_range(1..size));
+    }
+    corrupted
+}
+
+#[test]
+fn test_gf16_rs_15_11() {
+    // RS(15, 11) над GF(2^4) исправляет 2 ошибки
+    let rs = ReedSolomon::new(4, FastGF::<u8>::with_degree(4).unwrap());
+
+    for _ in 0..200 {
+        let message = (0..11).map(|_| rand::random_range(0..16)).collect::<Vec<u8>>();
+        let encoded = rs.encode_symbols(&message).unwrap();
+        assert_eq!(encoded.len(), 15);
+        assert!(rs.calculate_syndromes(&encoded).iter().all(|&s| s == 0));
+
+        let corrupted = corrupt(&encoded, 2, 16);
+        assert_eq!(message, rs.decode_symbols(&corrupted).unwrap());
+    }
+
+    // Длина кодового слова не больше 15, символы меньше 16
+    assert!(rs.encode_symbols(&[0; 12]).is_err());
+    assert!(rs.encode_symbols(&[16]).is_err());
+    assert!(rs.decode_symbols(&[0, 0, 0, 0, 16]).is_err());
+}
+
+#[test]
+fn test_long_codewords() {
+    // Кодовые слова длиннее 255 символов
+    for (degree, control_count, data_len) in [(10, 16, 1000), (12, 32, 4000), (16, 20, 3000)] {
+        let rs = ReedSolomon::new(control_count, FastGF::<u16>::with_degree(degree).unwrap()).with_first_root(1);
+        let size = 1 << degree;
+
+        let message = (0..data_len)
+            .map(|_| rand::random_range(0..size) as u16)
+            .collect::<Vec<_>>();
+        let encoded = rs.encode_symbols(&message).unwrap();
+
+        let corrupted = corrupt(&encoded, control_count / 2, size);
+        assert_eq!(message, rs.decode_symbols(&corrupted).unwrap(), "GF(2^{degree})");
+
+        // Ошибки и стирания: 2·3 + (control_count - 6) = control_count
+        let corrupted = corrupt(&encoded, control_count - 3, size);
+        let erasures = (0..corrupted.len())
+            .filter(|&i| corrupted[i] != encoded[i])
+            .take(control_count - 6)
+            .collect::<Vec<_>>();
+        assert_eq!(message, rs.decode_with_erasures(&corrupted, &erasures).unwrap());
+    }
+}
+
+#[test]
+fn test_simple_and_fast_fields_agree() {
+    let fast = ReedSolomon::new(6, FastGF::<u16>::with_degree(9).unwrap());
+    let simple = ReedSolomon::new(6, SimpleGF::<u16>::with_degree(9).unwrap());
+
+    let message = (0..300).map(|i| (i * 37 % 512) as u16).collect::<Vec<_>>();
+    let encoded = fast.encode_symbols(&message).unwrap();
+    assert_eq!(encoded, simple.encode_symbols(&message).unwrap());
+
+    let corrupted = corrupt(&encoded, 3, 512);
+    assert_eq!(message, simple.decode_symbols(&corrupted).unwrap());
+}
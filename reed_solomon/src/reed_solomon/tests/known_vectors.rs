@@ -0,0 +1,152 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::gf::{CCSDS_POLY, DATA_MATRIX_POLY};
+
+/// Кодирует сообщение, записанное в стандартах старшим коэффициентом вперед, и возвращает
+/// контрольные символы в том же порядке.
+fn control_symbols<T: GF256Poly>(rs: &ReedSolomon<T>, data: &[u8]) -> Vec<u8> {
+    let message = data.iter().rev().copied().collect::<Vec<_>>();
+    let encoded = rs.encode(&message).unwrap();
+    encoded[..rs.control_count].iter().rev().copied().collect()
+}
+
+/// Вносит `control_count / 2` ошибок и проверяет, что сообщение восстанавливается.
+fn check_decoding<T: GF256Poly>(rs: &ReedSolomon<T>, message: &[u8]) {
+    let mut encoded = rs.encode(message).unwrap();
+    for i in 0..rs.control_count / 2 {
+        encoded[3 * i] ^= 0x5A;
+    }
+    assert_eq!(message, rs.decode(&encoded).unwrap());
+}
+
+#[test]
+fn test_qr_code_hello_world() {
+    // Версия 1-M, ISO/IEC 18004: "HELLO WORLD"
+    let rs = create_encoder(10);
+    let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17];
+
+    assert_eq!(
+        control_symbols(&rs, &data),
+        vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+    );
+    check_decoding(&rs, &data);
+}
+
+#[test]
+fn test_data_matrix() {
+    // ISO/IEC 16022, символ 10x10: "123456"
+    let gf = FastGF256::build(DATA_MATRIX_POLY, 2).unwrap();
+    let rs = ReedSolomon::new(5, gf).with_first_root(1);
+
+    assert_eq!(control_symbols(&rs, &[142, 164, 186]), vec![114, 25, 5, 88, 102]);
+    check_decoding(&rs, &[142, 164, 186]);
+}
+
+#[test]
+fn test_ccsds_generator_poly() {
+    // Порождающий многочлен CCSDS (255,223): корни a^(11j), j = 112..143. Коэффициенты записаны
+    // логарифмами по основанию a = x (conventional basis)
+    let expected_logs = [
+        0, 249, 59, 66, 4, 43, 126, 251, 97, 30, 3, 213, 50, 66, 170, 5, 24, 5, 170, 66, 50, 213, 3, 30, 97, 251, 126,
+        43, 4, 66, 59, 249, 0,
+    ];
+
+    let conventional = FastGF256::build(CCSDS_POLY, 2).unwrap();
+    let gf = FastGF256::build(CCSDS_POLY, conventional.alpha_pow(11)).unwrap();
+    let rs = ReedSolomon::new(32, gf).with_first_root(112);
+
+    let expected = expected_logs
+        .iter()
+        .map(|&log| conventional.alpha_pow(log))
+        .collect::<Vec<_>>();
+    assert_eq!(rs.gen_poly, expected);
+
+    let message = (0..223).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>();
+    check_decoding(&rs, &message);
+}
+
+#[test]
+fn test_first_root_errata_stress() {
+    for first_root in [1, 2, 120, 254] {
+        let rs = create_encoder(8).with_first_root(first_root);
+        assert_eq!(rs.first_root(), first_root);
+
+        for len in 1..40 {
+            let message = (0..len).map(|_| rand::random()).collect::<Vec<u8>>();
+            let mut encoded = rs.encode(&message).unwrap();
+            check_syndromes_zero(&rs, &encoded);
+
+            let positions = rand::seq::index::sample(&mut rand::rng(), encoded.len(), 6).into_vec();
+            for &pos in &positions {
+                encoded[pos] ^= rand::random_range(1..=255);
+            }
+
+            // 2 ошибки и 4 стирания: 2·2 + 4 = 8
+            assert_eq!(message, rs.decode_with_erasures(&encoded, &positions[..4]).unwrap());
+        }
+    }
+}
+
+fn check_syndromes_zero<T: GF256Poly>(rs: &ReedSolomon<T>, encoded: RefPoly) {
+    assert!(rs.calculate_syndromes(encoded).iter().all(|&s| s == 0));
+}
+
+#[test]
+fn test_control_last_qr_vectors() {
+    // Версия 1-M "HELLO WORLD" и блоки версии 5-Q: данные, затем байты коррекции
+    let cases: [(&[u8], &[u8]); 3] = [
+        (
+            &[32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17],
+            &[196, 35, 39, 119, 235, 215, 231, 226, 93, 23],
+        ),
+        (
+            &[67, 85, 70, 134, 87, 38, 85, 194, 119, 50, 6, 18, 6, 103, 38],
+            &[
+                213, 199, 11, 45, 115, 247, 241, 223, 229, 248, 154, 117, 154, 111, 86, 161, 111, 39,
+            ],
+        ),
+        (
+            &[246, 246, 66, 7, 118, 134, 242, 7, 38, 86, 22, 198, 199, 146, 6],
+            &[
+                87, 204, 96, 60, 202, 182, 124, 157, 200, 134, 27, 129, 209, 17, 163, 163, 120, 133,
+            ],
+        ),
+    ];
+
+    for (data, ec) in cases {
+        let rs = create_encoder(ec.len()).with_layout(Layout::ControlLast);
+        assert_eq!(rs.layout(), Layout::ControlLast);
+
+        let encoded = rs.encode(data).unwrap();
+        assert_eq!(&encoded[..data.len()], data);
+        assert_eq!(&encoded[data.len()..], ec);
+        assert_eq!(control_symbols(&create_encoder(ec.len()), data), ec);
+
+        check_decoding(&rs, data);
+    }
+}
+
+#[test]
+fn test_control_last_positions() {
+    let rs = create_encoder(6).with_layout(Layout::ControlLast);
+    let message = (1..=10).collect::<Vec<u8>>();
+    let encoded = rs.encode(&message).unwrap();
+
+    let mut corrupted = encoded.clone();
+    corrupted[0] ^= 0x42;
+    corrupted[4] ^= 0x13;
+    corrupted[15] ^= 0x01;
+
+    // Позиции ошибок и стираний - индексы в кодовом слове, как его передали
+    let report = rs.decode_with_report(&corrupted, &[4]).unwrap();
+    assert_eq!(report.data, message);
+    assert_eq!(report.error_positions, vec![0, 4, 15]);
+    assert_eq!(report.error_magnitudes, vec![0x42, 0x13, 0x01]);
+    assert_eq!((report.error_count, report.erasure_count), (2, 1));
+
+    assert_eq!(
+        rs.decode_with_report(&corrupted, &[16]),
+        Err(DecodeError::ErasureOutOfBounds { position: 16, len: 16 })
+    );
+}
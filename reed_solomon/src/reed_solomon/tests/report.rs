@@ -0,0 +1,173 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+
+#[test]
+fn report_without_errors() {
+    let encoder = create_encoder(6);
+    let message = vec![10, 20, 30, 40];
+    let encoded = encoder.encode(&message).unwrap();
+
+    let report = encoder.decode_with_report(&encoded, &[]).unwrap();
+    assert_eq!(report.data, message);
+    assert_eq!(report.syndromes, vec![0; 6]);
+    assert_eq!(report.error_locator, vec![1]);
+    assert_eq!(report.error_count, 0);
+    assert!(!report.is_corrupted());
+}
+
+#[test]
+fn report_errors_and_erasures() {
+    let encoder = create_encoder(10);
+    let message = (0..20).collect::<Vec<u8>>();
+    let encoded = encoder.encode(&message).unwrap();
+
+    let mut corrupted = encoded.clone();
+    corrupted[3] ^= 0x11;
+    corrupted[17] ^= 0x80;
+    corrupted[25] ^= 0x01;
+
+    let report = encoder.decode_with_report(&corrupted, &[]).unwrap();
+    assert_eq!(report.data, message);
+    assert_eq!(report.error_positions, vec![3, 17, 25]);
+    assert_eq!(report.error_magnitudes, vec![0x11, 0x80, 0x01]);
+    assert_eq!(report.error_locator.len(), 4);
+    assert_eq!((report.error_count, report.erasure_count), (3, 0));
+    assert_eq!(report.corrected_count(), 3);
+
+    // Стертая позиция 5 не искажена: значение ошибки в ней нулевое
+    let report = encoder.decode_with_report(&corrupted, &[17, 5]).unwrap();
+    assert_eq!(report.data, message);
+    assert_eq!(report.error_positions, vec![3, 5, 17, 25]);
+    assert_eq!((report.error_count, report.erasure_count), (2, 2));
+    assert_eq!(report.corrected_count(), 3);
+}
+
+#[test]
+fn decode_into_reuses_buffers() {
+    let encoder = create_encoder(10);
+    let message = (0..20).collect::<Vec<u8>>();
+    let encoded = encoder.encode(&message).unwrap();
+
+    let mut buffers = DecodeBuffers::new();
+    let mut decoded = Vec::new();
+    for positions in [vec![3, 17, 25], vec![], vec![0, 29], vec![1, 2, 3, 4, 5]] {
+        let mut corrupted = encoded.clone();
+        for &pos in &positions {
+            corrupted[pos] ^= 0x5A;
+        }
+
+        encoder
+            .decode_symbols_into(&corrupted, &[], &mut buffers, &mut decoded)
+            .unwrap();
+        assert_eq!(decoded, message, "positions: {positions:?}");
+        assert_eq!(buffers.syndromes(), encoder.calculate_syndromes(&corrupted));
+        assert_eq!(buffers.positions, positions);
+
+        let report = encoder.decode_with_report(&corrupted, &[]).unwrap();
+        assert_eq!(buffers.locator, report.error_locator);
+    }
+
+    // Стирания вместе с ошибкой и неисправимое слово после удачного декодирования
+    let mut corrupted = encoded.clone();
+    corrupted[7] ^= 0x01;
+    encoder
+        .decode_symbols_into(&corrupted, &[0, 1, 2], &mut buffers, &mut decoded)
+        .unwrap();
+    assert_eq!(decoded, message);
+
+    for i in 0..6 {
+        corrupted[i * 5] ^= 0xAA;
+    }
+    assert!(
+        encoder
+            .decode_symbols_into(&corrupted, &[], &mut buffers, &mut decoded)
+            .is_err()
+    );
+}
+
+#[test]
+fn report_error_count_stress() {
+    for n in 1..=5 {
+        let cf = StressTestConfig::new_n_error_config(n);
+
+        stress_test_common(cf, |context, encoder, message, encoded, err_encoded| {
+            let report = encoder
+                .decode_with_report(err_encoded, &[])
+                .with_context(|| context.clone())
+                .unwrap();
+
+            let changed = (0..encoded.len())
+                .filter(|&i| encoded[i] != err_encoded[i])
+                .collect::<Vec<_>>();
+            assert_eq!(message, report.data, "{context}");
+            assert_eq!(changed, report.error_positions, "{context}");
+            assert_eq!(report.error_count, changed.len(), "{context}");
+        });
+    }
+}
+
+#[test]
+fn report_typed_errors() {
+    let encoder = create_encoder(4);
+    let encoded = encoder.encode(&[1, 2, 3, 4, 5, 6]).unwrap();
+
+    assert_eq!(
+        encoder.decode_with_report(&[0; 256], &[]),
+        Err(DecodeError::MessageTooLong { len: 256, max_len: 255 })
+    );
+    assert_eq!(
+        encoder.decode_with_report(&[0; 3], &[]),
+        Err(DecodeError::MessageTooShort {
+            len: 3,
+            control_count: 4
+        })
+    );
+    assert_eq!(
+        encoder.decode_with_report(&encoded, &[10]),
+        Err(DecodeError::ErasureOutOfBounds { position: 10, len: 10 })
+    );
+    assert_eq!(
+        encoder.decode_with_report(&encoded, &[0, 1, 2, 3, 4]),
+        Err(DecodeError::TooManyErasures {
+            erasures: 5,
+            control_count: 4
+        })
+    );
+
+    // 3 стирания и ошибка в неизвестной позиции: 2·1 + 3 > 4
+    let mut corrupted = encoded.clone();
+    for i in [0, 3, 6, 9] {
+        corrupted[i] ^= 0xAA;
+    }
+    assert!(matches!(
+        encoder.decode_with_report(&corrupted, &[0, 3, 6]),
+        Err(DecodeError::TooManyErrors { .. } | DecodeError::LocatorRootMismatch { .. })
+    ));
+
+    // Ошибка доступна и через `Coder::decode`
+    let error = encoder.decode(&corrupted).unwrap_err();
+    assert!(error.downcast_ref::<DecodeError>().is_some(), "{error}");
+
+    let gf16 = ReedSolomon::new(4, crate::gf::FastGF::<u8>::with_degree(4).unwrap());
+    assert_eq!(
+        gf16.decode_with_report(&[0, 0, 0, 0, 16], &[]),
+        Err(DecodeError::SymbolOutOfField { symbol: 16, degree: 4 })
+    );
+}
+
+#[test]
+fn report_failure_kinds_stress() {
+    // При ошибках сверх исправляемых декодер либо отказывает с типизированной ошибкой, либо
+    // находит другое кодовое слово, но не паникует
+    let mut cf = StressTestConfig::new_n_error_config(6);
+    cf.min_control_count = 2;
+    cf.max_control_count = 10;
+    cf.min_data_len = 6;
+
+    stress_test_common(cf, |context, encoder, _message, _encoded, err_encoded| {
+        if let Ok(report) = encoder.decode_with_report(err_encoded, &[]) {
+            assert!(2 * report.error_count <= encoder.control_count(), "{context}");
+        }
+    });
+}
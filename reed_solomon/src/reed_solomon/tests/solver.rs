@@ -0,0 +1,99 @@
+use pretty_assertions::assert_eq;
+
+use super::*;
+use crate::gf::FastGF;
+
+/// Сравнивает решения ключевого уравнения и результаты декодирования обоими алгоритмами.
+fn check_solvers_agree(control_count: usize, errors: usize, erasures: usize) {
+    let mut cf = StressTestConfig::new_n_error_config(errors + erasures);
+    cf.min_control_count = control_count;
+    cf.max_control_count = control_count;
+    cf.min_data_len = (errors + erasures).saturating_sub(control_count).max(1);
+
+    stress_test_common(cf, |context, encoder, message, encoded, err_encoded| {
+        let euclidean = create_encoder(control_count).with_solver(KeySolver::Euclidean);
+        assert_eq!(euclidean.solver(), KeySolver::Euclidean);
+
+        let erasure_positions = (0..encoded.len())
+            .filter(|&i| encoded[i] != err_encoded[i])
+            .take(erasures)
+            .collect::<Vec<_>>();
+        let syndromes = encoder.calculate_syndromes(err_encoded);
+
+        let bm = encoder
+            .solve_key_equation(&syndromes, &erasure_positions)
+            .with_context(|| context.clone())
+            .unwrap();
+        let sugiyama = euclidean
+            .solve_key_equation(&syndromes, &erasure_positions)
+            .with_context(|| context.clone())
+            .unwrap();
+        assert_eq!(bm, sugiyama, "{context}");
+
+        let report = euclidean
+            .decode_with_report(err_encoded, &erasure_positions)
+            .with_context(|| context.clone())
+            .unwrap();
+        assert_eq!(
+            report,
+            encoder.decode_with_report(err_encoded, &erasure_positions).unwrap()
+        );
+        assert_eq!(message, report.data, "{context}");
+    });
+}
+
+#[test]
+fn solvers_agree_on_errors() {
+    for errors in 0..=5 {
+        check_solvers_agree(10, errors, 0);
+    }
+    check_solvers_agree(1, 0, 0);
+    check_solvers_agree(7, 3, 0);
+}
+
+#[test]
+fn solvers_agree_on_errors_and_erasures() {
+    check_solvers_agree(10, 0, 10);
+    check_solvers_agree(10, 2, 6);
+    check_solvers_agree(9, 3, 3);
+    check_solvers_agree(8, 1, 5);
+}
+
+#[test]
+fn euclidean_decoder_in_gf2m() {
+    let rs = ReedSolomon::new(12, FastGF::<u16>::with_degree(11).unwrap())
+        .with_first_root(3)
+        .with_solver(KeySolver::Euclidean);
+
+    let message = (0..1000).map(|i| (i * 13 % 2048) as u16).collect::<Vec<_>>();
+    let mut encoded = rs.encode_symbols(&message).unwrap();
+    for pos in [0, 100, 500, 900, 1011] {
+        encoded[pos] ^= 0x3FF;
+    }
+
+    assert_eq!(message, rs.decode_with_erasures(&encoded, &[100, 900]).unwrap());
+}
+
+#[test]
+fn euclidean_too_many_errors() {
+    let rs = create_encoder(4).with_solver(KeySolver::Euclidean);
+    let encoded = rs.encode(&[1, 2, 3, 4, 5, 6]).unwrap();
+
+    let mut corrupted = encoded.clone();
+    for i in [0, 3, 6] {
+        corrupted[i] ^= 0xAA;
+    }
+    assert!(rs.decode(&corrupted).is_err());
+    // 2 стирания и ошибка в неизвестной позиции: 2·1 + 2 = 4
+    assert_eq!(
+        rs.decode_with_erasures(&corrupted, &[0, 3]).unwrap(),
+        vec![1, 2, 3, 4, 5, 6]
+    );
+
+    corrupted[9] ^= 0xAA;
+    assert!(rs.decode_with_erasures(&corrupted, &[0, 3]).is_err());
+    assert_eq!(
+        rs.decode_with_erasures(&corrupted, &[0, 3, 6, 9]).unwrap(),
+        vec![1, 2, 3, 4, 5, 6]
+    );
+}
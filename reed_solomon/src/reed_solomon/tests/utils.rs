@@ -182,7 +182,7 @@ pub fn check_syndromes(encoder: &ReedSolomon<FastGF256>, encoded: RefPoly) -> Re
             encoder.gen_poly,
             (0..encoder.control_count)
                 .into_iter()
-                .map(|i| encoder.gf.alpha_pow(i as u8))
+                .map(|i| encoder.gf.alpha_pow(i))
                 .collect::<Vec<_>>()
         )
     }
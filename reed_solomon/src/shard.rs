@@ -0,0 +1,409 @@
+//! Кодирование стираний для хранения данных в шардах, как в RAID-6 и объектных хранилищах.
+//!
+//! Данные делятся на `k` шардов одинаковой длины, к ним добавляются `m` шардов четности. Байт `i` шарда
+//! четности `r` - линейная комбинация байтов `i` всех шардов данных с коэффициентами строки `r` матрицы
+//! кодирования. Матрица кодирования `[E; P]` систематическая (сверху единичная `E`), а любые `k` ее строк
+//! образуют обратимую матрицу, поэтому данные восстанавливаются по любым `k` сохранившимся шардам.
+
+use anyhow::Result;
+
+use crate::gf::{FastGF256, GF256};
+
+/// Способ построения матрицы четности `P` размера `m × k`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShardMatrix {
+    /// Матрица Коши `P[i][j] = 1 / (xi + yj)` с `xi = k + i`, `yj = j`. Любая ее квадратная подматрица
+    /// обратима, поэтому `[E; P]` подходит без дополнительных преобразований.
+    #[default]
+    Cauchy,
+
+    /// Матрица Вандермонда `V[r][c] = r^c` размера `(k + m) × k`, приведенная к систематическому виду
+    /// умножением на обратную к верхнему квадрату: `[E; P] = V · V[0..k]^(-1)`.
+    Vandermonde,
+}
+
+/// Кодировщик шардов над полем GF(256).
+pub struct ShardCoder<T: GF256 = FastGF256> {
+    data_shards: usize,
+    parity_shards: usize,
+    matrix: ShardMatrix,
+    gf: T,
+    /// Строки матрицы четности, по `data_shards` коэффициентов.
+    parity_rows: Vec<Vec<u8>>,
+}
+
+impl<T: GF256> ShardCoder<T> {
+    /// Кодировщик `data_shards` шардов данных и `parity_shards` шардов четности с матрицей Коши.
+    ///
+    /// Возвращает ошибку, если шардов данных нет или общее количество шардов больше размера поля:
+    /// для матрицы нужны `k + m` различных элементов.
+    pub fn build(data_shards: usize, parity_shards: usize, gf: T) -> Result<Self> {
+        anyhow::ensure!(data_shards > 0, "Number of data shards must be positive");
+        anyhow::ensure!(
+            data_shards + parity_shards <= gf.size(),
+            "Total number of shards must not exceed {}, actual: {data_shards} + {parity_shards}",
+            gf.size()
+        );
+
+        let mut coder = Self {
+            data_shards,
+            parity_shards,
+            matrix: ShardMatrix::default(),
+            gf,
+            parity_rows: Vec::new(),
+        };
+        coder.parity_rows = coder.build_parity_rows();
+        Ok(coder)
+    }
+
+    /// Задает способ построения матрицы четности. По умолчанию [`ShardMatrix::Cauchy`].
+    pub fn with_matrix(mut self, matrix: ShardMatrix) -> Self {
+        self.matrix = matrix;
+        self.parity_rows = self.build_parity_rows();
+        self
+    }
+
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    pub fn matrix(&self) -> ShardMatrix {
+        self.matrix
+    }
+
+    /// Длина шарда для данных длины `data_len`: последний шард данных дополняется нулями.
+    pub fn shard_len(&self, data_len: usize) -> usize {
+        data_len.div_ceil(self.data_shards)
+    }
+
+    fn build_parity_rows(&self) -> Vec<Vec<u8>> {
+        let (k, m) = (self.data_shards, self.parity_shards);
+
+        match self.matrix {
+            ShardMatrix::Cauchy => (0..m)
+                .map(|i| {
+                    (0..k)
+                        .map(|j| self.gf.inverse(self.gf.add((k + i) as u8, j as u8)))
+                        .collect()
+                })
+                .collect(),
+            ShardMatrix::Vandermonde => {
+                let vandermonde = (0..k + m)
+                    .map(|r| (0..k).map(|c| self.gf.pow(r as u8, c)).collect::<Vec<_>>())
+                    .collect::<Vec<_>>();
+                let top_inverse = self
+                    .invert(&vandermonde[..k])
+                    .expect("Vandermonde matrix with distinct points is invertible");
+
+                vandermonde[k..]
+                    .iter()
+                    .map(|row| self.mul_row(row, &top_inverse))
+                    .collect()
+            }
+        }
+    }
+
+    /// Строка матрицы кодирования для шарда `index`.
+    fn encoding_row(&self, index: usize) -> Vec<u8> {
+        if index < self.data_shards {
+            let mut row = vec![0; self.data_shards];
+            row[index] = 1;
+            row
+        } else {
+            self.parity_rows[index - self.data_shards].clone()
+        }
+    }
+
+    /// Умножает вектор-строку на матрицу.
+    fn mul_row(&self, row: &[u8], matrix: &[Vec<u8>]) -> Vec<u8> {
+        (0..matrix[0].len())
+            .map(|c| {
+                row.iter().zip(matrix).fold(0, |acc, (&a, matrix_row)| {
+                    self.gf.add(acc, self.gf.mul(a, matrix_row[c]))
+                })
+            })
+            .collect()
+    }
+
+    /// Обращает квадратную матрицу методом Гаусса-Жордана. Возвращает `None` для вырожденной матрицы.
+    fn invert(&self, matrix: &[Vec<u8>]) -> Option<Vec<Vec<u8>>> {
+        let n = matrix.len();
+        // Расширенная матрица [A | E]
+        let mut work = matrix
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut extended = row.clone();
+                extended.resize(2 * n, 0);
+                extended[n + i] = 1;
+                extended
+            })
+            .collect::<Vec<_>>();
+
+        for col in 0..n {
+            let pivot = (col..n).find(|&r| work[r][col] != 0)?;
+            work.swap(col, pivot);
+
+            let scale = self.gf.inverse(work[col][col]);
+            for value in work[col].iter_mut() {
+                *value = self.gf.mul(*value, scale);
+            }
+
+            let pivot_row = work[col].clone();
+            for (r, row) in work.iter_mut().enumerate() {
+                let factor = row[col];
+                if r != col && factor != 0 {
+                    mul_add(&self.gf, factor, &pivot_row, row);
+                }
+            }
+        }
+
+        Some(work.into_iter().map(|row| row[n..].to_vec()).collect())
+    }
+
+    /// Делит данные на `k` шардов данных, дополняя последний нулями, и вычисляет `m` шардов четности.
+    /// Исходную длину нужно сохранить отдельно, чтобы убрать дополнение в [`Self::join`].
+    pub fn split(&self, data: &[u8]) -> Vec<Vec<u8>> {
+        let shard_len = self.shard_len(data.len());
+        let mut shards = (0..self.data_shards)
+            .map(|i| {
+                let start = (i * shard_len).min(data.len());
+                let mut shard = data[start..(start + shard_len).min(data.len())].to_vec();
+                shard.resize(shard_len, 0);
+                shard
+            })
+            .collect::<Vec<_>>();
+
+        let parity = self
+            .parity_rows
+            .iter()
+            .map(|row| self.combine(row, &shards))
+            .collect::<Vec<_>>();
+        shards.extend(parity);
+        shards
+    }
+
+    /// Линейная комбинация шардов с коэффициентами `row`.
+    fn combine(&self, row: &[u8], shards: &[Vec<u8>]) -> Vec<u8> {
+        let mut result = vec![0; shards.first().map_or(0, Vec::len)];
+        for (&coef, shard) in row.iter().zip(shards) {
+            mul_add(&self.gf, coef, shard, &mut result);
+        }
+        result
+    }
+
+    /// Восстанавливает недостающие шарды (`None`) по любым `k` сохранившимся. Шарды передаются в порядке
+    /// номеров: сначала `k` шардов данных, затем `m` шардов четности.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<()> {
+        anyhow::ensure!(
+            shards.len() == self.total_shards(),
+            "Expected {} shards, actual: {}",
+            self.total_shards(),
+            shards.len()
+        );
+
+        let present = (0..shards.len())
+            .filter(|&i| shards[i].is_some())
+            .take(self.data_shards)
+            .collect::<Vec<_>>();
+        anyhow::ensure!(
+            present.len() == self.data_shards,
+            "At least {} shards are required for reconstruction, actual: {}",
+            self.data_shards,
+            present.len()
+        );
+
+        let shard_len = shards[present[0]].as_ref().map_or(0, Vec::len);
+        anyhow::ensure!(
+            shards.iter().flatten().all(|shard| shard.len() == shard_len),
+            "Shards have different lengths"
+        );
+
+        if shards.iter().all(Option::is_some) {
+            return Ok(());
+        }
+
+        // Строки матрицы кодирования для сохранившихся шардов: shard[present[r]] = rows[r] · data
+        let rows = present.iter().map(|&i| self.encoding_row(i)).collect::<Vec<_>>();
+        let decoding = self
+            .invert(&rows)
+            .ok_or_else(|| anyhow::anyhow!("Encoding submatrix for shards {present:?} is singular"))?;
+
+        let available = present
+            .iter()
+            .map(|&i| shards[i].clone().unwrap_or_default())
+            .collect::<Vec<_>>();
+        let data = (0..self.data_shards)
+            .map(|j| match &shards[j] {
+                Some(shard) => shard.clone(),
+                None => self.combine(&decoding[j], &available),
+            })
+            .collect::<Vec<_>>();
+
+        for (i, shard) in shards.iter_mut().enumerate() {
+            if shard.is_none() {
+                *shard = Some(if i < self.data_shards {
+                    data[i].clone()
+                } else {
+                    self.combine(&self.parity_rows[i - self.data_shards], &data)
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Восстанавливает данные длины `len` по шардам, как [`Self::reconstruct`], и объединяет шарды данных.
+    pub fn join(&self, mut shards: Vec<Option<Vec<u8>>>, len: usize) -> Result<Vec<u8>> {
+        self.reconstruct(&mut shards)?;
+
+        let mut data = shards
+            .into_iter()
+            .take(self.data_shards)
+            .flatten()
+            .flatten()
+            .collect::<Vec<_>>();
+        anyhow::ensure!(
+            len <= data.len(),
+            "Data length {len} exceeds total length of data shards {}",
+            data.len()
+        );
+        data.truncate(len);
+        Ok(data)
+    }
+}
+
+/// `dst += coef · src` поэлементно.
+fn mul_add<T: GF256>(gf: &T, coef: u8, src: &[u8], dst: &mut [u8]) {
+    if coef == 0 {
+        return;
+    }
+    for (d, &s) in dst.iter_mut().zip(src) {
+        *d = gf.add(*d, gf.mul(coef, s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::gf::SimpleGF256;
+
+    fn random_data(len: usize) -> Vec<u8> {
+        (0..len).map(|_| rand::random()).collect()
+    }
+
+    /// Все подмножества из `count` номеров шардов среди `total`.
+    fn subsets(total: usize, count: usize) -> Vec<Vec<usize>> {
+        (0u32..1 << total)
+            .filter(|mask| mask.count_ones() as usize == count)
+            .map(|mask| (0..total).filter(|&i| mask >> i & 1 == 1).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_layout() {
+        let coder = ShardCoder::build(3, 2, FastGF256::new()).unwrap();
+        let shards = coder.split(&[1, 2, 3, 4, 5, 6, 7]);
+
+        assert_eq!(shards.len(), 5);
+        assert_eq!(&shards[..3], &[vec![1, 2, 3], vec![4, 5, 6], vec![7, 0, 0]]);
+        assert!(shards.iter().all(|shard| shard.len() == 3));
+
+        assert!(ShardCoder::build(0, 2, FastGF256::new()).is_err());
+        assert!(ShardCoder::build(200, 57, FastGF256::new()).is_err());
+        assert!(ShardCoder::build(200, 56, FastGF256::new()).is_ok());
+    }
+
+    #[test]
+    fn test_reconstruct_any_lost_shards() {
+        for matrix in [ShardMatrix::Cauchy, ShardMatrix::Vandermonde] {
+            let (k, m) = (4, 3);
+            let coder = ShardCoder::build(k, m, FastGF256::new()).unwrap().with_matrix(matrix);
+            let data = random_data(1001);
+            let shards = coder.split(&data);
+
+            for lost in (0..=m).flat_map(|count| subsets(k + m, count)) {
+                let mut damaged = shards.iter().cloned().map(Some).collect::<Vec<_>>();
+                for &i in &lost {
+                    damaged[i] = None;
+                }
+
+                assert_eq!(
+                    coder.join(damaged.clone(), data.len()).unwrap(),
+                    data,
+                    "{matrix:?}, lost: {lost:?}"
+                );
+                coder.reconstruct(&mut damaged).unwrap();
+                assert_eq!(
+                    damaged.into_iter().flatten().collect::<Vec<_>>(),
+                    shards,
+                    "lost: {lost:?}"
+                );
+            }
+
+            let mut damaged = shards.iter().cloned().map(Some).collect::<Vec<_>>();
+            for shard in &mut damaged[..m + 1] {
+                *shard = None;
+            }
+            assert!(coder.join(damaged, data.len()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_matrices_and_fields() {
+        let data = random_data(500);
+
+        for (k, m) in [(1, 0), (1, 4), (10, 4), (100, 50)] {
+            let fast = ShardCoder::build(k, m, FastGF256::new()).unwrap();
+            let simple = ShardCoder::build(k, m, SimpleGF256::new()).unwrap();
+            assert_eq!(fast.split(&data), simple.split(&data));
+
+            for matrix in [ShardMatrix::Cauchy, ShardMatrix::Vandermonde] {
+                let coder = ShardCoder::build(k, m, FastGF256::new()).unwrap().with_matrix(matrix);
+                let mut shards = coder.split(&data).into_iter().map(Some).collect::<Vec<_>>();
+
+                // Теряются первые m шардов: все шарды данных, если их не больше m
+                for shard in &mut shards[..m] {
+                    *shard = None;
+                }
+                assert_eq!(
+                    coder.join(shards, data.len()).unwrap(),
+                    data,
+                    "k: {k}, m: {m}, {matrix:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_shards() {
+        let coder = ShardCoder::build(2, 1, FastGF256::new()).unwrap();
+        let shards = coder.split(&[1, 2, 3, 4]);
+
+        assert!(coder.join(vec![Some(shards[0].clone()), None], 4).is_err());
+        assert!(
+            coder
+                .join(vec![Some(vec![1]), None, Some(shards[2].clone())], 4)
+                .is_err()
+        );
+        assert!(coder.join(shards.iter().cloned().map(Some).collect(), 5).is_err());
+        assert!(coder.join(vec![None, None, None], 0).is_err());
+
+        let empty = coder.split(&[]);
+        assert_eq!(
+            coder
+                .join(vec![None, Some(empty[1].clone()), Some(empty[2].clone())], 0)
+                .unwrap(),
+            Vec::<u8>::new()
+        );
+    }
+}
@@ -1,7 +1,19 @@
-use anyhow::Result;
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 
-use reed_solomon::{BlockCoder, Coder, new_reed_solomon};
+use reed_solomon::{BlockCoder, Coder, new_reed_solomon, shard::ShardMatrix};
+
+mod shard;
+mod stream;
+
+use shard::{join_files, split_file};
+use stream::{FrameHeader, decode_stream, encode_stream};
 
 const MAX_BLOCK_SIZE: usize = 255;
 
@@ -19,36 +31,128 @@ fn main() -> Result<()> {
 
     match cli.command {
         Command::Encode {
-            input,
+            data,
+            files,
+            block_size,
             input_format,
             output_format,
         } => {
-            let data = input_format.parse_input_data(&input)?;
-            let block_size = MAX_BLOCK_SIZE - cli.controls;
+            // Без заголовка потока декодер не узнает размер блока и делит данные на полные кодовые слова
+            anyhow::ensure!(
+                block_size.is_none() || files.is_streaming(),
+                "--block-size поддерживается только вместе с --input или --output"
+            );
+            let block_size = block_size.unwrap_or(MAX_BLOCK_SIZE - cli.controls);
+            anyhow::ensure!(
+                block_size > 0 && block_size + cli.controls <= MAX_BLOCK_SIZE,
+                "Размер блока должен быть от 1 до {}",
+                MAX_BLOCK_SIZE - cli.controls
+            );
+
+            if files.is_streaming() {
+                let (mut input, original_len) = match (&files.input, data) {
+                    (Some(path), _) => open_input(path)?,
+                    (None, Some(data)) => {
+                        let data = input_format.parse_input_data(&data)?;
+                        let len = data.len() as u64;
+                        (Box::new(io::Cursor::new(data)) as Box<dyn Read>, Some(len))
+                    }
+                    (None, None) => anyhow::bail!("Нет входных данных: укажите строку или --input"),
+                };
+
+                let header = FrameHeader::build(cli.controls, block_size, original_len)?;
+                encode_stream(header, &mut input, &mut open_output(files.output.as_deref())?)?;
+                return Ok(());
+            }
 
+            let data = data.context("Нет входных данных: укажите строку или --input")?;
+            let data = input_format.parse_input_data(&data)?;
             let encoded = rs.encode_blocks_to_vec(&data, block_size)?;
 
             println!("{}", output_format.parse(&encoded)?);
         }
         Command::Decode {
-            input,
+            data,
+            files,
             input_format: r#type,
             output_format,
         } => {
-            let data = r#type.parse_input_data(&input)?;
-            let block_size = MAX_BLOCK_SIZE; // полный блок: данные + контрольные
-
-            check_len(&data, block_size);
+            if files.is_streaming() {
+                let (mut input, _) = match &files.input {
+                    Some(path) => open_input(path)?,
+                    None => anyhow::bail!("Потоковое декодирование читает только --input"),
+                };
+                decode_stream(&mut input, &mut open_output(files.output.as_deref())?)?;
+                return Ok(());
+            }
 
-            let decoded = rs.decode_blocks_to_vec(&data, block_size)?;
+            let data = data.context("Нет входных данных: укажите строку или --input")?;
+            let data = r#type.parse_input_data(&data)?;
+            // Полный блок: данные + контрольные, последний блок может быть короче
+            let decoded = rs.decode_blocks_to_vec(&data, MAX_BLOCK_SIZE)?;
             let output = output_format.parse(&decoded)?;
 
             println!("{}", output);
         }
+        Command::Shard {
+            command:
+                ShardCommand::Split {
+                    input,
+                    data_shards,
+                    parity_shards,
+                    matrix,
+                    out_dir,
+                },
+        } => {
+            let out_dir = match out_dir {
+                Some(dir) => dir,
+                None => input.parent().map(Path::to_path_buf).unwrap_or_default(),
+            };
+            for path in split_file(&input, &out_dir, data_shards, parity_shards, matrix.into())? {
+                println!("{}", path.display());
+            }
+        }
+        Command::Shard {
+            command: ShardCommand::Join { shards, output },
+        } => {
+            let report = join_files(&shards)?;
+            for (path, error) in &report.skipped {
+                eprintln!("Пропущен {}: {error:#}", path.display());
+            }
+            if !report.restored.is_empty() {
+                eprintln!("Восстановлены шарды: {:?}", report.restored);
+            }
+
+            let mut output = open_output(output.as_deref())?;
+            output.write_all(&report.data)?;
+            output.flush()?;
+        }
     };
     Ok(())
 }
 
+/// Открывает файл или stdin для `-`. Возвращает также размер файла, если он известен.
+fn open_input(path: &Path) -> Result<(Box<dyn Read>, Option<u64>)> {
+    if path == Path::new("-") {
+        return Ok((Box::new(io::stdin().lock()), None));
+    }
+
+    let file = File::open(path).with_context(|| format!("Не удалось открыть {}", path.display()))?;
+    let len = file.metadata()?.len();
+    Ok((Box::new(BufReader::new(file)), Some(len)))
+}
+
+/// Открывает файл для записи или stdout, если путь не указан или равен `-`.
+fn open_output(path: Option<&Path>) -> Result<Box<dyn Write>> {
+    match path {
+        Some(path) if path != Path::new("-") => {
+            let file = File::create(path).with_context(|| format!("Не удалось создать {}", path.display()))?;
+            Ok(Box::new(BufWriter::new(file)))
+        }
+        _ => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
+    }
+}
+
 #[derive(Parser)]
 #[command(version, about = "Кодер/декодер Рида-Соломона", long_about = None, author = "laroxyss")]
 struct Cli {
@@ -66,7 +170,15 @@ enum Command {
     /// Закодировать данные
     Encode {
         /// Входные данные
-        input: String,
+        data: Option<String>,
+
+        #[command(flatten)]
+        files: Files,
+
+        /// Количество байт данных в блоке, по умолчанию 255 - controls. Только для потокового режима:
+        /// размер блока записывается в заголовок потока
+        #[arg(long)]
+        block_size: Option<usize>,
 
         /// Тип входных данных
         #[arg(long, default_value = "auto")]
@@ -80,7 +192,10 @@ enum Command {
     /// Декодировать данные
     Decode {
         /// Входные данные
-        input: String,
+        data: Option<String>,
+
+        #[command(flatten)]
+        files: Files,
 
         /// Тип входных данных
         #[arg(long, default_value = "auto")]
@@ -90,6 +205,86 @@ enum Command {
         #[arg(long, default_value = "auto")]
         output_format: DataFormat,
     },
+
+    /// Хранение файла в шардах с кодированием стираний: файл восстанавливается по любым k шардам из k + m
+    Shard {
+        #[command(subcommand)]
+        command: ShardCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ShardCommand {
+    /// Разделить файл на k шардов данных и m шардов четности
+    Split {
+        /// Исходный файл
+        input: PathBuf,
+
+        /// Количество шардов данных k
+        #[arg(short = 'k', long, default_value = "4")]
+        data_shards: usize,
+
+        /// Количество шардов четности m: столько шардов можно потерять
+        #[arg(short = 'm', long, default_value = "2")]
+        parity_shards: usize,
+
+        /// Матрица кодирования
+        #[arg(long, default_value = "cauchy")]
+        matrix: MatrixKind,
+
+        /// Каталог для шардов, по умолчанию каталог исходного файла
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+
+    /// Собрать файл из шардов, восстановив потерянные и поврежденные
+    Join {
+        /// Файлы шардов
+        #[arg(required = true)]
+        shards: Vec<PathBuf>,
+
+        /// Выходной файл, `-` - stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Матрица кодирования шардов четности
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum MatrixKind {
+    /// Матрица Коши
+    Cauchy,
+
+    /// Матрица Вандермонда, приведенная к систематическому виду
+    Vandermonde,
+}
+
+impl From<MatrixKind> for ShardMatrix {
+    fn from(kind: MatrixKind) -> Self {
+        match kind {
+            MatrixKind::Cauchy => ShardMatrix::Cauchy,
+            MatrixKind::Vandermonde => ShardMatrix::Vandermonde,
+        }
+    }
+}
+
+/// Файлы для потокового режима: данные кодируются блоками с заголовком, хранящим количество контрольных
+/// символов, размер блока и длину исходных данных
+#[derive(clap::Args)]
+struct Files {
+    /// Входной файл, `-` - stdin
+    #[arg(short, long, conflicts_with = "data")]
+    input: Option<PathBuf>,
+
+    /// Выходной файл, `-` - stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl Files {
+    fn is_streaming(&self) -> bool {
+        self.input.is_some() || self.output.is_some()
+    }
 }
 
 /// Выходной формат — только для бинарных данных
@@ -249,16 +444,6 @@ impl DataFormat {
     }
 }
 
-fn check_len(data: &[u8], block_size: usize) {
-    if data.len() > block_size && data.len() % block_size != 0 {
-        eprint!(
-            "Длина входных данных ({}) не кратна размеру блока ({})",
-            data.len(),
-            block_size
-        );
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
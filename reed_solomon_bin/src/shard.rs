@@ -0,0 +1,378 @@
+//! Файлы шардов: заголовок с параметрами кодирования и контрольными суммами, затем содержимое шарда.
+//!
+//! Шард с поврежденным заголовком или содержимым считается потерянным и восстанавливается по остальным,
+//! поэтому контрольная сумма превращает незаметную порчу файла в стирание. Контрольная сумма исходного
+//! файла в заголовке отличает шарды разных файлов и проверяет собранный результат.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use reed_solomon::{
+    gf::FastGF256,
+    shard::{ShardCoder, ShardMatrix},
+};
+
+/// Сигнатура файла шарда.
+const MAGIC: &[u8; 4] = b"RSS2";
+
+/// Длина заголовка: сигнатура, матрица, k, m, номер шарда, длина данных, длина шарда, CRC-32 исходного
+/// файла, CRC-32 содержимого и CRC-32 заголовка.
+const HEADER_LEN: usize = MAGIC.len() + 4 + 8 + 8 + 4 + 4 + 4;
+
+/// Заголовок файла шарда. Одинаков у всех шардов файла, кроме номера и контрольной суммы содержимого.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardHeader {
+    pub matrix: ShardMatrix,
+    pub data_shards: u8,
+    pub parity_shards: u8,
+    /// Номер шарда: сначала шарды данных, затем четности.
+    pub index: u8,
+    /// Длина исходного файла.
+    pub original_len: u64,
+    pub shard_len: u64,
+    /// CRC-32 исходного файла. Отличает шарды разных файлов с одинаковыми параметрами.
+    pub data_checksum: u32,
+    /// CRC-32 содержимого шарда.
+    pub checksum: u32,
+}
+
+impl ShardHeader {
+    fn to_bytes(self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.push(match self.matrix {
+            ShardMatrix::Cauchy => 0,
+            ShardMatrix::Vandermonde => 1,
+        });
+        header.extend([self.data_shards, self.parity_shards, self.index]);
+        header.extend_from_slice(&self.original_len.to_le_bytes());
+        header.extend_from_slice(&self.shard_len.to_le_bytes());
+        header.extend_from_slice(&self.data_checksum.to_le_bytes());
+        header.extend_from_slice(&self.checksum.to_le_bytes());
+        header.extend_from_slice(&crc32(&header).to_le_bytes());
+        header
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        anyhow::ensure!(bytes.len() >= HEADER_LEN, "Файл короче заголовка шарда");
+        anyhow::ensure!(
+            &bytes[..MAGIC.len()] == MAGIC,
+            "Неизвестный формат: нет сигнатуры {MAGIC:?}"
+        );
+
+        let (header, header_checksum) = bytes[..HEADER_LEN].split_at(HEADER_LEN - 4);
+        anyhow::ensure!(
+            crc32(header).to_le_bytes() == header_checksum,
+            "Заголовок шарда поврежден"
+        );
+
+        let field = |offset: usize| u64::from_le_bytes(header[offset..offset + 8].try_into().unwrap());
+        let checksum = |offset: usize| u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap());
+        let matrix = match header[4] {
+            0 => ShardMatrix::Cauchy,
+            1 => ShardMatrix::Vandermonde,
+            other => anyhow::bail!("Неизвестная матрица кодирования: {other}"),
+        };
+
+        Ok(Self {
+            matrix,
+            data_shards: header[5],
+            parity_shards: header[6],
+            index: header[7],
+            original_len: field(8),
+            shard_len: field(16),
+            data_checksum: checksum(24),
+            checksum: checksum(28),
+        })
+    }
+
+    /// Совпадают ли параметры кодирования, общие для всех шардов файла.
+    fn same_frame(&self, other: &Self) -> bool {
+        (
+            self.matrix,
+            self.data_shards,
+            self.parity_shards,
+            self.original_len,
+            self.shard_len,
+            self.data_checksum,
+        ) == (
+            other.matrix,
+            other.data_shards,
+            other.parity_shards,
+            other.original_len,
+            other.shard_len,
+            other.data_checksum,
+        )
+    }
+}
+
+/// CRC-32 (IEEE 802.3, полином 0xEDB88320 в отраженной форме), как в zip и gzip.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Путь к шарду `index` файла `input` в каталоге `out_dir`: `<имя файла>.<index>.shard`.
+fn shard_path(input: &Path, out_dir: &Path, index: usize) -> Result<PathBuf> {
+    let name = input
+        .file_name()
+        .with_context(|| format!("Не удалось определить имя файла {}", input.display()))?;
+    Ok(out_dir.join(format!("{}.{index}.shard", name.to_string_lossy())))
+}
+
+/// Делит файл на `data_shards` шардов данных и `parity_shards` шардов четности и записывает их
+/// в каталог `out_dir`. Возвращает пути к записанным шардам.
+pub fn split_file(
+    input: &Path,
+    out_dir: &Path,
+    data_shards: usize,
+    parity_shards: usize,
+    matrix: ShardMatrix,
+) -> Result<Vec<PathBuf>> {
+    anyhow::ensure!(
+        (1..=255).contains(&data_shards) && parity_shards <= 255,
+        "Количество шардов данных должно быть от 1 до 255, шардов четности - не больше 255"
+    );
+    let coder = ShardCoder::build(data_shards, parity_shards, FastGF256::new())?.with_matrix(matrix);
+
+    let data = fs::read(input).with_context(|| format!("Не удалось прочитать {}", input.display()))?;
+    let shards = coder.split(&data);
+    let data_checksum = crc32(&data);
+
+    let mut paths = Vec::with_capacity(shards.len());
+    for (index, shard) in shards.iter().enumerate() {
+        let header = ShardHeader {
+            matrix,
+            data_shards: data_shards as u8,
+            parity_shards: parity_shards as u8,
+            index: index as u8,
+            original_len: data.len() as u64,
+            shard_len: shard.len() as u64,
+            data_checksum,
+            checksum: crc32(shard),
+        };
+
+        let path = shard_path(input, out_dir, index)?;
+        let mut contents = header.to_bytes();
+        contents.extend_from_slice(shard);
+        fs::write(&path, contents).with_context(|| format!("Не удалось записать {}", path.display()))?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Читает файл шарда и проверяет контрольные суммы.
+pub fn read_shard(path: &Path) -> Result<(ShardHeader, Vec<u8>)> {
+    let bytes = fs::read(path).with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+    let header = ShardHeader::from_bytes(&bytes)?;
+
+    let shard = &bytes[HEADER_LEN..];
+    anyhow::ensure!(
+        shard.len() as u64 == header.shard_len,
+        "Длина шарда {} не совпадает с заголовком: {}",
+        shard.len(),
+        header.shard_len
+    );
+    anyhow::ensure!(crc32(shard) == header.checksum, "Контрольная сумма шарда не совпадает");
+    Ok((header, shard.to_vec()))
+}
+
+/// Результат сборки файла из шардов.
+#[derive(Debug)]
+pub struct JoinReport {
+    pub data: Vec<u8>,
+    /// Файлы, пропущенные из-за повреждений, несовпадающих параметров или повторных номеров шардов.
+    pub skipped: Vec<(PathBuf, anyhow::Error)>,
+    /// Номера шардов, которые пришлось восстановить.
+    pub restored: Vec<usize>,
+}
+
+/// Собирает файл из шардов. Поврежденные файлы пропускаются, а недостающие шарды восстанавливаются,
+/// если сохранилось хотя бы `k` шардов. Параметры кодирования и контрольная сумма исходного файла
+/// берутся у большинства целых шардов, шарды других файлов и повторы одного номера пропускаются.
+/// Собранный файл проверяется по контрольной сумме.
+pub fn join_files(paths: &[PathBuf]) -> Result<JoinReport> {
+    let mut skipped = Vec::new();
+    let mut read = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        match read_shard(path) {
+            Ok((header, shard)) => read.push((path, header, shard)),
+            Err(e) => skipped.push((path.clone(), e)),
+        }
+    }
+
+    let frame = read
+        .iter()
+        .map(|(_, header, _)| header)
+        .max_by_key(|header| read.iter().filter(|(_, other, _)| header.same_frame(other)).count())
+        .copied()
+        .context("Нет ни одного целого шарда")?;
+
+    let mut shards = vec![None; frame.data_shards as usize + frame.parity_shards as usize];
+    for (path, header, shard) in read {
+        if !header.same_frame(&frame) {
+            skipped.push((path.clone(), anyhow::anyhow!("Шард относится к другому файлу")));
+            continue;
+        }
+
+        match shards.get_mut(header.index as usize) {
+            Some(slot @ None) => *slot = Some(shard),
+            Some(Some(_)) => skipped.push((
+                path.clone(),
+                anyhow::anyhow!("Шард {} уже прочитан из другого файла", header.index),
+            )),
+            None => skipped.push((
+                path.clone(),
+                anyhow::anyhow!("Номер шарда {} вне диапазона", header.index),
+            )),
+        }
+    }
+
+    let restored = (0..shards.len()).filter(|&i| shards[i].is_none()).collect();
+
+    let coder = ShardCoder::build(
+        frame.data_shards as usize,
+        frame.parity_shards as usize,
+        FastGF256::new(),
+    )?
+    .with_matrix(frame.matrix);
+    let data = coder
+        .join(shards, frame.original_len as usize)
+        .context("Не удалось восстановить файл")?;
+    anyhow::ensure!(
+        crc32(&data) == frame.data_checksum,
+        "Контрольная сумма собранного файла не совпадает"
+    );
+
+    Ok(JoinReport {
+        data,
+        skipped,
+        restored,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rs_shard_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_split_join() {
+        let dir = temp_dir("split_join");
+        let input = dir.join("data.bin");
+        let data = rand::random_iter().take(10_000).collect::<Vec<u8>>();
+        fs::write(&input, &data).unwrap();
+
+        for matrix in [ShardMatrix::Cauchy, ShardMatrix::Vandermonde] {
+            let paths = split_file(&input, &dir, 5, 3, matrix).unwrap();
+            assert_eq!(paths.len(), 8);
+            assert!(paths[7].ends_with("data.bin.7.shard"));
+
+            // Один шард удален, один поврежден, заголовок еще одного испорчен
+            let mut available = paths.clone();
+            available.remove(1);
+            let mut contents = fs::read(&paths[4]).unwrap();
+            contents[HEADER_LEN + 100] ^= 1;
+            fs::write(&paths[4], contents).unwrap();
+            let mut contents = fs::read(&paths[6]).unwrap();
+            contents[10] ^= 1;
+            fs::write(&paths[6], contents).unwrap();
+
+            let report = join_files(&available).unwrap();
+            assert_eq!(report.data, data);
+            assert_eq!(report.restored, vec![1, 4, 6]);
+            assert_eq!(report.skipped.len(), 2);
+
+            // Четвертый потерянный шард уже не восстановить
+            available.retain(|path| path != &paths[0]);
+            assert!(join_files(&available).is_err());
+        }
+
+        assert!(split_file(&input, &dir, 0, 3, ShardMatrix::Cauchy).is_err());
+        assert!(join_files(&[]).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_foreign_shards() {
+        let dir = temp_dir("foreign");
+        let (first, second) = (dir.join("first"), dir.join("second"));
+        fs::write(&first, b"first file").unwrap();
+        fs::write(&second, b"second, longer file").unwrap();
+
+        let mut paths = split_file(&first, &dir, 2, 1, ShardMatrix::Cauchy).unwrap();
+        let foreign = split_file(&second, &dir, 2, 1, ShardMatrix::Cauchy).unwrap();
+        paths[0] = foreign[0].clone();
+
+        let report = join_files(&paths).unwrap();
+        assert_eq!(report.data, b"first file");
+        assert_eq!(report.restored, vec![0]);
+        assert_eq!(report.skipped.len(), 1);
+
+        // Файл той же длины с теми же параметрами отличается только контрольной суммой
+        let same_len = dir.join("same_len");
+        fs::write(&same_len, b"other file").unwrap();
+        let foreign = split_file(&same_len, &dir, 2, 1, ShardMatrix::Cauchy).unwrap();
+        let mut paths = split_file(&first, &dir, 2, 1, ShardMatrix::Cauchy).unwrap();
+        paths[1] = foreign[1].clone();
+
+        let report = join_files(&paths).unwrap();
+        assert_eq!(report.data, b"first file");
+        assert_eq!(report.restored, vec![1]);
+        assert_eq!(report.skipped.len(), 1);
+
+        // Повтор одного шарда пропускается
+        let mut paths = split_file(&first, &dir, 2, 1, ShardMatrix::Cauchy).unwrap();
+        let copy = dir.join("copy.shard");
+        fs::copy(&paths[0], &copy).unwrap();
+        paths.push(copy);
+
+        let report = join_files(&paths).unwrap();
+        assert_eq!(report.data, b"first file");
+        assert_eq!(report.skipped.len(), 1);
+
+        // Шарды согласованы между собой, но собранный файл не совпадает с контрольной суммой
+        for path in &paths[..3] {
+            let contents = fs::read(path).unwrap();
+            let mut header = ShardHeader::from_bytes(&contents).unwrap();
+            header.data_checksum ^= 1;
+            let mut contents_with_header = header.to_bytes();
+            contents_with_header.extend_from_slice(&contents[HEADER_LEN..]);
+            fs::write(path, contents_with_header).unwrap();
+        }
+        assert!(join_files(&paths[..3]).is_err());
+
+        // Пустой файл: шарды нулевой длины
+        let empty = dir.join("empty");
+        fs::write(&empty, b"").unwrap();
+        let paths = split_file(&empty, &dir, 3, 2, ShardMatrix::Vandermonde).unwrap();
+        assert_eq!(join_files(&paths[2..]).unwrap().data, b"");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,253 @@
+//! Потоковое кодирование файлов: заголовок кадра и последовательность кодовых слов.
+//!
+//! Формат: заголовок [`FrameHeader`], защищенный собственными [`HEADER_CONTROLS`] контрольными символами,
+//! затем кодовые слова по `block_size + controls` байт. Последнее слово укорочено до остатка данных.
+//! В памяти одновременно находится только одно кодовое слово.
+
+use std::io::{self, Read, Write};
+
+use anyhow::{Context, Result};
+use reed_solomon::{Coder, DecodeBuffers, new_reed_solomon};
+
+/// Сигнатура кадра.
+const MAGIC: &[u8; 4] = b"RSF1";
+
+/// Количество контрольных символов заголовка: исправляет до 8 ошибок в нем.
+pub const HEADER_CONTROLS: usize = 16;
+
+/// Длина заголовка без контрольных символов: сигнатура, controls, block_size, длина исходных данных.
+const HEADER_LEN: usize = MAGIC.len() + 2 + 8;
+
+/// Длина исходных данных неизвестна при кодировании потока без размера, например stdin.
+const UNKNOWN_LEN: u64 = u64::MAX;
+
+/// Параметры, которые нужны декодеру для разбора кадра.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHeader {
+    /// Количество контрольных символов в кодовом слове.
+    pub controls: u8,
+    /// Количество байт данных в кодовом слове.
+    pub block_size: u8,
+    /// Длина исходных данных, если была известна при кодировании.
+    pub original_len: Option<u64>,
+}
+
+impl FrameHeader {
+    pub fn build(controls: usize, block_size: usize, original_len: Option<u64>) -> Result<Self> {
+        anyhow::ensure!(
+            controls > 0,
+            "Количество контрольных символов должно быть положительным"
+        );
+        anyhow::ensure!(block_size > 0, "Размер блока должен быть положительным");
+        anyhow::ensure!(
+            controls + block_size <= 255,
+            "Кодовое слово длиннее 255 байт: {block_size} + {controls}"
+        );
+
+        Ok(Self {
+            controls: controls as u8,
+            block_size: block_size as u8,
+            original_len,
+        })
+    }
+
+    /// Длина кодового слова: данные и контрольные символы.
+    pub fn codeword_len(&self) -> usize {
+        self.block_size as usize + self.controls as usize
+    }
+
+    fn write(&self, output: &mut impl Write) -> Result<()> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.push(self.controls);
+        header.push(self.block_size);
+        header.extend_from_slice(&self.original_len.unwrap_or(UNKNOWN_LEN).to_le_bytes());
+
+        let encoded = new_reed_solomon(HEADER_CONTROLS).encode(&header)?;
+        output.write_all(&encoded).context("Не удалось записать заголовок")
+    }
+
+    fn read(input: &mut impl Read) -> Result<Self> {
+        let mut encoded = [0; HEADER_LEN + HEADER_CONTROLS];
+        input
+            .read_exact(&mut encoded)
+            .context("Не удалось прочитать заголовок")?;
+
+        let header = new_reed_solomon(HEADER_CONTROLS)
+            .decode(&encoded)
+            .context("Заголовок поврежден")?;
+        anyhow::ensure!(
+            &header[..MAGIC.len()] == MAGIC,
+            "Неизвестный формат: нет сигнатуры {MAGIC:?}"
+        );
+
+        let original_len = u64::from_le_bytes(header[MAGIC.len() + 2..].try_into()?);
+        Self::build(
+            header[MAGIC.len()] as usize,
+            header[MAGIC.len() + 1] as usize,
+            (original_len != UNKNOWN_LEN).then_some(original_len),
+        )
+    }
+}
+
+/// Читает до `buf.len()` байт, пока не закончится поток. Возвращает количество прочитанных байт.
+fn read_block(input: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Кодирует поток блоками по `header.block_size` байт. Возвращает количество байт исходных данных.
+pub fn encode_stream(header: FrameHeader, input: &mut impl Read, output: &mut impl Write) -> Result<u64> {
+    let rs = new_reed_solomon(header.controls as usize);
+    header.write(output)?;
+
+    let mut block = vec![0; header.block_size as usize];
+    let mut codeword = Vec::with_capacity(header.codeword_len());
+    let mut total = 0;
+
+    loop {
+        let len = read_block(input, &mut block).context("Не удалось прочитать входные данные")?;
+        if len == 0 {
+            break;
+        }
+        total += len as u64;
+        rs.encode_symbols_into(&block[..len], &mut codeword)?;
+        output.write_all(&codeword)?;
+
+        if len < block.len() {
+            break;
+        }
+    }
+
+    if let Some(expected) = header.original_len {
+        anyhow::ensure!(
+            total == expected,
+            "Размер входных данных изменился при чтении: {total} вместо {expected}"
+        );
+    }
+    output.flush()?;
+    Ok(total)
+}
+
+/// Декодирует поток, закодированный [`encode_stream`]. Возвращает заголовок кадра.
+pub fn decode_stream(input: &mut impl Read, output: &mut impl Write) -> Result<FrameHeader> {
+    let header = FrameHeader::read(input)?;
+    let rs = new_reed_solomon(header.controls as usize);
+
+    let mut codeword = vec![0; header.codeword_len()];
+    let mut buffers = DecodeBuffers::new();
+    let mut decoded = Vec::with_capacity(header.codeword_len());
+    let mut total = 0;
+
+    for index in 0.. {
+        let len = read_block(input, &mut codeword).context("Не удалось прочитать входные данные")?;
+        if len == 0 {
+            break;
+        }
+        anyhow::ensure!(
+            len > header.controls as usize,
+            "Последний блок ({len} байт) не длиннее количества контрольных символов ({})",
+            header.controls
+        );
+
+        rs.decode_symbols_into(&codeword[..len], &[], &mut buffers, &mut decoded)
+            .with_context(|| format!("Не удалось исправить блок {index}"))?;
+        total += decoded.len() as u64;
+        output.write_all(&decoded)?;
+
+        if len < codeword.len() {
+            break;
+        }
+    }
+
+    if let Some(expected) = header.original_len {
+        anyhow::ensure!(
+            total == expected,
+            "Данные обрезаны: декодировано {total} байт из {expected}"
+        );
+    }
+    output.flush()?;
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(data: &[u8], controls: usize, block_size: usize, known_len: bool) -> Vec<u8> {
+        let header = FrameHeader::build(controls, block_size, known_len.then_some(data.len() as u64)).unwrap();
+        let mut encoded = Vec::new();
+        encode_stream(header, &mut &data[..], &mut encoded).unwrap();
+        encoded
+    }
+
+    fn decode(encoded: &[u8]) -> Result<Vec<u8>> {
+        let mut decoded = Vec::new();
+        decode_stream(&mut &encoded[..], &mut decoded)?;
+        Ok(decoded)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for len in [0, 1, 9, 10, 11, 245, 1000, 10_000] {
+            let data = rand::random_iter().take(len).collect::<Vec<u8>>();
+
+            for known_len in [true, false] {
+                let encoded = encode(&data, 10, 245, known_len);
+                let codewords = len.div_ceil(245);
+                assert_eq!(encoded.len(), HEADER_LEN + HEADER_CONTROLS + len + 10 * codewords);
+                assert_eq!(decode(&encoded).unwrap(), data, "len: {len}");
+            }
+
+            assert_eq!(decode(&encode(&data, 4, 10, true)).unwrap(), data, "len: {len}");
+        }
+    }
+
+    #[test]
+    fn test_corrected_errors() {
+        let data = rand::random_iter().take(3000).collect::<Vec<u8>>();
+        let mut encoded = encode(&data, 8, 100, true);
+        let header_len = HEADER_LEN + HEADER_CONTROLS;
+
+        // 8 ошибок в заголовке и по 4 в каждом кодовом слове, включая последнее укороченное
+        for i in 0..8 {
+            encoded[3 * i] ^= 0xFF;
+        }
+        for start in (header_len..encoded.len()).step_by(108) {
+            for i in 0..4 {
+                if let Some(byte) = encoded.get_mut(start + 2 * i) {
+                    *byte ^= 0x5A;
+                }
+            }
+        }
+
+        assert_eq!(decode(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_invalid_frames() {
+        let data = vec![7; 500];
+        let encoded = encode(&data, 10, 100, true);
+
+        // Обрезано по границе кодового слова: длина из заголовка не совпадает
+        let truncated = &encoded[..HEADER_LEN + HEADER_CONTROLS + 110];
+        assert!(decode(truncated).is_err());
+
+        // Последний блок короче контрольных символов
+        assert!(decode(&encoded[..encoded.len() - 100]).is_err());
+
+        assert!(decode(&encoded[..10]).is_err());
+        assert!(decode(&[0; 100]).is_err());
+
+        assert!(FrameHeader::build(0, 10, None).is_err());
+        assert!(FrameHeader::build(10, 246, None).is_err());
+    }
+}